@@ -0,0 +1,152 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Measures the Rust-side overhead of creating dialogs and strings, and audits how many
+//! allocations each of them makes. The mock backend is installed, so no native dialog is ever
+//! created and the numbers only cover this crate's own work.
+//!
+//! Run with `cargo bench --features mock`. The allocation audit is printed before the timings;
+//! the unit tests check the allocations that must not regress.
+
+use criterion::{criterion_group, Criterion};
+use nvdialog_rs::{
+    c_string, try_c_string, DialogBox, DialogType, DynamicString, FileDialog, FileDialogType,
+    MockBackend, QuestionDialog, QuestionDialogButtons,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The system allocator, counting every allocation made through it.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the average number of allocations `f` makes per call.
+fn allocations_per_call<R>(mut f: impl FnMut() -> R) -> f64 {
+    const CALLS: usize = 1000;
+    /* Warm up thread-local buffers, so they don't count against the first call. */
+    black_box(f());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..CALLS {
+        black_box(f());
+    }
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / CALLS as f64
+}
+
+fn new_dialog_box() -> Result<DialogBox, nvdialog_rs::Error> {
+    DialogBox::new("Error", "Something went wrong.", DialogType::Error)
+}
+
+fn new_question() -> QuestionDialog {
+    QuestionDialog::new(
+        "Error",
+        "Something went wrong. Retry?",
+        QuestionDialogButtons::YesNo,
+    )
+}
+
+fn new_file_dialog() -> FileDialog {
+    FileDialog::new(
+        "Open image",
+        FileDialogType::OpenFile,
+        Some(["png", "jpg", "webp"]),
+    )
+}
+
+/// Hands a string to NvDialog and reads it back, as input boxes do.
+fn dynamic_string_round_trip() -> String {
+    let string = DynamicString::new("Report.odt").expect("Failed to create dynamic string");
+    String::from(string.clone().as_str())
+}
+
+fn allocation_audit() {
+    println!("Allocations per call:");
+    for (name, allocations) in [
+        ("c_string!", allocations_per_call(|| c_string!("Error"))),
+        (
+            "try_c_string!",
+            allocations_per_call(|| try_c_string!("Error")),
+        ),
+        ("DialogBox::new", allocations_per_call(new_dialog_box)),
+        ("QuestionDialog::new", allocations_per_call(new_question)),
+        ("FileDialog::new", allocations_per_call(new_file_dialog)),
+        (
+            "DynamicString round trip",
+            allocations_per_call(dynamic_string_round_trip),
+        ),
+    ] {
+        println!("  {name:<26}{allocations:>6.2}");
+    }
+    println!();
+}
+
+fn strings(c: &mut Criterion) {
+    c.bench_function("c_string!", |b| b.iter(|| c_string!(black_box("Error"))));
+    c.bench_function("try_c_string!", |b| {
+        b.iter(|| try_c_string!(black_box("Error")))
+    });
+}
+
+fn dynamic_strings(c: &mut Criterion) {
+    c.bench_function("DynamicString round trip", |b| {
+        b.iter(dynamic_string_round_trip)
+    });
+}
+
+fn constructors(c: &mut Criterion) {
+    c.bench_function("DialogBox::new", |b| b.iter(new_dialog_box));
+    c.bench_function("QuestionDialog::new", |b| b.iter(new_question));
+    c.bench_function("FileDialog::new", |b| b.iter(new_file_dialog));
+}
+
+criterion_group!(benches, strings, dynamic_strings, constructors);
+
+fn main() {
+    let _mock = MockBackend::install();
+    allocation_audit();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
@@ -0,0 +1,42 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+extern crate nvdialog_rs;
+use nvdialog_rs::DialogBox;
+use nvdialog_rs::DialogType;
+
+/* Run this from a session where `org.gnome.Settings.desktop` is installed: the dialog should show
+ * the Settings icon and be grouped with it in the dock or task switcher. */
+fn main() {
+    nvdialog_rs::set_app_id("org.gnome.Settings").expect("Invalid application ID");
+    nvdialog_rs::init().expect("Can't initialize NvDialog!");
+
+    let mut dialog_box = DialogBox::new(
+        "Application ID",
+        "This dialog should use the icon of the application whose ID was set.",
+        DialogType::Simple,
+    )
+    .expect("Error");
+    dialog_box.show()
+}
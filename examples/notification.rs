@@ -32,5 +32,7 @@ fn main() {
         NotificationKind::Simple,
     )
     .expect("Unable to construct notification");
-    notification.send();
+    notification
+        .send()
+        .expect("Failed to send notification");
 }
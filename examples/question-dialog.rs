@@ -37,5 +37,6 @@ fn main() {
         nvdialog_rs::Reply::Accepted => println!("Yes selected."),
         nvdialog_rs::Reply::Cancelled => println!("Cancel selected."),
         nvdialog_rs::Reply::Rejected => println!("No selected."),
+        nvdialog_rs::Reply::Dismissed => println!("Dialog closed without an answer."),
     }
 }
@@ -0,0 +1,258 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Procedural macros for `nvdialog-rs`. Use them through the `macros` feature of `nvdialog-rs`
+//! rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr, Variant};
+
+/// Turns an enum of application prompts into a catalog of dialogs.
+///
+/// Each variant is annotated with `#[dialog(...)]`, taking:
+/// - `title` and `message`: the text of the dialog. The message can refer to the variant's named
+///   fields like a `format!` string (`"Could not open {path}"`).
+/// - `kind`: `"simple"` (the default), `"warning"` or `"error"`, for a message box.
+/// - `buttons`: `"yes"`, `"yes_no"` or `"yes_no_cancel"`, for a question instead.
+///
+/// For every variant, the enum gets a `show_<variant>` function in snake case, taking the
+/// variant's fields as arguments: message boxes return `Result<(), nvdialog_rs::Error>`, and
+/// questions return the `nvdialog_rs::Reply`. `title` and `message` return the text of a prompt,
+/// and the `CATALOG` constant lists every prompt with its untranslated text, for review and
+/// translation.
+///
+/// See the documentation of `nvdialog_rs::DialogCatalog` for an example.
+#[proc_macro_derive(DialogCatalog, attributes(dialog))]
+pub fn derive_dialog_catalog(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// What a variant is shown as.
+enum Shape {
+    Message(&'static str),
+    Question(&'static str),
+}
+
+/// The contents of a variant's `#[dialog(...)]` attribute.
+struct Prompt {
+    title: LitStr,
+    message: LitStr,
+    shape: Shape,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "DialogCatalog can only be derived for enums",
+        ));
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut titles = Vec::new();
+    let mut messages = Vec::new();
+    let mut entries = Vec::new();
+    let mut functions = Vec::new();
+    for variant in &data.variants {
+        let prompt = parse_prompt(variant)?;
+        let ident = &variant.ident;
+        let fields: Vec<_> = match &variant.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "DialogCatalog variants must be unit variants or have named fields",
+                ))
+            }
+        };
+        let field_names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+        let field_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+        let (pattern, any) = if fields.is_empty() {
+            (quote!(Self::#ident), quote!(Self::#ident))
+        } else {
+            (
+                quote!(Self::#ident { #(#field_names),* }),
+                quote!(Self::#ident { .. }),
+            )
+        };
+
+        let (title, message) = (&prompt.title, &prompt.message);
+        titles.push(quote!(#any => #title,));
+        messages.push(quote!(#pattern => ::std::format!(#message),));
+        let variant_name = ident.to_string();
+        entries.push(quote! {
+            ::nvdialog_rs::CatalogEntry {
+                name: #variant_name,
+                title: #title,
+                message: #message,
+            },
+        });
+
+        let function = format_ident!("show_{}", snake_case(&variant_name));
+        let doc = format!("Shows the [`{name}::{variant_name}`] prompt.");
+        functions.push(match prompt.shape {
+            Shape::Message(kind) => {
+                let kind = format_ident!("{kind}");
+                quote! {
+                    #[doc = #doc]
+                    pub fn #function(#(#field_names: #field_types),*)
+                        -> ::std::result::Result<(), ::nvdialog_rs::Error>
+                    {
+                        let prompt = #pattern;
+                        let mut dialog = ::nvdialog_rs::DialogBox::new(
+                            prompt.title(),
+                            prompt.message().as_str(),
+                            ::nvdialog_rs::DialogType::#kind,
+                        )?;
+                        dialog.show();
+                        ::std::result::Result::Ok(())
+                    }
+                }
+            }
+            Shape::Question(buttons) => {
+                let buttons = format_ident!("{buttons}");
+                quote! {
+                    #[doc = #doc]
+                    pub fn #function(#(#field_names: #field_types),*) -> ::nvdialog_rs::Reply {
+                        let prompt = #pattern;
+                        ::nvdialog_rs::QuestionDialog::new(
+                            prompt.title(),
+                            prompt.message().as_str(),
+                            ::nvdialog_rs::QuestionDialogButtons::#buttons,
+                        )
+                        .get_reply()
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Every prompt of the catalog, with its untranslated text.
+            pub const CATALOG: &'static [::nvdialog_rs::CatalogEntry] = &[#(#entries)*];
+
+            /// Returns the title of the prompt.
+            pub fn title(&self) -> &'static str {
+                match self {
+                    #(#titles)*
+                }
+            }
+
+            /// Returns the message of the prompt, with the prompt's fields filled in.
+            #[allow(unused_variables)]
+            pub fn message(&self) -> ::std::string::String {
+                match self {
+                    #(#messages)*
+                }
+            }
+
+            #(#functions)*
+        }
+    })
+}
+
+fn parse_prompt(variant: &Variant) -> syn::Result<Prompt> {
+    let Some(attribute) = variant
+        .attrs
+        .iter()
+        .find(|attribute| attribute.path().is_ident("dialog"))
+    else {
+        return Err(Error::new_spanned(
+            variant,
+            "missing #[dialog(title = \"...\", message = \"...\")] attribute",
+        ));
+    };
+
+    let (mut title, mut message, mut shape) = (None, None, None);
+    attribute.parse_nested_meta(|meta| {
+        let value: LitStr = meta.value()?.parse()?;
+        let key = meta
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        match key.as_str() {
+            "title" => title = Some(value),
+            "message" => message = Some(value),
+            "kind" | "buttons" if shape.is_some() => {
+                return Err(meta.error("only one of `kind` and `buttons` can be given"))
+            }
+            "kind" => {
+                shape = Some(Shape::Message(match value.value().as_str() {
+                    "simple" => "Simple",
+                    "warning" => "Warning",
+                    "error" => "Error",
+                    _ => {
+                        return Err(Error::new_spanned(
+                            value,
+                            "expected \"simple\", \"warning\" or \"error\"",
+                        ))
+                    }
+                }))
+            }
+            "buttons" => {
+                shape = Some(Shape::Question(match value.value().as_str() {
+                    "yes" => "Yes",
+                    "yes_no" => "YesNo",
+                    "yes_no_cancel" => "YesNoCancel",
+                    _ => {
+                        return Err(Error::new_spanned(
+                            value,
+                            "expected \"yes\", \"yes_no\" or \"yes_no_cancel\"",
+                        ))
+                    }
+                }))
+            }
+            _ => return Err(meta.error("expected `title`, `message`, `kind` or `buttons`")),
+        }
+        Ok(())
+    })?;
+
+    let missing = |key| Error::new_spanned(attribute, format!("missing `{key}` in #[dialog]"));
+    Ok(Prompt {
+        title: title.ok_or_else(|| missing("title"))?,
+        message: message.ok_or_else(|| missing("message"))?,
+        shape: shape.unwrap_or(Shape::Message("Simple")),
+    })
+}
+
+/// Converts a `CamelCase` variant name to `snake_case`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
@@ -7,9 +7,29 @@ use cmake::Config;
 
 fn main() {
     println!("cargo:rerun-if-changed=nvdialog.h");
+    println!("cargo:rerun-if-env-changed=NVDIALOG_BUILD_CACHE_DIR");
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if !SUPPORTED_TARGETS.contains(&target_os.as_str()) {
+        eprintln!(
+            "error: NvDialog has no backend for the `{target_os}` target OS, so nvdialog-sys can't \
+             be built for it. Supported target OSes are: {}.",
+            SUPPORTED_TARGETS.join(", ")
+        );
+        std::process::exit(1);
+    }
 
     let bindings = bindgen::Builder::default()
         .header("nvdialog/include/nvdialog.h")
+        /* Only NvDialog's own API is exposed. Anything else the header pulls in (GTK, GLib,
+         * Win32) stays out of the bindings, and whatever NvDialog's types still refer to is
+         * opaque so that no toolkit struct layout is baked into them. */
+        .allowlist_function("nvd_.*")
+        .allowlist_type("Nvd.*")
+        .allowlist_var("NVD_.*")
+        .opaque_type("_?Gtk.*")
+        .opaque_type("_?Gdk.*")
+        .opaque_type("_?G[A-Z].*")
         .generate()
         .expect("Unable to generate bindings");
 
@@ -17,8 +37,12 @@ fn main() {
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
+    std::fs::write(out_path.join("version.rs"), native_version())
+        .expect("Couldn't write the NvDialog version!");
+    detect_optional_apis();
 
-    let dst = Config::new("./nvdialog")
+    let mut config = Config::new("./nvdialog");
+    config
         .build_target("nvdialog")
         .define("NVD_BUILD_STATIC", "ON")
         .define("NVDIALOG_MAXBUF", "256")
@@ -30,9 +54,170 @@ fn main() {
             } else {
                 "Release"
             },
-        )
-        .build();
+        );
+    /* `cmake --build` reads this as its default `--parallel` level. */
+    config.env("CMAKE_BUILD_PARALLEL_LEVEL", build_jobs());
+    if let Some(cache_dir) = env::var_os("NVDIALOG_BUILD_CACHE_DIR") {
+        /* Shared between target directories, so libnvdialog is only rebuilt when it changed.
+         * Each target and profile gets its own build tree. */
+        let profile = if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        };
+        let target = env::var("TARGET").unwrap_or_default();
+        config.out_dir(PathBuf::from(cache_dir).join(format!("{target}-{profile}")));
+    }
+    let prefixes = bsd_prefixes(&target_os);
+    if !prefixes.is_empty() {
+        /* GTK comes from ports or pkgsrc there, which CMake doesn't search by default. */
+        config.define("CMAKE_PREFIX_PATH", prefixes.join(";"));
+    }
+    let dst = config.build();
 
     println!("cargo:rustc-link-search=native={}/build/", dst.display());
     println!("cargo:rustc-link-lib=nvdialog");
+
+    if target_os == "linux" || !prefixes.is_empty() {
+        link_gtk(&prefixes);
+    }
+}
+
+/// The target OSes NvDialog has a backend for.
+const SUPPORTED_TARGETS: &[&str] = &[
+    "linux",
+    "windows",
+    "macos",
+    "freebsd",
+    "dragonfly",
+    "openbsd",
+    "netbsd",
+];
+
+/// Reads the version of the vendored NvDialog from its header, formatted as a Rust tuple.
+fn native_version() -> String {
+    let header =
+        std::fs::read_to_string("nvdialog/include/nvdialog.h").expect("Couldn't read nvdialog.h");
+    let component = |name: &str| {
+        header
+            .lines()
+            .find_map(|line| {
+                let mut words = line.split_whitespace();
+                (words.next() == Some("#define") && words.next() == Some(name))
+                    .then(|| words.next()?.parse::<u32>().ok())
+                    .flatten()
+            })
+            .unwrap_or_else(|| panic!("{name} is missing from nvdialog.h"))
+    };
+    format!(
+        "({}, {}, {})",
+        component("NVDIALOG_VERSION_MAJOR"),
+        component("NVDIALOG_VERSION_MINOR"),
+        component("NVDIALOG_VERSION_PATCH")
+    )
+}
+
+/// NvDialog APIs that older releases don't have, with the functions making up each of them.
+const OPTIONAL_APIS: &[(&str, &[&str])] = &[
+    (
+        "dynamic_string",
+        &[
+            "nvd_string_new",
+            "nvd_duplicate_string",
+            "nvd_string_to_cstr",
+            "nvd_delete_string",
+        ],
+    ),
+    (
+        "input_box",
+        &[
+            "nvd_input_box_new",
+            "nvd_show_input_box",
+            "nvd_input_box_get_string",
+        ],
+    ),
+    ("get_version", &["nvd_get_version"]),
+];
+
+/// Sets `nvd_has_<api>` for each of [`OPTIONAL_APIS`] whose functions are all declared in the
+/// headers. Missing ones are stubbed out in `src/compat.rs` so that the bindings keep the same
+/// shape.
+fn detect_optional_apis() {
+    let mut headers = String::new();
+    let mut dirs = vec![PathBuf::from("nvdialog/include")];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir).expect("Couldn't read NvDialog's headers") {
+            let path = entry.expect("Couldn't read NvDialog's headers").path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "h") {
+                headers += &std::fs::read_to_string(&path).unwrap_or_default();
+            }
+        }
+    }
+
+    let declared = |function: &str| {
+        headers.match_indices(function).any(|(index, _)| {
+            let before = headers[..index].chars().next_back();
+            let after = headers[index + function.len()..].trim_start();
+            !before.is_some_and(|c| c.is_alphanumeric() || c == '_') && after.starts_with('(')
+        })
+    };
+    for (api, functions) in OPTIONAL_APIS {
+        println!("cargo:rustc-check-cfg=cfg(nvd_has_{api})");
+        if functions.iter().all(|function| declared(function)) {
+            println!("cargo:rustc-cfg=nvd_has_{api}");
+        }
+    }
+}
+
+/// Returns how many jobs the native build may run at once: Cargo's `NUM_JOBS`, or the number of
+/// CPUs when it isn't set.
+fn build_jobs() -> String {
+    env::var("NUM_JOBS").unwrap_or_else(|_| {
+        std::thread::available_parallelism()
+            .map_or(1, |jobs| jobs.get())
+            .to_string()
+    })
+}
+
+/// Returns where third-party packages, GTK included, are installed on the BSDs, or nothing for
+/// other targets.
+fn bsd_prefixes(target_os: &str) -> &'static [&'static str] {
+    match target_os {
+        "freebsd" | "dragonfly" => &["/usr/local"],
+        "openbsd" => &["/usr/local", "/usr/X11R6"],
+        "netbsd" => &["/usr/pkg", "/usr/X11R7"],
+        _ => &[],
+    }
+}
+
+/// Links GTK the way the enabled features ask for, on Linux and the BSDs. `no-gtk-link` takes precedence over
+/// `gtk-runtime`, which takes precedence over `gtk-pkg-config`.
+fn link_gtk(prefixes: &[&str]) {
+    if env::var_os("CARGO_FEATURE_NO_GTK_LINK").is_some() {
+        return;
+    }
+
+    if env::var_os("CARGO_FEATURE_GTK_RUNTIME").is_some() {
+        /* No build-time lookup: the libraries are found by the dynamic loader when the program
+         * starts, wherever the system or runtime (eg. Flatpak) provides them. */
+        for prefix in prefixes {
+            println!("cargo:rustc-link-search=native={prefix}/lib");
+        }
+        for lib in ["gtk-3", "gdk-3", "gobject-2.0", "glib-2.0"] {
+            println!("cargo:rustc-link-lib=dylib={lib}");
+        }
+        return;
+    }
+
+    if env::var_os("CARGO_FEATURE_GTK_PKG_CONFIG").is_some() {
+        pkg_config::Config::new()
+            .atleast_version("3.0")
+            .probe("gtk+-3.0")
+            .expect(
+                "GTK 3 was not found through pkg-config. Install its development files, or \
+                     enable the `gtk-runtime` or `no-gtk-link` feature of nvdialog-sys",
+            );
+    }
 }
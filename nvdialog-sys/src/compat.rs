@@ -0,0 +1,77 @@
+//! Stand-ins for the NvDialog functions older releases don't declare, so the bindings have the
+//! same shape whichever version they were generated from. They are never meant to be called:
+//! check [`crate::available`] first. Items the generated bindings do define take precedence
+//! over these, as the `ffi` module only glob-imports them.
+
+#![allow(non_camel_case_types, clippy::missing_safety_doc)]
+
+#[cfg(not(nvd_has_dynamic_string))]
+mod dynamic_string {
+    use std::os::raw::c_char;
+
+    #[repr(C)]
+    pub struct NvdDynamicString {
+        _private: [u8; 0],
+    }
+
+    pub unsafe extern "C" fn nvd_string_new(_data: *const c_char) -> *mut NvdDynamicString {
+        std::ptr::null_mut()
+    }
+
+    pub unsafe extern "C" fn nvd_duplicate_string(
+        _string: *mut NvdDynamicString,
+    ) -> *mut NvdDynamicString {
+        std::ptr::null_mut()
+    }
+
+    pub unsafe extern "C" fn nvd_string_to_cstr(_string: *mut NvdDynamicString) -> *const c_char {
+        std::ptr::null()
+    }
+
+    pub unsafe extern "C" fn nvd_delete_string(_string: *mut NvdDynamicString) {}
+}
+#[cfg(not(nvd_has_dynamic_string))]
+pub use dynamic_string::*;
+
+#[cfg(not(nvd_has_input_box))]
+mod input_box {
+    use crate::ffi::NvdDynamicString;
+    use std::os::raw::c_char;
+
+    #[repr(C)]
+    pub struct NvdInputBox {
+        _private: [u8; 0],
+    }
+
+    pub unsafe extern "C" fn nvd_input_box_new(
+        _title: *const c_char,
+        _message: *const c_char,
+    ) -> *mut NvdInputBox {
+        std::ptr::null_mut()
+    }
+
+    pub unsafe extern "C" fn nvd_show_input_box(_input_box: *mut NvdInputBox) {}
+
+    pub unsafe extern "C" fn nvd_input_box_get_string(
+        _input_box: *mut NvdInputBox,
+    ) -> *mut NvdDynamicString {
+        std::ptr::null_mut()
+    }
+}
+#[cfg(not(nvd_has_input_box))]
+pub use input_box::*;
+
+#[cfg(not(nvd_has_get_version))]
+mod get_version {
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct NvdVersion {
+        pub major: i16,
+        pub minor: i16,
+        pub patch: i16,
+    }
+
+    pub unsafe extern "C" fn nvd_get_version(_version: *mut NvdVersion) {}
+}
+#[cfg(not(nvd_has_get_version))]
+pub use get_version::*;
@@ -4,4 +4,23 @@ pub mod ffi {
     #![allow(non_upper_case_globals)]
     #![allow(unused, dead_code)]
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+    pub use crate::compat::*;
 }
+
+mod compat;
+
+/// Which of NvDialog's newer APIs the headers the bindings were generated from provide. When one
+/// is missing, its functions are still present in [`ffi`] but do nothing, so callers should check
+/// here before relying on them.
+pub mod available {
+    /// `NvdDynamicString` and the `nvd_string_*` functions.
+    pub const DYNAMIC_STRING: bool = cfg!(nvd_has_dynamic_string);
+    /// `NvdInputBox` and the `nvd_*input_box*` functions.
+    pub const INPUT_BOX: bool = cfg!(nvd_has_input_box);
+    /// `nvd_get_version`.
+    pub const GET_VERSION: bool = cfg!(nvd_has_get_version);
+}
+
+/// The version of the libnvdialog headers these bindings were generated from, as
+/// `(major, minor, patch)`.
+pub const NATIVE_VERSION: (u32, u32, u32) = include!(concat!(env!("OUT_DIR"), "/version.rs"));
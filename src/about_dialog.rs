@@ -22,7 +22,12 @@
  * IN THE SOFTWARE.
  */
 
+use crate::{
+    util::{free_raw, with_truncated_c_strings},
+    DialogInfo, DialogKind, Error, Image, Object,
+};
 use nvdialog_sys::ffi::*;
+use std::ffi::c_void;
 
 /// A struct for a dialog to show about your application.
 /// 
@@ -42,10 +47,16 @@ use nvdialog_sys::ffi::*;
 /// 
 /// dialog.show()
 /// ```
+///
+/// # Icons
+/// The [`Image`] passed to [`AboutDialog::icon`] is owned by the dialog and kept alive for as
+/// long as the native dialog exists: NvDialog only stores a pointer to it, so it is destroyed
+/// after the native dialog is freed, never before.
 pub struct AboutDialog {
     app_name: String,
     details: String,
-    icon: String,
+    /* Must outlive `raw`, which refers to it; see `Drop` below. */
+    icon: Option<Image>,
     raw: *mut NvdAboutDialog
 }
 
@@ -54,7 +65,7 @@ impl AboutDialog {
         Self {
             app_name: String::new(),
             details: String::new(),
-            icon: String::new(),
+            icon: None,
             raw: std::ptr::null_mut(),
         }
     }
@@ -70,29 +81,134 @@ impl AboutDialog {
     }
 
 
-    pub fn icon(mut self, icon: String) -> Self {
-        self.icon = icon;
+    /// Sets the icon shown in the dialog. The dialog takes ownership of `icon`; use
+    /// [`Image::try_clone`] to keep using the same image elsewhere.
+    pub fn icon(mut self, icon: Image) -> Self {
+        self.icon = Some(icon);
         self
     }
 
     pub fn build(mut self) -> Self {
-        let dialog = unsafe {
-            let n = c_string!(&*self.app_name);
-            let d = c_string!(&*self.details);
-            let _i = c_string!(&*self.icon);
+        crate::init::check_thread();
+        #[cfg(feature = "auto-reply")]
+        if crate::auto_reply_enabled() {
+            return self;
+        }
+
+        if !crate::init::native_available() {
+            return self;
+        }
+
+        let dialog = with_truncated_c_strings([&self.app_name, &self.details], |[n, d]| unsafe {
             nvd_about_dialog_new(
-                n.as_ptr(),
-                d.as_ptr(),
-                std::ptr::null_mut() // TODO: Fix this
+                n,
+                d,
+                std::ptr::null_mut()
             )
-        };
-        self.raw = dialog;
+        });
+        match &self.icon {
+            Some(icon) if !dialog.is_null() => unsafe { nvd_dialog_set_icon(dialog, icon.get_raw()) },
+            _ => {}
+        }
+        self.raw = crate::ffi_check::track(dialog);
         self
     }
 
+    /// Returns the toolkit's own object behind this dialog: a `GtkWidget*` with GTK, an `HWND`
+    /// on Windows or an `NSWindow*` on macOS. Returns a null pointer if no native dialog was
+    /// created, or if the backend doesn't create the window before it is shown.
+    ///
+    /// This is unsupported territory, meant for tweaking properties this crate has no API for.
+    ///
+    /// # Safety
+    /// The pointer is owned by NvDialog and invalid once the dialog is dropped. Changing the
+    /// object in ways NvDialog doesn't expect (destroying it, replacing its contents...) is
+    /// undefined behavior.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_about_dialog_get_raw`.
+    pub unsafe fn native_widget(&self) -> *mut c_void {
+        if self.raw.is_null() {
+            return std::ptr::null_mut();
+        }
+        crate::ffi_check::check(self.raw);
+        nvd_about_dialog_get_raw(self.raw)
+    }
+
+    /// Returns the application name shown in the dialog.
+    pub fn title(&self) -> &str {
+        &self.app_name
+    }
+
+    /// Returns the description shown in the dialog.
+    pub fn message(&self) -> &str {
+        &self.details
+    }
+
+    /// Returns the kind of the dialog, which is always [`DialogKind::About`].
+    pub fn kind(&self) -> DialogKind {
+        DialogKind::About
+    }
+
+    /// Returns a [`DialogInfo`] describing what the dialog is going to display.
+    pub fn info(&self) -> DialogInfo {
+        DialogInfo {
+            kind: self.kind(),
+            title: self.app_name.clone(),
+            message: self.details.clone(),
+        }
+    }
+
     pub fn show(&mut self) {
-        unsafe {
-            nvd_show_about_dialog(self.raw)
+        let _ = self.try_show();
+    }
+
+    /// Same as [`AboutDialog::show`], but reports about dialogs that can't be shown.
+    ///
+    /// # Errors
+    /// Returns [`Error::Unsupported`] if a fallback [`Backend`](crate::Backend) is active, as
+    /// they have no about dialogs.
+    pub fn try_show(&mut self) -> Result<(), Error> {
+        crate::init::check_thread();
+        #[cfg(feature = "recorder")]
+        crate::recorder::record("about", &self.app_name, &self.details, None);
+        if crate::backend::fallback().is_some() {
+            return Err(Error::Unsupported
+                .context("About dialogs are only supported by the native backend"));
+        }
+        if self.raw.is_null() {
+            return Ok(());
         }
+        crate::instrument::instrument(
+            || self.info(),
+            || unsafe { nvd_show_about_dialog(self.raw) },
+            |_| None,
+        );
+        Ok(())
+    }
+}
+
+impl Object for AboutDialog {
+    type NativeType = NvdAboutDialog;
+    type ReturnValue = ();
+
+    fn get_raw(&self) -> *mut NvdAboutDialog {
+        crate::ffi_check::check(self.raw);
+        self.raw
     }
-}
\ No newline at end of file
+
+    fn show(&mut self) -> Result<(), Error> {
+        self.try_show()
+    }
+
+    fn free(&mut self) {
+        unsafe { free_raw(std::mem::replace(&mut self.raw, std::ptr::null_mut())) };
+    }
+}
+
+impl Drop for AboutDialog {
+    fn drop(&mut self) {
+        /* Fields are dropped after this returns, so the icon outlives the native dialog. */
+        unsafe { free_raw(self.raw) };
+    }
+}
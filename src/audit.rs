@@ -0,0 +1,99 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{DialogInfo, Reply};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+/// What the user decided in a dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// A [`QuestionDialog`](crate::QuestionDialog) was answered.
+    Reply(Reply),
+    /// A [`FileDialog`](crate::FileDialog) was closed, with the chosen file or `None` if it was
+    /// cancelled.
+    File(Option<PathBuf>),
+}
+
+/// A decision taken by the user in a dialog, passed to the logger registered with
+/// [`set_decision_logger`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogEvent {
+    /// The dialog the decision was taken in.
+    pub dialog: DialogInfo,
+    /// The decision itself.
+    pub decision: Decision,
+}
+
+type Logger = Arc<dyn Fn(&DialogEvent) + Send + Sync>;
+
+static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// Registers `logger` to be called every time the user answers a question or picks a file, in
+/// any thread, replacing the previous one.
+///
+/// This is meant for audit trails, for example to keep track of who confirmed a destructive
+/// operation and when. The logger runs synchronously right after the reply is collected and
+/// before it is returned to the caller.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::set_decision_logger;
+///
+/// set_decision_logger(|event| {
+///     eprintln!("[audit] '{}': {:?}", event.dialog.title, event.decision);
+/// });
+/// ```
+pub fn set_decision_logger<F: Fn(&DialogEvent) + Send + Sync + 'static>(logger: F) {
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(logger));
+}
+
+/// Removes the logger registered with [`set_decision_logger`], if any.
+pub fn clear_decision_logger() {
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Passes the event built by `event` to the registered logger and, with the `async` feature, to
+/// the [`EventStream`](crate::EventStream)s. `event` is only called if one of them is listening,
+/// so dialogs don't pay for building events nobody reads.
+pub(crate) fn log_decision(event: impl FnOnce() -> DialogEvent) {
+    /* Cloned out of the lock, so that the logger itself may register another one. */
+    let logger = LOGGER.read().unwrap_or_else(|e| e.into_inner()).clone();
+    #[cfg(feature = "async")]
+    let streaming = crate::events::has_subscribers();
+    #[cfg(not(feature = "async"))]
+    let streaming = false;
+    if logger.is_none() && !streaming {
+        return;
+    }
+
+    let event = event();
+    if let Some(logger) = logger {
+        logger(&event);
+    }
+    #[cfg(feature = "async")]
+    crate::events::publish(&event);
+}
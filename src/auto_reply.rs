@@ -0,0 +1,199 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::Reply;
+use std::{cell::RefCell, path::PathBuf};
+
+/// The environment variable holding the scripted answers used by the `auto-reply` feature.
+///
+/// It is only read by [`init_with`](crate::init_with) if the application opted in with
+/// [`InitOptions::auto_reply`](crate::InitOptions::auto_reply).
+///
+/// The value is a list of answers separated by `;`. Write `;;` for a `;` that is part of an
+/// answer, such as a path. Every dialog that returns something to the caller consumes one
+/// answer, in order. Once the list runs out, the last answer is reused, so
+/// `NVDIALOG_AUTO_REPLY=yes` answers every question with [`Reply::Accepted`].
+///
+/// Accepted answers are:
+/// - `yes`, `ok` or `accept` for [`Reply::Accepted`].
+/// - `no` or `reject` for [`Reply::Rejected`].
+/// - `cancel` for [`Reply::Cancelled`], or no file at all for file dialogs.
+/// - `dismiss` or `close` for [`Reply::Dismissed`], or no file at all for file dialogs.
+/// - `default` for the question's [`DefaultAnswer`](crate::DefaultAnswer), or no file at all for
+///   file dialogs.
+/// - Anything else is used as the path returned by file dialogs, and gives question dialogs
+///   their [`DefaultAnswer`](crate::DefaultAnswer).
+///
+/// Dialog boxes, about dialogs and notifications don't return anything, so they are skipped
+/// without consuming an answer.
+pub const AUTO_REPLY_ENV: &str = "NVDIALOG_AUTO_REPLY";
+
+struct Script {
+    answers: Vec<String>,
+    position: usize,
+}
+
+thread_local! {
+    static SCRIPT: RefCell<Option<Script>> = const { RefCell::new(None) };
+}
+
+/// Splits the value of [`AUTO_REPLY_ENV`] into answers, turning `;;` back into `;`.
+fn parse_script(value: &str) -> Option<Script> {
+    let mut answers = Vec::new();
+    let mut answer = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' if chars.peek() == Some(&';') => {
+                chars.next();
+                answer.push(';');
+            }
+            ';' => answers.push(std::mem::take(&mut answer)),
+            c => answer.push(c),
+        }
+    }
+    answers.push(answer);
+
+    let answers: Vec<String> = answers
+        .iter()
+        .map(|answer| answer.trim().to_owned())
+        .filter(|answer| !answer.is_empty())
+        .collect();
+    if answers.is_empty() {
+        None
+    } else {
+        Some(Script {
+            answers,
+            position: 0,
+        })
+    }
+}
+
+/// Loads the answers from [`AUTO_REPLY_ENV`] for the current thread, returning `true` if it holds
+/// any. Called by [`init_with`](crate::init_with) when the application opted in.
+pub(crate) fn enable() -> bool {
+    let script = std::env::var(AUTO_REPLY_ENV)
+        .ok()
+        .and_then(|value| parse_script(&value));
+    SCRIPT.with(|current| *current.borrow_mut() = script);
+    auto_reply_enabled()
+}
+
+pub(crate) fn disable() {
+    SCRIPT.with(|script| *script.borrow_mut() = None);
+}
+
+/// Returns `true` if dialogs in the current thread are answered from [`AUTO_REPLY_ENV`]
+/// instead of being shown, that is if the thread was initialized with
+/// [`InitOptions::auto_reply`](crate::InitOptions::auto_reply) and the variable holds answers.
+pub fn auto_reply_enabled() -> bool {
+    SCRIPT.with(|script| script.borrow().is_some())
+}
+
+fn next_answer() -> Option<String> {
+    SCRIPT.with(|script| {
+        let mut script = script.borrow_mut();
+        let script = script.as_mut()?;
+        let index = script.position.min(script.answers.len() - 1);
+        script.position += 1;
+        Some(script.answers[index].clone())
+    })
+}
+
+/// Returns `Some(None)` for answers that don't name a reply, which resolve to the question's
+/// [`DefaultAnswer`](crate::DefaultAnswer).
+pub(crate) fn next_reply() -> Option<Option<Reply>> {
+    next_answer().map(|answer| reply_for(&answer))
+}
+
+pub(crate) fn next_file() -> Option<Option<PathBuf>> {
+    next_answer().map(|answer| file_for(&answer))
+}
+
+fn reply_for(answer: &str) -> Option<Reply> {
+    match answer.to_ascii_lowercase().as_str() {
+        "yes" | "ok" | "accept" => Some(Reply::Accepted),
+        "no" | "reject" => Some(Reply::Rejected),
+        "cancel" => Some(Reply::Cancelled),
+        "dismiss" | "close" => Some(Reply::Dismissed),
+        _ => None,
+    }
+}
+
+fn file_for(answer: &str) -> Option<PathBuf> {
+    if ["cancel", "dismiss", "close", "default"]
+        .iter()
+        .any(|keyword| answer.eq_ignore_ascii_case(keyword))
+    {
+        None
+    } else {
+        Some(PathBuf::from(answer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(value: &str) -> Vec<String> {
+        parse_script(value)
+            .map(|script| script.answers)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn answers_are_split_and_trimmed() {
+        assert_eq!(answers("yes; no ; cancel;"), ["yes", "no", "cancel"]);
+        assert_eq!(answers(" ; "), Vec::<String>::new());
+        assert!(parse_script("").is_none());
+    }
+
+    #[test]
+    fn doubled_separators_are_kept_in_answers() {
+        assert_eq!(answers("/tmp/a;;b.txt;no"), ["/tmp/a;b.txt", "no"]);
+        assert_eq!(answers("a;;;b"), ["a;", "b"]);
+    }
+
+    #[test]
+    fn answers_map_to_replies_and_files() {
+        assert_eq!(reply_for("YES"), Some(Reply::Accepted));
+        assert_eq!(reply_for("reject"), Some(Reply::Rejected));
+        assert_eq!(reply_for("cancel"), Some(Reply::Cancelled));
+        assert_eq!(reply_for("close"), Some(Reply::Dismissed));
+        assert_eq!(reply_for("default"), None);
+        assert_eq!(file_for("Default"), None);
+        assert_eq!(file_for("/tmp/a.txt"), Some(PathBuf::from("/tmp/a.txt")));
+    }
+
+    #[test]
+    fn the_last_answer_is_reused() {
+        SCRIPT.with(|script| *script.borrow_mut() = parse_script("no;/tmp/a.txt"));
+        assert!(auto_reply_enabled());
+        assert_eq!(next_reply(), Some(Some(Reply::Rejected)));
+        assert_eq!(next_file(), Some(Some(PathBuf::from("/tmp/a.txt"))));
+        assert_eq!(next_file(), Some(Some(PathBuf::from("/tmp/a.txt"))));
+        disable();
+        assert_eq!(next_reply(), None);
+    }
+}
@@ -0,0 +1,151 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{ButtonLayout, DialogType, FileDialogType, QuestionDialogButtons, Reply};
+use std::{cell::Cell, path::PathBuf};
+
+/// A backend [`init_with`](crate::init_with) can show dialogs with.
+///
+/// Backends are tried in the order given to [`InitOptions::backends`](crate::InitOptions::backends)
+/// until one of them initializes, and the one chosen is returned by [`current_backend`]. Which
+/// toolkit the native backend uses (GTK, Win32, Cocoa...) is decided when NvDialog is built, so it
+/// can't be picked at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// NvDialog's native backend. Fails if `nvd_init` does, for example when GTK is missing or
+    /// there is no display.
+    Native,
+    /// `zenity` or `kdialog` on Linux and `osascript` on macOS, run as subprocesses. Fails if the
+    /// `external-tools` feature is disabled or none of these tools are installed.
+    ExternalTools,
+    /// Plain text prompts on the terminal. Fails unless both standard input and standard error
+    /// are terminals.
+    Tty,
+    /// Records dialogs instead of showing them, see [`MockBackend`](crate::MockBackend). Fails
+    /// if the `mock` feature is disabled.
+    Mock,
+}
+
+thread_local! {
+    static ACTIVE: Cell<Option<Backend>> = const { Cell::new(None) };
+}
+
+/// Returns the backend NvDialog was initialized with in the current thread, or `None` if
+/// [`crate::init`] hasn't succeeded yet.
+pub fn current_backend() -> Option<Backend> {
+    ACTIVE.with(|active| active.get())
+}
+
+pub(crate) fn set_current_backend(backend: Backend) {
+    ACTIVE.with(|active| active.set(Some(backend)));
+}
+
+pub(crate) fn clear_current_backend() {
+    ACTIVE.with(|active| active.set(None));
+}
+
+/// Returns the backend dialogs are delegated to instead of NvDialog in the current thread, if any.
+pub(crate) fn fallback() -> Option<Backend> {
+    current_backend().filter(|backend| *backend != Backend::Native)
+}
+
+impl Backend {
+    /// Tries to make this backend usable in the current thread, apart from [`Backend::Native`]
+    /// which is initialized by [`crate::init_with`] itself.
+    pub(crate) fn init_fallback(self) -> bool {
+        match self {
+            Self::Native => false,
+            #[cfg(feature = "external-tools")]
+            Self::ExternalTools => match crate::external::detect() {
+                Some(tool) => {
+                    crate::external::set_active(Some(tool));
+                    true
+                }
+                None => false,
+            },
+            #[cfg(not(feature = "external-tools"))]
+            Self::ExternalTools => false,
+            Self::Tty => crate::tty::available(),
+            Self::Mock => cfg!(feature = "mock"),
+        }
+    }
+
+    pub(crate) fn message(self, title: &str, msg: &str, dialog_type: DialogType) {
+        match self {
+            Self::Native => {}
+            #[cfg(feature = "external-tools")]
+            Self::ExternalTools => {
+                if let Some(tool) = crate::external::active() {
+                    tool.message(title, msg, dialog_type);
+                }
+            }
+            #[cfg(not(feature = "external-tools"))]
+            Self::ExternalTools => {}
+            Self::Tty => crate::tty::message(title, msg, dialog_type),
+            #[cfg(feature = "mock")]
+            Self::Mock => crate::mock::message(title, msg, dialog_type),
+            #[cfg(not(feature = "mock"))]
+            Self::Mock => {}
+        }
+    }
+
+    /// Asks a question. Only the mock backend uses `layout` and the `labels` of the buttons, to
+    /// draw them.
+    #[cfg_attr(not(feature = "mock"), allow(unused_variables))]
+    pub(crate) fn question(
+        self,
+        title: &str,
+        msg: &str,
+        buttons: &QuestionDialogButtons,
+        layout: ButtonLayout,
+        labels: &[(Reply, &str)],
+    ) -> Reply {
+        match self {
+            #[cfg(feature = "external-tools")]
+            Self::ExternalTools => crate::external::active()
+                .map_or(Reply::Cancelled, |tool| tool.question(title, msg, buttons)),
+            Self::Tty => crate::tty::question(title, msg, buttons),
+            #[cfg(feature = "mock")]
+            Self::Mock => crate::mock::question(title, msg, buttons, layout, labels),
+            _ => Reply::Cancelled,
+        }
+    }
+
+    pub(crate) fn file(
+        self,
+        title: &str,
+        dialog_type: FileDialogType,
+        extensions: &[String],
+    ) -> Option<PathBuf> {
+        match self {
+            #[cfg(feature = "external-tools")]
+            Self::ExternalTools => crate::external::active()
+                .and_then(|tool| tool.file(title, dialog_type, extensions)),
+            Self::Tty => crate::tty::file(title, dialog_type, extensions),
+            #[cfg(feature = "mock")]
+            Self::Mock => crate::mock::file(title, dialog_type, extensions),
+            _ => None,
+        }
+    }
+}
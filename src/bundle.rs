@@ -0,0 +1,71 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Detection of macOS app bundles, outside of which notifications can't be delivered.
+
+use std::sync::OnceLock;
+
+static BUNDLED: OnceLock<bool> = OnceLock::new();
+
+/// Returns `true` if the process runs from a macOS app bundle (a `.app` directory with an
+/// `Info.plist` declaring a bundle identifier).
+///
+/// macOS silently drops notifications from processes that aren't bundled, which includes
+/// binaries started with `cargo run`. [`crate::Notification::new`] checks this and either
+/// delivers notifications through `osascript` (with the `external-tools` feature) or returns
+/// [`crate::Error::RequiresAppBundle`]. Always `false` on other platforms.
+pub fn is_app_bundle() -> bool {
+    *BUNDLED.get_or_init(detect)
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> bool {
+    use std::ffi::{c_char, c_void};
+
+    #[link(name = "Foundation", kind = "framework")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    if !crate::init::NATIVE_CODE {
+        return false;
+    }
+    unsafe {
+        let send_id: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+        let class = objc_getClass(c"NSBundle".as_ptr());
+        let bundle = send_id(class, sel_registerName(c"mainBundle".as_ptr()));
+        /* Unbundled executables still get a main bundle, but without an identifier. */
+        !bundle.is_null()
+            && !send_id(bundle, sel_registerName(c"bundleIdentifier".as_ptr())).is_null()
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn detect() -> bool {
+    false
+}
@@ -0,0 +1,255 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! The order of the buttons of question dialogs.
+
+use crate::Reply;
+use std::sync::Mutex;
+
+/// Where the affirmative button (Yes, Save...) of a question dialog goes relative to the others.
+///
+/// Human interface guidelines disagree on this: GNOME and macOS put the affirmative button on the
+/// right, with Cancel next to it, while Windows puts it on the left. Set the layout with
+/// [`set_button_layout`], or for a single question with
+/// [`QuestionDialog::button_layout`](crate::QuestionDialog::button_layout).
+///
+/// The layout applies to GTK and Windows dialogs, and to the snapshots of the
+/// [`MockBackend`](crate::MockBackend). The alerts of macOS always put the affirmative button on
+/// the right, as its guidelines want, and the other fallback backends don't have buttons to
+/// arrange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ButtonLayout {
+    /// The layout of the platform's guidelines: [`ButtonLayout::AffirmativeLeft`] on Windows,
+    /// [`ButtonLayout::AffirmativeRight`] elsewhere.
+    #[default]
+    Platform,
+    /// Yes, No, Cancel from left to right.
+    AffirmativeLeft,
+    /// No, Cancel, Yes from left to right.
+    AffirmativeRight,
+}
+
+impl ButtonLayout {
+    /// Returns [`ButtonLayout::AffirmativeLeft`] or [`ButtonLayout::AffirmativeRight`], resolving
+    /// [`ButtonLayout::Platform`] for the current platform.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Platform if cfg!(target_os = "windows") => Self::AffirmativeLeft,
+            Self::Platform => Self::AffirmativeRight,
+            layout => layout,
+        }
+    }
+
+    /// Returns the replies of the buttons in this layout, from left to right.
+    pub(crate) fn order(self) -> [Reply; 3] {
+        match self.resolve() {
+            Self::AffirmativeLeft => [Reply::Accepted, Reply::Rejected, Reply::Cancelled],
+            _ => [Reply::Rejected, Reply::Cancelled, Reply::Accepted],
+        }
+    }
+}
+
+static LAYOUT: Mutex<ButtonLayout> = Mutex::new(ButtonLayout::Platform);
+
+/// Sets the [`ButtonLayout`] of every question dialog that doesn't set its own. Defaults to
+/// [`ButtonLayout::Platform`].
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::ButtonLayout;
+///
+/// // A cross-platform application keeping the same layout everywhere.
+/// nvdialog_rs::set_button_layout(ButtonLayout::AffirmativeRight);
+/// ```
+pub fn set_button_layout(layout: ButtonLayout) {
+    *LAYOUT.lock().unwrap_or_else(|e| e.into_inner()) = layout;
+}
+
+/// Returns the process-wide [`ButtonLayout`].
+pub fn button_layout() -> ButtonLayout {
+    *LAYOUT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Reorders the buttons of the GTK question dialog `dialog` following `layout`.
+#[cfg(target_os = "linux")]
+pub(crate) unsafe fn apply_gtk(dialog: *mut std::ffi::c_void, layout: ButtonLayout) {
+    use crate::question_dialog::gtk_button_for;
+    use std::ffi::{c_int, c_void};
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_widget_get_parent(widget: *mut c_void) -> *mut c_void;
+        fn gtk_box_get_type() -> usize;
+        fn gtk_box_reorder_child(container: *mut c_void, child: *mut c_void, position: c_int);
+        fn g_type_check_instance_is_a(instance: *mut c_void, type_: usize) -> c_int;
+    }
+
+    if dialog.is_null() {
+        return;
+    }
+    let buttons: Vec<*mut c_void> = layout
+        .order()
+        .into_iter()
+        .map(|reply| gtk_button_for(dialog, reply).0)
+        .filter(|button| !button.is_null())
+        .collect();
+    for (position, button) in buttons.into_iter().enumerate() {
+        let parent = gtk_widget_get_parent(button);
+        /* Dialogs using a header bar place their buttons themselves. */
+        if parent.is_null() || g_type_check_instance_is_a(parent, gtk_box_get_type()) == 0 {
+            return;
+        }
+        gtk_box_reorder_child(parent, button, position as c_int);
+    }
+}
+
+/// Reorders the buttons of the Windows message box `dialog` following `layout`, by swapping
+/// their places, as its buttons all have the same size.
+#[cfg(target_os = "windows")]
+pub(crate) unsafe fn apply_win32(dialog: *mut std::ffi::c_void, layout: ButtonLayout) {
+    use crate::question_dialog::win32_button_for;
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetWindowRect(hwnd: *mut c_void, rect: *mut Rect) -> i32;
+        fn MapWindowPoints(
+            from: *mut c_void,
+            to: *mut c_void,
+            points: *mut Rect,
+            count: u32,
+        ) -> i32;
+        fn SetWindowPos(
+            hwnd: *mut c_void,
+            after: *mut c_void,
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            flags: u32,
+        ) -> i32;
+    }
+
+    const SWP_NOSIZE: u32 = 0x0001;
+    const SWP_NOZORDER: u32 = 0x0004;
+    const SWP_NOACTIVATE: u32 = 0x0010;
+
+    let buttons: Vec<*mut c_void> = layout
+        .order()
+        .into_iter()
+        .map(|reply| win32_button_for(dialog, reply).0)
+        .filter(|button| !button.is_null())
+        .collect();
+    /* The places the buttons take, from left to right. */
+    let mut places: Vec<(i32, i32)> = buttons
+        .iter()
+        .map(|&button| {
+            let mut rect = Rect::default();
+            GetWindowRect(button, &mut rect);
+            /* A `RECT` is two `POINT`s. */
+            MapWindowPoints(std::ptr::null_mut(), dialog, &mut rect, 2);
+            (rect.left, rect.top)
+        })
+        .collect();
+    places.sort_unstable();
+    for (i, (&button, (x, y))) in buttons.iter().zip(places).enumerate() {
+        /* Each button goes after the previous one, so that Tab follows the new order. */
+        match i.checked_sub(1) {
+            Some(previous) => SetWindowPos(
+                button,
+                buttons[previous],
+                x,
+                y,
+                0,
+                0,
+                SWP_NOSIZE | SWP_NOACTIVATE,
+            ),
+            None => SetWindowPos(
+                button,
+                std::ptr::null_mut(),
+                x,
+                y,
+                0,
+                0,
+                SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+            ),
+        };
+    }
+}
+
+#[cfg(all(test, feature = "mock", not(feature = "strict-threading")))]
+mod tests {
+    use super::*;
+    use crate::{mock::any, MockBackend, QuestionDialog, QuestionDialogButtons};
+
+    fn buttons(layout: ButtonLayout, buttons: QuestionDialogButtons) -> String {
+        let mock = MockBackend::install();
+        mock.on_question(any()).reply(Reply::Accepted);
+        QuestionDialog::new("Quit", "Quit now?", buttons)
+            .button_layout(layout)
+            .get_reply();
+        let snapshot = mock.snapshot().unwrap();
+        let line = snapshot.lines().rev().nth(1).unwrap();
+        String::from(line.trim_matches(|c| c == '|' || c == ' '))
+    }
+
+    #[test]
+    fn mock_snapshots_follow_the_layout() {
+        use QuestionDialogButtons::{YesNo, YesNoCancel};
+
+        let left = buttons(ButtonLayout::AffirmativeLeft, YesNoCancel);
+        assert_eq!(left, "[ Yes ] [ No ] [ Cancel ]");
+        let right = buttons(ButtonLayout::AffirmativeRight, YesNoCancel);
+        assert_eq!(right, "[ No ] [ Cancel ] [ Yes ]");
+        assert_eq!(
+            buttons(ButtonLayout::AffirmativeRight, YesNo),
+            "[ No ] [ Yes ]"
+        );
+        assert_eq!(
+            buttons(ButtonLayout::Platform, YesNoCancel),
+            buttons(ButtonLayout::Platform.resolve(), YesNoCancel)
+        );
+    }
+
+    #[test]
+    fn platform_resolves_to_the_guidelines() {
+        let expected = match cfg!(target_os = "windows") {
+            true => ButtonLayout::AffirmativeLeft,
+            false => ButtonLayout::AffirmativeRight,
+        };
+        assert_eq!(ButtonLayout::Platform.resolve(), expected);
+        assert_eq!(
+            ButtonLayout::AffirmativeLeft.resolve(),
+            ButtonLayout::AffirmativeLeft
+        );
+    }
+}
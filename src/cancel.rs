@@ -0,0 +1,326 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::Error;
+use std::{
+    ffi::c_void,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Closes a dialog while it is shown, from another thread or from a callback that runs while the
+/// dialog is up, such as a timer of the application's event loop.
+///
+/// Hand a clone of the token to a dialog with [`QuestionDialog::cancel_token`],
+/// [`FileDialog::cancel_token`] or [`DialogBox::set_cancel_token`], and call
+/// [`CancelToken::cancel`] to close it. The question then returns [`Reply::Dismissed`], the file
+/// dialog `None`, and [`DialogBox::show`] returns. A dialog given a token that was already
+/// cancelled isn't shown at all, so cancelling a token also cancels the dialogs queued with it,
+/// for example with [`QuestionDialog::show_nonblocking`].
+///
+/// How the dialog is closed depends on the platform:
+/// - With GTK, the dialog is closed as if the user cancelled it.
+/// - On Windows, the window of the dialog is caught when it appears, and closed as if the user
+///   pressed Cancel, or No if it has no Cancel button. Other windows of the thread, such as a
+///   message box opened on top of the dialog, are left alone.
+/// - On macOS, with fallback backends, or if the backend doesn't create the dialog's window
+///   before it is shown (see [`FileDialog::native_widget`]), the dialog can't be closed:
+///   [`CancelToken::cancel`] returns [`Error::Unsupported`], and the dialog stays open until the
+///   user closes it. Its answer is discarded all the same.
+///
+/// A token closes one dialog at a time: show the dialogs sharing a token one after the other.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{CancelToken, QuestionDialog, QuestionDialogButtons, Reply};
+/// use std::{thread, time::Duration};
+///
+/// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+/// let token = CancelToken::new();
+/// let timeout = token.clone();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(30));
+///     // Questions can be closed on every platform but macOS.
+///     let _ = timeout.cancel();
+/// });
+/// let reply = QuestionDialog::new("Update", "Restart now?", QuestionDialogButtons::YesNoCancel)
+///     .cancel_token(token)
+///     .get_reply();
+/// if reply == Reply::Dismissed {
+///     println!("No answer after 30 seconds, restarting later.");
+/// }
+/// ```
+///
+/// [`QuestionDialog::cancel_token`]: crate::QuestionDialog::cancel_token
+/// [`QuestionDialog::show_nonblocking`]: crate::QuestionDialog::show_nonblocking
+/// [`FileDialog::cancel_token`]: crate::FileDialog::cancel_token
+/// [`FileDialog::native_widget`]: crate::FileDialog::native_widget
+/// [`DialogBox::set_cancel_token`]: crate::DialogBox::set_cancel_token
+/// [`DialogBox::show`]: crate::DialogBox::show
+/// [`Reply::Dismissed`]: crate::Reply::Dismissed
+/// [`Error::Unsupported`]: crate::Error::Unsupported
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    cancelled: bool,
+    /// What closes the dialog while it is shown.
+    showing: Option<Showing>,
+}
+
+/// How a [`CancelToken`] closes the dialog it was given.
+#[derive(Clone)]
+pub(crate) enum Showing {
+    /// What [`close_native`] needs to close the dialog. On Windows, this is the window of the
+    /// dialog, which is `0` until it appears.
+    Native(usize),
+    /// A dialog of the mock backend, kept open by `FileDialogRule::until_closed`.
+    #[cfg(feature = "mock")]
+    Mock(Arc<crate::mock::HeldDialog>),
+    /// A dialog that can't be closed.
+    Untargeted,
+}
+
+impl CancelToken {
+    /// Creates a token that isn't cancelled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Closes the dialog shown with this token, if any, and keeps the dialogs given this token
+    /// from being shown from now on. Can be called from any thread.
+    ///
+    /// # Errors
+    /// Returns [`Error::Unsupported`] if the dialog shown with this token can't be closed, see
+    /// [`CancelToken`]. The token is cancelled regardless, and the answer to that dialog is
+    /// discarded once the user closes it.
+    pub fn cancel(&self) -> Result<(), Error> {
+        let mut state = self.lock();
+        state.cancelled = true;
+        match &state.showing {
+            Some(Showing::Native(handle)) => close_native(*handle, self),
+            #[cfg(feature = "mock")]
+            Some(Showing::Mock(dialog)) => dialog.close(),
+            Some(Showing::Untargeted) => return Err(Error::Unsupported),
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Returns whether [`CancelToken::cancel`] was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.lock().cancelled
+    }
+
+    /// Runs `show`, which shows a dialog closed through `showing`, unless the token is cancelled.
+    /// Returns `None` if it was cancelled before or while the dialog was shown.
+    pub(crate) fn run<T>(&self, showing: Showing, show: impl FnOnce() -> T) -> Option<T> {
+        {
+            let mut state = self.lock();
+            if state.cancelled {
+                return None;
+            }
+            state.showing = Some(showing.clone());
+        }
+        #[cfg(target_os = "windows")]
+        let result = match showing {
+            Showing::Native(_) => {
+                let token = self.clone();
+                crate::win_hook::on_dialog(move |hwnd| token.attach(hwnd), show)
+            }
+            _ => show(),
+        };
+        #[cfg(not(target_os = "windows"))]
+        let result = show();
+        let mut state = self.lock();
+        state.showing = None;
+        (!state.cancelled).then_some(result)
+    }
+
+    /// Records `hwnd` as the window of the dialog shown, closing it if the token was cancelled
+    /// before it appeared.
+    #[cfg(target_os = "windows")]
+    fn attach(&self, hwnd: crate::win_hook::Hwnd) {
+        let mut state = self.lock();
+        if let Some(Showing::Native(handle)) = &mut state.showing {
+            *handle = hwnd as usize;
+        }
+        if state.cancelled {
+            close_native(hwnd as usize, self);
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_showing(&self) -> bool {
+        self.lock().showing.is_some()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Returns what closes the native dialog `widget`, as returned by its `native_widget` method.
+/// Dialogs shown by a fallback backend can't be closed.
+pub(crate) fn showing(widget: *mut c_void) -> Showing {
+    if crate::backend::fallback().is_some() {
+        return Showing::Untargeted;
+    }
+    native_handle(widget).map_or(Showing::Untargeted, Showing::Native)
+}
+
+/// Returns `widget` if it is a `GtkDialog`, which `close_native` can close.
+#[cfg(target_os = "linux")]
+fn native_handle(widget: *mut c_void) -> Option<usize> {
+    use std::ffi::c_int;
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_dialog_get_type() -> usize;
+        fn g_type_check_instance_is_a(instance: *mut c_void, type_: usize) -> c_int;
+    }
+
+    let is_dialog = !widget.is_null()
+        && unsafe { g_type_check_instance_is_a(widget, gtk_dialog_get_type()) } != 0;
+    is_dialog.then_some(widget as usize)
+}
+
+/// Closes the `GtkDialog` `widget` from the thread running the GTK main loop, if `token` still
+/// shows it by then.
+#[cfg(target_os = "linux")]
+fn close_native(_widget: usize, token: &CancelToken) {
+    use std::ffi::{c_int, c_uint};
+
+    crate::gtk_link::gtk_extern! {
+        fn g_idle_add(
+            function: unsafe extern "C" fn(*mut c_void) -> c_int,
+            data: *mut c_void,
+        ) -> c_uint;
+        fn gtk_dialog_response(dialog: *mut c_void, response_id: c_int);
+    }
+
+    const GTK_RESPONSE_CANCEL: c_int = -6;
+
+    unsafe extern "C" fn close(data: *mut c_void) -> c_int {
+        let token = Box::from_raw(data as *mut CancelToken);
+        /* The dialog may have been closed and freed since, in which case this is `None`. */
+        let showing = token.lock().showing.clone();
+        if let Some(Showing::Native(widget)) = showing {
+            gtk_dialog_response(widget as *mut c_void, GTK_RESPONSE_CANCEL);
+        }
+        0
+    }
+
+    let data = Box::into_raw(Box::new(token.clone()));
+    unsafe { g_idle_add(close, data as *mut c_void) };
+}
+
+/* The dialog's window only exists once it is shown, so `CancelToken::run` catches it then. */
+#[cfg(target_os = "windows")]
+fn native_handle(_widget: *mut c_void) -> Option<usize> {
+    Some(0)
+}
+
+/// Closes the dialog box `hwnd` as if its Cancel button was pressed. Message boxes without one
+/// ignore `WM_CLOSE`, so the button rejecting them is pressed instead, as the answer is discarded
+/// anyway.
+#[cfg(target_os = "windows")]
+fn close_native(hwnd: usize, _token: &CancelToken) {
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetDlgItem(dialog: *mut c_void, id: i32) -> *mut c_void;
+        fn PostMessageW(hwnd: *mut c_void, msg: u32, wparam: usize, lparam: isize) -> i32;
+    }
+
+    const WM_CLOSE: u32 = 0x0010;
+    const WM_COMMAND: u32 = 0x0111;
+    const IDOK: i32 = 1;
+    const IDCANCEL: i32 = 2;
+    const IDYES: i32 = 6;
+    const IDNO: i32 = 7;
+
+    let hwnd = hwnd as *mut c_void;
+    if hwnd.is_null() {
+        return;
+    }
+    unsafe {
+        let button = [IDCANCEL, IDNO, IDOK, IDYES]
+            .into_iter()
+            .find(|id| !GetDlgItem(hwnd, *id).is_null());
+        match button {
+            Some(IDCANCEL) | None => PostMessageW(hwnd, WM_CLOSE, 0, 0),
+            Some(id) => PostMessageW(hwnd, WM_COMMAND, id as usize, 0),
+        };
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn native_handle(_widget: *mut c_void) -> Option<usize> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn close_native(_handle: usize, _token: &CancelToken) {}
+
+#[cfg(all(test, feature = "mock", not(feature = "strict-threading")))]
+mod tests {
+    use super::*;
+    use crate::{MockBackend, QuestionDialog, QuestionDialogButtons, Reply};
+
+    #[test]
+    fn cancelled_before_shown_dismisses_without_showing() {
+        let mock = MockBackend::install();
+        let token = CancelToken::new();
+        token.cancel().unwrap();
+        let reply = QuestionDialog::new("Quit", "Quit now?", QuestionDialogButtons::YesNo)
+            .cancel_token(token)
+            .get_reply();
+        assert_eq!(reply, Reply::Dismissed);
+        assert!(mock.dialogs().is_empty());
+    }
+
+    #[test]
+    fn run_reports_cancellation_while_shown() {
+        let token = CancelToken::new();
+        assert_eq!(token.run(Showing::Untargeted, || 1), Some(1));
+        assert_eq!(token.run(Showing::Untargeted, || token.cancel()), None);
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn dialogs_that_cant_be_closed_are_reported() {
+        let mut result = None;
+        let token = CancelToken::new();
+        token.run(Showing::Untargeted, || result = Some(token.cancel()));
+        assert_eq!(result, Some(Err(Error::Unsupported)));
+        assert!(token.is_cancelled());
+
+        let token = CancelToken::new();
+        let held = Arc::new(crate::mock::HeldDialog::default());
+        token.run(Showing::Mock(held), || result = Some(token.cancel()));
+        assert_eq!(result, Some(Ok(())));
+        assert_eq!(CancelToken::new().cancel(), Ok(()));
+    }
+}
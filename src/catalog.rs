@@ -0,0 +1,71 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+/// A prompt of a dialog catalog, as listed by the `CATALOG` constant that
+/// [`DialogCatalog`](crate::DialogCatalog) generates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogEntry {
+    /// The name of the enum variant.
+    pub name: &'static str,
+    /// The title of the dialog.
+    pub title: &'static str,
+    /// The message of the dialog, with the placeholders for the variant's fields.
+    pub message: &'static str,
+}
+
+/// Derives a catalog of dialogs from an enum of application prompts, so that every user-facing
+/// string lives in one place, where it can be reviewed and translated.
+///
+/// Each variant takes a `#[dialog(...)]` attribute with its `title` and `message`, and either
+/// the `kind` of message box (`"simple"`, `"warning"` or `"error"`) or the `buttons` of a
+/// question (`"yes"`, `"yes_no"` or `"yes_no_cancel"`). Messages can refer to the variant's
+/// named fields as in `format!`.
+///
+/// The enum then gets a `show_<variant>` function per variant, taking the variant's fields:
+/// message boxes return `Result<(), Error>` and questions return their [`Reply`](crate::Reply).
+/// `title()` and `message()` return the text of a prompt, and `CATALOG` lists all of them.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DialogCatalog, Reply};
+///
+/// #[derive(DialogCatalog)]
+/// enum Prompt {
+///     #[dialog(title = "Quit", message = "Save changes before quitting?", buttons = "yes_no_cancel")]
+///     ConfirmQuit,
+///     #[dialog(title = "Error", message = "Could not open {path}.", kind = "error")]
+///     OpenFailed { path: String },
+/// }
+///
+/// nvdialog_rs::init()?;
+/// Prompt::show_open_failed("notes.txt".into())?;
+/// if Prompt::show_confirm_quit() == Reply::Accepted {
+///     // Save...
+/// }
+/// for entry in Prompt::CATALOG {
+///     println!("{}: {} / {}", entry.name, entry.title, entry.message);
+/// }
+/// # Ok::<(), nvdialog_rs::Error>(())
+/// ```
+pub use nvdialog_macros::DialogCatalog;
@@ -0,0 +1,182 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Persistence of "don't ask again" answers to questions.
+
+use crate::Reply;
+use std::sync::{Arc, RwLock};
+
+/// Where the answers of questions marked with [`QuestionDialog::remember_choice`] are kept.
+///
+/// Implementations decide how long answers survive: an in-memory map forgets them when the
+/// application exits, [`JsonChoiceStore`] (with the `choice-store` feature) keeps them in a file
+/// across restarts. They are shared between threads, so use interior mutability.
+///
+/// [`QuestionDialog::remember_choice`]: crate::QuestionDialog::remember_choice
+pub trait ChoiceStore: Send + Sync {
+    /// Returns the answer remembered for `key`, if any.
+    fn load(&self, key: &str) -> Option<Reply>;
+    /// Remembers `reply` as the answer for `key`, replacing the previous one.
+    fn store(&self, key: &str, reply: Reply);
+    /// Forgets the answer for `key`, so that the question is asked again.
+    fn forget(&self, key: &str);
+}
+
+static STORE: RwLock<Option<Arc<dyn ChoiceStore>>> = RwLock::new(None);
+
+/// Registers `store` as the place remembered answers are read from and written to, replacing
+/// the previous one.
+///
+/// Until a store is registered, [`QuestionDialog::remember_choice`] has no effect.
+///
+/// [`QuestionDialog::remember_choice`]: crate::QuestionDialog::remember_choice
+pub fn set_choice_store<S: ChoiceStore + 'static>(store: S) {
+    *STORE.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(store));
+}
+
+/// Removes the store registered with [`set_choice_store`], if any.
+pub fn clear_choice_store() {
+    *STORE.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Forgets the remembered answer for `key` in the registered store, for example from a "Reset
+/// all warnings" button in the application's settings.
+pub fn forget_choice(key: &str) {
+    if let Some(store) = store() {
+        store.forget(key);
+    }
+}
+
+fn store() -> Option<Arc<dyn ChoiceStore>> {
+    STORE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Called by [`crate::QuestionDialog::get_reply`] before showing a remembered question.
+pub(crate) fn recall(key: &str) -> Option<Reply> {
+    store()?.load(key)
+}
+
+/// Called by [`crate::QuestionDialog::get_reply`] with the user's answer to a remembered
+/// question. Cancelling or dismissing the question isn't an answer, so it isn't remembered.
+pub(crate) fn remember(key: &str, reply: Reply) {
+    if matches!(reply, Reply::Accepted | Reply::Rejected) {
+        if let Some(store) = store() {
+            store.store(key, reply);
+        }
+    }
+}
+
+#[cfg(feature = "choice-store")]
+pub use json::JsonChoiceStore;
+
+#[cfg(feature = "choice-store")]
+mod json {
+    use super::ChoiceStore;
+    use crate::Reply;
+    use std::{
+        collections::BTreeMap,
+        io,
+        path::{Path, PathBuf},
+        sync::Mutex,
+    };
+
+    /// A [`ChoiceStore`] keeping answers in a JSON file, mapping each key to `"accepted"` or
+    /// `"rejected"`. Requires the `choice-store` feature.
+    ///
+    /// The file is rewritten every time an answer changes. Failing to write it doesn't affect
+    /// the question; the answer is then only remembered until the application exits.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::{JsonChoiceStore, QuestionDialog, QuestionDialogButtons};
+    ///
+    /// let store = JsonChoiceStore::open("/home/user/.config/app/choices.json")
+    ///     .expect("Failed to read remembered choices");
+    /// nvdialog_rs::set_choice_store(store);
+    ///
+    /// let reply = QuestionDialog::new("Empty trash", "Delete the files for good?", QuestionDialogButtons::YesNo)
+    ///     .remember_choice("empty-trash")
+    ///     .get_reply();
+    /// ```
+    pub struct JsonChoiceStore {
+        path: PathBuf,
+        choices: Mutex<BTreeMap<String, String>>,
+    }
+
+    impl JsonChoiceStore {
+        /// Opens the store kept in the file at `path`. The file doesn't need to exist yet.
+        ///
+        /// # Errors
+        /// Returns the I/O error if the file exists but can't be read, or an
+        /// [`io::ErrorKind::InvalidData`] error if it isn't a valid store.
+        pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            let path = path.as_ref().to_path_buf();
+            let choices = match std::fs::read(&path) {
+                Ok(data) => serde_json::from_slice(&data)?,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+                Err(e) => return Err(e),
+            };
+            Ok(Self {
+                path,
+                choices: Mutex::new(choices),
+            })
+        }
+
+        fn update(&self, change: impl FnOnce(&mut BTreeMap<String, String>)) {
+            let mut choices = self.choices.lock().unwrap_or_else(|e| e.into_inner());
+            change(&mut choices);
+            if let Ok(data) = serde_json::to_vec_pretty(&*choices) {
+                let _ = std::fs::write(&self.path, data);
+            }
+        }
+    }
+
+    impl ChoiceStore for JsonChoiceStore {
+        fn load(&self, key: &str) -> Option<Reply> {
+            let choices = self.choices.lock().unwrap_or_else(|e| e.into_inner());
+            match choices.get(key)?.as_str() {
+                "accepted" => Some(Reply::Accepted),
+                "rejected" => Some(Reply::Rejected),
+                _ => None,
+            }
+        }
+
+        fn store(&self, key: &str, reply: Reply) {
+            let value = match reply {
+                Reply::Accepted => "accepted",
+                Reply::Rejected => "rejected",
+                Reply::Cancelled | Reply::Dismissed => return,
+            };
+            self.update(|choices| {
+                choices.insert(key.to_owned(), value.to_owned());
+            });
+        }
+
+        fn forget(&self, key: &str) {
+            self.update(|choices| {
+                choices.remove(key);
+            });
+        }
+    }
+}
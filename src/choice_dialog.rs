@@ -0,0 +1,180 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{ButtonLayout, Error, QuestionDialog, QuestionDialogButtons, Reply};
+
+/// A question answered by picking one of up to three choices labelled by the application, such as
+/// "Save", "Discard" and "Cancel". Returns the index of the choice picked.
+///
+/// The choices take the place of the Yes, No and Cancel buttons of a question dialog, in that
+/// order, so they are laid out following the [`ButtonLayout`] like those buttons: the first
+/// choice is the affirmative one. It is also the default one, which Enter picks.
+///
+/// Only GTK and Windows dialogs, and the [`MockBackend`](crate::MockBackend), can show the
+/// labels. On Windows, the buttons keep the size of NvDialog's own, so keep the labels short.
+/// An underscore in a label makes the following character a keyboard mnemonic, as in
+/// [`DialogBox::set_accept_label`].
+///
+/// [`DialogBox::set_accept_label`]: crate::DialogBox::set_accept_label
+pub struct ChoiceDialog {
+    question: QuestionDialog,
+    choices: usize,
+}
+
+impl ChoiceDialog {
+    /// Creates a question with the given `title` and `msg`, answered with one of `choices`.
+    ///
+    /// # Errors
+    /// - [`Error::Unsupported`] on macOS and with the fallback backends other than the mock one,
+    ///   whose dialogs can't show the labels, and if there are more than three choices, as
+    ///   NvDialog's question dialogs have at most three buttons.
+    /// - [`Error::ParametersError`] if `choices` is empty or a choice contains a null byte.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::ChoiceDialog;
+    ///
+    /// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+    /// let mut dialog = ChoiceDialog::new(
+    ///     "Unsaved changes",
+    ///     "Save the changes to \"Report.odt\" before closing?",
+    ///     &["_Save", "_Discard", "_Cancel"],
+    /// )
+    /// .expect("Failed to create the dialog");
+    /// match dialog.get_choice() {
+    ///     Some(0) => { /* save, then close */ }
+    ///     Some(1) => { /* close */ }
+    ///     _ => { /* keep editing */ }
+    /// }
+    /// ```
+    pub fn new<S: AsRef<str>, C: AsRef<str>>(
+        title: S,
+        msg: S,
+        choices: &[C],
+    ) -> Result<Self, Error> {
+        /* The reply given by the button of each choice, in order. */
+        const REPLIES: [Reply; 3] = [Reply::Accepted, Reply::Rejected, Reply::Cancelled];
+
+        let buttons = match choices.len() {
+            0 => return Err(Error::ParametersError.context("A ChoiceDialog needs a choice")),
+            1 => QuestionDialogButtons::Yes,
+            2 => QuestionDialogButtons::YesNo,
+            3 => QuestionDialogButtons::YesNoCancel,
+            _ => return Err(Error::Unsupported.context("A ChoiceDialog has at most 3 choices")),
+        };
+        let labels = REPLIES
+            .into_iter()
+            .zip(choices)
+            .map(|(reply, choice)| (reply, String::from(choice.as_ref())))
+            .collect();
+        let question = QuestionDialog::new(title.as_ref(), msg.as_ref(), buttons)
+            .relabel(labels, Reply::Accepted)?;
+        Ok(Self {
+            question,
+            choices: choices.len(),
+        })
+    }
+
+    /// Lays the choices out following `layout`, in place of the one set with
+    /// [`crate::set_button_layout`].
+    pub fn button_layout(mut self, layout: ButtonLayout) -> Self {
+        self.question = self.question.button_layout(layout);
+        self
+    }
+
+    /// Shows the dialog and returns the index of the choice picked, or `None` if the dialog was
+    /// closed without picking one.
+    ///
+    /// NvDialog can't tell closing the dialog apart from its Cancel button, so with three choices,
+    /// closing the dialog picks the third one. Make it the choice that cancels.
+    pub fn get_choice(&mut self) -> Option<usize> {
+        match self.question.get_reply() {
+            Reply::Accepted => Some(0),
+            Reply::Rejected => Some(1),
+            Reply::Cancelled if self.choices == 3 => Some(2),
+            Reply::Cancelled | Reply::Dismissed => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "mock", not(feature = "strict-threading")))]
+mod tests {
+    use super::*;
+    use crate::{mock::any, MockBackend};
+
+    #[test]
+    fn replies_map_to_choice_indices() {
+        let mock = MockBackend::install();
+        mock.on_question(any()).times(1).reply(Reply::Rejected);
+        let mut dialog =
+            ChoiceDialog::new("Close", "Save first?", &["Save", "Discard", "Cancel"]).unwrap();
+        assert_eq!(dialog.get_choice(), Some(1));
+        mock.verify();
+    }
+
+    #[test]
+    fn cancel_is_the_third_choice_only_when_there_is_one() {
+        let mock = MockBackend::install();
+        mock.on_question(any()).reply(Reply::Cancelled);
+        let mut three =
+            ChoiceDialog::new("Close", "Save first?", &["Save", "Discard", "Cancel"]).unwrap();
+        assert_eq!(three.get_choice(), Some(2));
+        let mut two = ChoiceDialog::new("Delete", "Delete the file?", &["Delete", "Keep"]).unwrap();
+        assert_eq!(two.get_choice(), None);
+    }
+
+    #[test]
+    fn choices_are_shown_as_labels() {
+        let mock = MockBackend::install();
+        mock.on_question(any()).reply(Reply::Accepted);
+        ChoiceDialog::new("Close", "", &["_Save", "_Discard", "Cancel"])
+            .unwrap()
+            .button_layout(ButtonLayout::AffirmativeLeft)
+            .get_choice();
+        assert!(mock
+            .snapshot()
+            .unwrap()
+            .contains("[ Save ] [ Discard ] [ Cancel ]"));
+    }
+
+    #[test]
+    fn invalid_choices_are_rejected() {
+        MockBackend::install();
+        let four = ChoiceDialog::new("Pick", "Pick one", &["a", "b", "c", "d"]);
+        assert_eq!(
+            four.err().map(|e| e.root().clone()),
+            Some(Error::Unsupported)
+        );
+        let none = ChoiceDialog::new("Pick", "Pick one", &[] as &[&str]);
+        assert_eq!(
+            none.err().map(|e| e.root().clone()),
+            Some(Error::ParametersError)
+        );
+        let null = ChoiceDialog::new("Pick", "Pick one", &["a\0b"]);
+        assert_eq!(
+            null.err().map(|e| e.root().clone()),
+            Some(Error::ParametersError)
+        );
+    }
+}
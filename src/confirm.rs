@@ -0,0 +1,168 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Confirmation dialogs following each platform's conventions.
+
+use crate::{ButtonLayout, Error, QuestionDialog, QuestionDialogButtons, Reply};
+
+/// The answer to [`confirm_discard_changes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveDiscardCancel {
+    /// Save the document, then go on with closing it.
+    Save,
+    /// Close the document without saving it.
+    Discard,
+    /// Keep the document open.
+    Cancel,
+}
+
+/// Asks whether to save the unsaved changes to `document_name` before closing it, with the
+/// wording, button order and default button of the platform's guidelines, and returns the user's
+/// choice.
+///
+/// Saving is the default answer, the one Enter picks, and the buttons are laid out as follows:
+/// - On Windows: "Save", "Don't Save" and "Cancel", from left to right.
+/// - With GTK: "Close without Saving", "Cancel" and "Save", as in the GNOME guidelines.
+///
+/// The [`ButtonLayout`] set with [`set_button_layout`](crate::set_button_layout) doesn't apply,
+/// as the point of this dialog is to follow the platform. Closing the dialog without answering
+/// gives [`SaveDiscardCancel::Cancel`].
+///
+/// # Localization
+/// With the `i18n` feature, the texts are looked up through the registered
+/// [`Localizer`](crate::Localizer) under the keys `nvdialog-unsaved-changes-title`,
+/// `nvdialog-unsaved-changes-message`, `nvdialog-unsaved-changes-save`,
+/// `nvdialog-unsaved-changes-discard` and `nvdialog-unsaved-changes-cancel`, with the
+/// `document` argument set to `document_name`. Keys without a translation keep the English text.
+///
+/// # Errors
+/// Returns [`Error::Unsupported`] where the buttons can't be labelled: on macOS, and with the
+/// fallback backends other than the [`MockBackend`](crate::MockBackend).
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{confirm_discard_changes, SaveDiscardCancel};
+///
+/// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+/// match confirm_discard_changes("Report.odt").expect("Failed to ask about the changes") {
+///     SaveDiscardCancel::Save => { /* save, then close */ }
+///     SaveDiscardCancel::Discard => { /* close */ }
+///     SaveDiscardCancel::Cancel => { /* keep editing */ }
+/// }
+/// ```
+pub fn confirm_discard_changes<S: AsRef<str>>(
+    document_name: S,
+) -> Result<SaveDiscardCancel, Error> {
+    let name = document_name.as_ref();
+    let text = |key: &str, english: &str| {
+        localized(&format!("nvdialog-unsaved-changes-{key}"), name, english)
+    };
+    let (message, [save, discard, cancel]) = if cfg!(target_os = "windows") {
+        (
+            format!("Do you want to save changes to {name}?"),
+            ["_Save", "Do_n't Save", "Cancel"],
+        )
+    } else {
+        (
+            format!(
+                "Save changes to document \u{201c}{name}\u{201d} before closing?\n\n\
+                 If you don't save, changes will be permanently lost."
+            ),
+            ["_Save", "Close _without Saving", "_Cancel"],
+        )
+    };
+    let labels = vec![
+        (Reply::Accepted, text("save", save)),
+        (Reply::Rejected, text("discard", discard)),
+        (Reply::Cancelled, text("cancel", cancel)),
+    ];
+    let mut dialog = QuestionDialog::new(
+        text("title", "Unsaved changes"),
+        text("message", &message),
+        QuestionDialogButtons::YesNoCancel,
+    )
+    .button_layout(ButtonLayout::Platform)
+    .relabel(labels, Reply::Accepted)?;
+    Ok(match dialog.get_reply() {
+        Reply::Accepted => SaveDiscardCancel::Save,
+        Reply::Rejected => SaveDiscardCancel::Discard,
+        Reply::Cancelled | Reply::Dismissed => SaveDiscardCancel::Cancel,
+    })
+}
+
+/// Returns the translation of `key` for the document `name`, or `english` if there is none.
+#[cfg_attr(not(feature = "i18n"), allow(unused_variables))]
+fn localized(key: &str, name: &str, english: &str) -> String {
+    #[cfg(feature = "i18n")]
+    if let Some(text) = crate::i18n::try_localize(key, &[("document", name)]) {
+        return text;
+    }
+    String::from(english)
+}
+
+#[cfg(all(test, feature = "mock", not(feature = "strict-threading")))]
+mod tests {
+    use super::*;
+    use crate::{mock::any, MockBackend};
+
+    #[test]
+    fn buttons_follow_the_platform() {
+        let mock = MockBackend::install();
+        mock.on_question(any()).reply(Reply::Rejected);
+        let answer = confirm_discard_changes("Report.odt");
+        assert_eq!(answer, Ok(SaveDiscardCancel::Discard));
+        let expected = match cfg!(target_os = "windows") {
+            true => "[ Save ] [ Don't Save ] [ Cancel ]",
+            false => "[ Close without Saving ] [ Cancel ] [ Save ]",
+        };
+        let snapshot = mock.snapshot().unwrap();
+        assert!(snapshot.contains(expected), "{snapshot}");
+        assert_eq!(mock.dialogs()[0].title, "Unsaved changes");
+    }
+
+    #[cfg(feature = "i18n")]
+    #[test]
+    fn texts_are_localized() {
+        use std::collections::HashMap;
+
+        let mock = MockBackend::install();
+        mock.on_question(any()).reply(Reply::Accepted);
+        crate::set_localizer(HashMap::from([
+            (
+                String::from("nvdialog-unsaved-changes-title"),
+                String::from("Ungespeicherte Änderungen"),
+            ),
+            (
+                String::from("nvdialog-unsaved-changes-message"),
+                String::from("{document} speichern?"),
+            ),
+        ]));
+        let answer = confirm_discard_changes("Bericht.odt");
+        crate::clear_localizer();
+        assert_eq!(answer, Ok(SaveDiscardCancel::Save));
+        let dialog = &mock.dialogs()[0];
+        assert_eq!(dialog.title, "Ungespeicherte Änderungen");
+        assert_eq!(dialog.message, "Bericht.odt speichern?");
+    }
+}
@@ -0,0 +1,257 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::DialogSpec;
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The kind, title and message of a dialog.
+type Key = (String, String, String);
+
+struct Seen {
+    shown_at: Instant,
+    last_seen: Instant,
+    suppressed: u64,
+}
+
+struct State {
+    window: Option<Duration>,
+    seen: BTreeMap<Key, Seen>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    window: None,
+    seen: BTreeMap::new(),
+});
+
+/// Enables suppressing duplicate dialogs for `window`, or disables it with `None` (the default).
+///
+/// Once enabled, a dialog box, question dialog or file dialog with the same kind, title and
+/// message as one shown less than `window` ago is suppressed, as if
+/// [`set_dialog_interceptor`](crate::set_dialog_interceptor) returned
+/// [`InterceptAction::Suppress`](crate::InterceptAction::Suppress) for it. This keeps error
+/// loops that fire the same dialog hundreds of times from flooding the user. When the dialog is
+/// shown again after the window, its message mentions how many times it was suppressed. A
+/// dialog that isn't repeated for a whole window is forgotten, along with that count.
+///
+/// Duplicates are detected across all threads. Disabling deduplication forgets every dialog
+/// seen so far.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// nvdialog_rs::set_dedupe_window(Some(Duration::from_secs(30)));
+/// ```
+pub fn set_dedupe_window(window: Option<Duration>) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.window = window;
+    if window.is_none() {
+        state.seen.clear();
+    }
+}
+
+fn key(spec: &DialogSpec) -> Key {
+    match spec {
+        DialogSpec::Message(spec) => (
+            format!("message:{}", spec.kind),
+            spec.title.clone(),
+            spec.message.clone(),
+        ),
+        DialogSpec::Question(spec) => (
+            format!("question:{:?}", spec.buttons),
+            spec.title.clone(),
+            spec.message.clone(),
+        ),
+        DialogSpec::File(spec) => (
+            format!("file:{:?}", spec.mode),
+            spec.title.clone(),
+            String::new(),
+        ),
+    }
+}
+
+/// Returns `true` if deduplication is enabled.
+pub(crate) fn enabled() -> bool {
+    STATE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .window
+        .is_some()
+}
+
+/// Returns `true` if `spec` duplicates a recently shown dialog and must be suppressed.
+/// Otherwise, records it as shown and mentions the suppressed duplicates in its message.
+pub(crate) fn is_duplicate(spec: &mut DialogSpec) -> bool {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.is_duplicate(spec, Instant::now())
+}
+
+impl State {
+    /// Like [`is_duplicate`], with `now` as the current time.
+    fn is_duplicate(&mut self, spec: &mut DialogSpec, now: Instant) -> bool {
+        let Some(window) = self.window else {
+            return false;
+        };
+        /*
+         * Every dialog not seen for a whole window is dropped, suppressed or not, so the map only
+         * holds what was seen in the last window. A pending count is reported if the dialog comes
+         * back within a window of its last duplicate, and lost otherwise.
+         */
+        self.seen
+            .retain(|_, seen| now.duration_since(seen.last_seen) < window);
+
+        let key = key(spec);
+        let suppressed = match self.seen.get_mut(&key) {
+            Some(seen) if now.duration_since(seen.shown_at) < window => {
+                seen.suppressed += 1;
+                seen.last_seen = now;
+                return true;
+            }
+            Some(seen) => seen.suppressed,
+            None => 0,
+        };
+        self.seen.insert(
+            key,
+            Seen {
+                shown_at: now,
+                last_seen: now,
+                suppressed: 0,
+            },
+        );
+        note_suppressed(spec, suppressed);
+        false
+    }
+}
+
+/// Mentions in the message of `spec` that it was suppressed `suppressed` times, if it was.
+fn note_suppressed(spec: &mut DialogSpec, suppressed: u64) {
+    if suppressed > 0 {
+        let note = format!("\n\n(This was repeated {suppressed} more times.)");
+        match spec {
+            DialogSpec::Message(spec) => spec.message.push_str(&note),
+            DialogSpec::Question(spec) => spec.message.push_str(&note),
+            DialogSpec::File(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DialogType, MessageSpec};
+
+    const WINDOW: Duration = Duration::from_secs(30);
+
+    fn deduping() -> State {
+        State {
+            window: Some(WINDOW),
+            seen: BTreeMap::new(),
+        }
+    }
+
+    fn message(text: &str) -> DialogSpec {
+        DialogSpec::Message(MessageSpec {
+            title: String::from("Error"),
+            message: String::from(text),
+            kind: DialogType::Error,
+            accept_label: None,
+        })
+    }
+
+    fn message_text(spec: &DialogSpec) -> &str {
+        match spec {
+            DialogSpec::Message(spec) => &spec.message,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn duplicates_within_the_window_are_suppressed_and_counted() {
+        let mut state = deduping();
+        let start = Instant::now();
+        assert!(!state.is_duplicate(&mut message("Disk full"), start));
+        assert!(state.is_duplicate(&mut message("Disk full"), start + WINDOW / 2));
+        assert!(state.is_duplicate(&mut message("Disk full"), start + WINDOW / 2));
+        assert!(!state.is_duplicate(&mut message("Out of memory"), start + WINDOW / 2));
+
+        let mut again = message("Disk full");
+        assert!(!state.is_duplicate(&mut again, start + WINDOW));
+        assert_eq!(
+            message_text(&again),
+            "Disk full\n\n(This was repeated 2 more times.)"
+        );
+        let mut once_more = message("Disk full");
+        assert!(!state.is_duplicate(&mut once_more, start + WINDOW * 2));
+        assert_eq!(message_text(&once_more), "Disk full");
+    }
+
+    #[test]
+    fn dialogs_not_seen_for_a_window_are_forgotten() {
+        let mut state = deduping();
+        let start = Instant::now();
+        for i in 0..100 {
+            let mut spec = message(&format!("Failure {i}"));
+            assert!(!state.is_duplicate(&mut spec, start));
+            assert!(state.is_duplicate(&mut spec, start));
+        }
+        assert_eq!(state.seen.len(), 100);
+
+        let mut later = message("Failure 0");
+        assert!(!state.is_duplicate(&mut later, start + WINDOW));
+        assert_eq!(state.seen.len(), 1);
+        assert_eq!(message_text(&later), "Failure 0");
+    }
+
+    #[test]
+    fn a_late_repeat_of_a_suppressed_dialog_reports_the_count() {
+        let mut state = deduping();
+        let start = Instant::now();
+        assert!(!state.is_duplicate(&mut message("Disk full"), start));
+        assert!(state.is_duplicate(
+            &mut message("Disk full"),
+            start + WINDOW - Duration::from_secs(1)
+        ));
+
+        let mut later = message("Disk full");
+        assert!(!state.is_duplicate(&mut later, start + WINDOW + Duration::from_secs(10)));
+        assert_eq!(
+            message_text(&later),
+            "Disk full\n\n(This was repeated 1 more times.)"
+        );
+    }
+
+    #[test]
+    fn nothing_is_suppressed_when_disabled() {
+        let mut state = deduping();
+        state.window = None;
+        let now = Instant::now();
+        assert!(!state.is_duplicate(&mut message("Disk full"), now));
+        assert!(!state.is_duplicate(&mut message("Disk full"), now));
+        assert!(state.seen.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! The answer given to questions nobody can see.
+
+use crate::Reply;
+use std::sync::Mutex;
+
+/// The reply questions resolve to when they are answered without the user: with the `noop`
+/// feature, for [`AUTO_REPLY_ENV`](crate::AUTO_REPLY_ENV) answers that don't name a reply, when
+/// the question was suppressed (see [`InterceptAction::Suppress`](crate::InterceptAction::Suppress)), and
+/// when NvDialog couldn't be initialized and no fallback backend is available.
+///
+/// Unattended runs should make this an explicit decision instead of relying on each of these
+/// cases: a confirmation to overwrite data is usually safer rejected, one to continue an update
+/// usually accepted. Set it process-wide with [`set_default_answer`], or for a single question
+/// with [`QuestionDialog::default_answer`](crate::QuestionDialog::default_answer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultAnswer(pub Reply);
+
+impl Default for DefaultAnswer {
+    /// [`Reply::Cancelled`], as if the user cancelled the question.
+    fn default() -> Self {
+        Self(Reply::Cancelled)
+    }
+}
+
+static DEFAULT_ANSWER: Mutex<DefaultAnswer> = Mutex::new(DefaultAnswer(Reply::Cancelled));
+
+/// Sets the [`DefaultAnswer`] of every question that doesn't set its own. Defaults to
+/// [`Reply::Cancelled`].
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DefaultAnswer, Reply};
+///
+/// // Nightly jobs must never overwrite anything without a human confirming it.
+/// nvdialog_rs::set_default_answer(DefaultAnswer(Reply::Rejected));
+/// ```
+pub fn set_default_answer(answer: DefaultAnswer) {
+    *DEFAULT_ANSWER.lock().unwrap_or_else(|e| e.into_inner()) = answer;
+}
+
+/// Returns the process-wide [`DefaultAnswer`].
+pub fn default_answer() -> DefaultAnswer {
+    *DEFAULT_ANSWER.lock().unwrap_or_else(|e| e.into_inner())
+}
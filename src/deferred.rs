@@ -0,0 +1,149 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::{Rc, Weak},
+    time::{Duration, Instant},
+};
+
+type Job = Box<dyn FnOnce()>;
+
+thread_local! {
+    static QUEUE: RefCell<VecDeque<Job>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// A dialog that is created right away but only shown on the next call to [`pump_events`].
+///
+/// Applications with their own render loop (games, `wgpu` or Bevy apps...) can't show a native
+/// dialog from the middle of a frame without leaving the frame half done. A `DeferredDialog` is
+/// queued instead, and shown at a well defined point of the loop, between two frames, when
+/// [`pump_events`] is called. The result is then available from [`DeferredDialog::take`].
+///
+/// Native dialogs are modal, so the frame in which one is shown still waits for it to be closed.
+/// Dropping a `DeferredDialog` before it was shown cancels it.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{DeferredDialog, FileDialog, FileDialogType};
+/// use std::time::Duration;
+///
+/// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+/// let save = DeferredDialog::new(|| {
+///     FileDialog::new("Save game", FileDialogType::SaveFile, None::<Vec<&str>>).retrieve_filename()
+/// });
+///
+/// loop {
+///     // Update and render the frame...
+///     nvdialog_rs::pump_events(Duration::from_millis(2));
+///     if let Some(path) = save.take() {
+///         println!("Saving to {path:?}");
+///         break;
+///     }
+/// }
+/// ```
+pub struct DeferredDialog<T> {
+    result: Rc<RefCell<Option<T>>>,
+}
+
+impl<T: 'static> DeferredDialog<T> {
+    /// Queues `show` to run on the next call to [`pump_events`] in this thread. `show` should
+    /// create and show the dialog, and return its result.
+    pub fn new<F: FnOnce() -> T + 'static>(show: F) -> Self {
+        let result = Rc::new(RefCell::new(None));
+        let slot: Weak<RefCell<Option<T>>> = Rc::downgrade(&result);
+        QUEUE.with(|queue| {
+            queue.borrow_mut().push_back(Box::new(move || {
+                /* The `DeferredDialog` was dropped, so nobody wants the dialog anymore. */
+                if let Some(slot) = slot.upgrade() {
+                    *slot.borrow_mut() = Some(show());
+                }
+            }))
+        });
+        Self { result }
+    }
+}
+
+impl<T> DeferredDialog<T> {
+    /// Returns the result of the dialog if it has been shown, leaving `None` in its place.
+    pub fn take(&self) -> Option<T> {
+        self.result.borrow_mut().take()
+    }
+
+    /// Returns `true` if the dialog has been shown and its result hasn't been taken yet.
+    pub fn is_ready(&self) -> bool {
+        self.result.borrow().is_some()
+    }
+}
+
+/// Processes pending toolkit events for at most `budget`, then shows the next queued
+/// [`DeferredDialog`], if any. Returns the number of dialogs still waiting to be shown.
+///
+/// Notifications scheduled with [`crate::Notification::send_after`] are sent from here too, by
+/// the first call made after they are due. With the `log` feature, records queued by the
+/// `DialogLogger` are shown from here as well, one per call and before queued dialogs.
+///
+/// Call this once per frame from the thread that initialized NvDialog. Only one dialog is shown
+/// per call, so the application gets to render a frame between two dialogs.
+///
+/// On Linux, GTK's events are not processed by the application's own event loop, so without
+/// this function windows of closed dialogs stay on screen until the next dialog is shown. The
+/// `budget` bounds the time spent on them, so a busy event queue can't stall the frame. On
+/// other platforms the application's event loop already handles the toolkit's events and
+/// `budget` is unused.
+pub fn pump_events(budget: Duration) -> usize {
+    crate::init::check_thread();
+    process_toolkit_events(Instant::now() + budget);
+    crate::schedule::send_due();
+    #[cfg(feature = "log")]
+    if crate::logger::show_next() {
+        return QUEUE.with(|queue| queue.borrow().len());
+    }
+
+    if let Some(job) = QUEUE.with(|queue| queue.borrow_mut().pop_front()) {
+        job();
+    }
+    QUEUE.with(|queue| queue.borrow().len())
+}
+
+#[cfg(target_os = "linux")]
+fn process_toolkit_events(deadline: Instant) {
+    use std::ffi::c_int;
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_events_pending() -> c_int;
+        fn gtk_main_iteration_do(blocking: c_int) -> c_int;
+    }
+
+    if !crate::init::native_available() {
+        return;
+    }
+    while Instant::now() < deadline && unsafe { gtk_events_pending() } != 0 {
+        unsafe { gtk_main_iteration_do(0) };
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_toolkit_events(_deadline: Instant) {}
@@ -22,19 +22,30 @@
  * IN THE SOFTWARE.
  */
 
-use std::ffi::{c_void, CString};
 use nvdialog_sys::ffi::*;
-use crate::Error;
+use std::ffi::{c_void, OsStr};
+use crate::{
+    util::{free_raw, native_mnemonic, with_c_strings, with_owner},
+    CancelToken, DialogInfo, DialogKind, Error, MessageSpec, Object,
+};
 
 /// An enumeration of the different types of dialogs that can be created.
 ///
 /// This enum is used to specify the type of dialog to be created when calling
 /// the appropriate dialog creation functions. The different variants represent
 /// different types of dialogs that can be used to communicate with the user.
+///
+/// The discriminants are NvDialog's own `NvdDialogType` constants, so the enum converts to and
+/// from `u32` (see the `From` and `TryFrom` implementations). It displays as `simple`, `warning`
+/// or `error`, the same names the `serde` feature uses, so it can round-trip through logs and
+/// configuration files.
+#[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum DialogType {
     /// A simple dialog box with no specific type.
-    Simple,
+    Simple = 0xff,
     /// A warning dialog box, indicating that the user should be cautious or take
     /// extra care when performing the action.
     Warning,
@@ -43,6 +54,37 @@ pub enum DialogType {
     Error,
 }
 
+impl From<DialogType> for u32 {
+    fn from(dialog_type: DialogType) -> u32 {
+        dialog_type as u32
+    }
+}
+
+impl TryFrom<u32> for DialogType {
+    type Error = Error;
+
+    /// Converts an `NvdDialogType` constant back into a `DialogType`, returning
+    /// [`Error::ParametersError`] for values that aren't one.
+    fn try_from(value: u32) -> Result<Self, Error> {
+        match value {
+            v if v == DialogType::Simple as u32 => Ok(DialogType::Simple),
+            v if v == DialogType::Warning as u32 => Ok(DialogType::Warning),
+            v if v == DialogType::Error as u32 => Ok(DialogType::Error),
+            _ => Err(Error::ParametersError),
+        }
+    }
+}
+
+impl std::fmt::Display for DialogType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Simple => "simple",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
 /// A struct representing a dialog box.
 ///
 /// This struct provides a simple interface for creating and showing different types of dialog
@@ -67,6 +109,13 @@ pub enum DialogType {
 /// Corresponds to `NvdDialogBox`.
 pub struct DialogBox {
     raw: *mut NvdDialogBox,
+    title: String,
+    msg: String,
+    dialog_type: DialogType,
+    alert_sound: Option<bool>,
+    owner: *mut c_void,
+    suppressed: bool,
+    cancel: Option<CancelToken>,
 }
 
 impl DialogBox {
@@ -83,36 +132,181 @@ impl DialogBox {
     /// # Returns
     ///
     /// Returns `Ok(DialogBox)` if the dialog box was successfully created, otherwise
-    /// returns `Err(Error)` with the error converted from NvDialog's error code.
+    /// returns `Err(Error)` with the error converted from NvDialog's error code. If
+    /// [`crate::init`] hasn't been called in this thread, [`Error::NotYetInitialized`] is
+    /// returned without calling into NvDialog at all.
     ///
-    /// # Panics
-    /// This function will panic if `CString::new` fails to convert the given `title` or `msg`
-    /// to a null-terminated byte string.
+    /// If the given `title` or `msg` contain a null byte, they can't be converted to C strings and
+    /// [`Error::ParametersError`] is returned.
+    ///
+    /// The dialog goes through the interceptor registered with
+    /// [`set_dialog_interceptor`](crate::set_dialog_interceptor) first, if any.
     pub fn new<S: AsRef<str>>(title: S, msg: S, dialog_type: DialogType) -> Result<Self, Error> {
-        let _type = match dialog_type {
-            DialogType::Simple => 0xff,
-            DialogType::Warning => 0xff + 1,
-            DialogType::Error => 0xff + 2,
+        crate::init::check_thread();
+        let (spec, suppressed) = crate::intercept::intercept(MessageSpec {
+            title: String::from(title.as_ref()),
+            message: String::from(msg.as_ref()),
+            kind: dialog_type,
+            accept_label: None,
+        });
+        let mut dialog = Self {
+            raw: std::ptr::null_mut(),
+            title: spec.title,
+            msg: spec.message,
+            dialog_type: spec.kind,
+            alert_sound: None,
+            owner: std::ptr::null_mut(),
+            suppressed,
+            cancel: None,
         };
 
-        let title = CString::new(title.as_ref()).expect("CString::new error");
-        let msg = CString::new(msg.as_ref()).expect("CString::new error");
+        #[cfg(feature = "auto-reply")]
+        if crate::auto_reply_enabled() {
+            return Ok(dialog);
+        }
 
-        let raw = unsafe {
-            let raw = nvd_dialog_box_new(title.as_ptr(), msg.as_ptr(), _type);
-            if raw.is_null() {
-                return Err(Error::from(nvd_get_error() as i32));
-            }
-            raw
-        };
+        crate::init::ensure_initialized()?;
+        if suppressed || !crate::init::native_available() {
+            return Ok(dialog);
+        }
+
+        dialog.raw = crate::ffi_check::track(with_c_strings(
+            [dialog.title.as_str(), dialog.msg.as_str()],
+            |[t, m]| unsafe { nvd_dialog_box_new(t, m, dialog.dialog_type.into()) },
+        )?);
+        if dialog.raw.is_null() {
+            return Err(Error::last_native().context("Failed to create dialog box"));
+        }
+        if let Some(label) = spec.accept_label {
+            dialog.set_accept_label(label)?;
+        }
+        Ok(dialog)
+    }
+
+    /// Creates a new `DialogBox` from OS strings, such as file names or paths taken from the
+    /// filesystem. The toolkits only display Unicode text, so anything in `title` or `msg` that
+    /// isn't valid Unicode is shown as `U+FFFD`.
+    ///
+    /// # Errors
+    /// Same as [`DialogBox::new`].
+    pub fn from_os_str<S: AsRef<OsStr>>(
+        title: S,
+        msg: S,
+        dialog_type: DialogType,
+    ) -> Result<Self, Error> {
+        Self::new(
+            title.as_ref().to_string_lossy(),
+            msg.as_ref().to_string_lossy(),
+            dialog_type,
+        )
+    }
+
+    /// Creates a new `DialogBox` whose title and message are resolved from message keys through
+    /// the registered [`Localizer`](crate::Localizer), with `args` substituted in.
+    ///
+    /// Keys without a translation are shown as-is. See [`crate::localize`] for details.
+    #[cfg(feature = "i18n")]
+    pub fn new_localized(
+        title_key: &str,
+        msg_key: &str,
+        args: &[(&str, &str)],
+        dialog_type: DialogType,
+    ) -> Result<Self, Error> {
+        Self::new(
+            crate::localize(title_key, args),
+            crate::localize(msg_key, args),
+            dialog_type,
+        )
+    }
+
+    /// Sets the text of the button accepting the dialog.
+    ///
+    /// An underscore in `label` makes the following character a keyboard mnemonic, so `"_Save"`
+    /// can be pressed with Alt+S. Use two underscores for a literal one. The label is converted
+    /// to each platform's own syntax (`&Save` on Windows), and mnemonics are left out on macOS,
+    /// which doesn't have them.
+    ///
+    /// # Errors
+    /// Returns [`Error::ParametersError`] if `label` contains a null byte.
+    pub fn set_accept_label<S: AsRef<str>>(&mut self, label: S) -> Result<(), Error> {
+        crate::init::check_thread();
+        if self.raw.is_null() {
+            return Ok(());
+        }
+        with_c_strings([&native_mnemonic(label.as_ref())], |[label]| unsafe {
+            nvd_dialog_box_set_accept_text(self.raw, label);
+        })
+    }
 
-        Ok(Self { raw })
+    /// Overrides the global alert sound setting (see [`crate::set_alert_sound`]) for this dialog.
+    ///
+    /// `Some(true)` plays the system alert sound when the dialog is shown, `Some(false)` keeps it
+    /// silent and `None` goes back to the global setting. Has no effect on dialogs of type
+    /// [`DialogType::Simple`].
+    pub fn set_alert_sound(&mut self, enabled: Option<bool>) {
+        self.alert_sound = enabled;
+    }
+
+    /// Lets `token` close this dialog box while it is shown. Pass `None` to remove the token. See
+    /// [`CancelToken`].
+    pub fn set_cancel_token(&mut self, token: Option<CancelToken>) {
+        self.cancel = token;
+    }
+
+    /// Makes the window with the handle `hwnd` the owner of this dialog box, so that it stays on top
+    /// of the application's window and doesn't get its own taskbar entry. Pass a null pointer to
+    /// go back to NvDialog's global parent window.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_set_parent`, which is called right before the dialog box is shown and
+    /// undone afterwards.
+    #[cfg(target_os = "windows")]
+    pub fn set_owner_hwnd(&mut self, hwnd: *mut c_void) {
+        self.owner = hwnd;
+    }
+
+    /// Returns the toolkit's own object behind this dialog box: a `GtkWidget*` with GTK, an `HWND`
+    /// on Windows or an `NSWindow*` on macOS. Returns a null pointer if no native dialog box was
+    /// created, or if the backend doesn't create the window before it is shown.
+    ///
+    /// This is unsupported territory, meant for tweaking properties this crate has no API for.
+    ///
+    /// # Safety
+    /// The pointer is owned by NvDialog and invalid once the dialog box is dropped. Changing the
+    /// object in ways NvDialog doesn't expect (destroying it, replacing its contents...) is
+    /// undefined behavior.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_dialog_box_get_raw`.
+    pub unsafe fn native_widget(&self) -> *mut c_void {
+        if self.raw.is_null() {
+            return std::ptr::null_mut();
+        }
+        crate::ffi_check::check(self.raw);
+        nvd_dialog_box_get_raw(self.raw)
+    }
+
+    /// Returns the title of the dialog box.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the message displayed in the dialog box.
+    pub fn message(&self) -> &str {
+        &self.msg
     }
 
-    pub fn set_accept_label<S: AsRef<str>>(&mut self, label: S) {
-        let label = CString::new(label.as_ref()).expect("CString::new error");
-        unsafe {
-            nvd_dialog_box_set_accept_text(self.raw, label.as_ptr());
+    /// Returns the kind of the dialog box, which is always [`DialogKind::DialogBox`].
+    pub fn kind(&self) -> DialogKind {
+        DialogKind::DialogBox(self.dialog_type)
+    }
+
+    /// Returns a [`DialogInfo`] describing what the dialog box is going to display.
+    pub fn info(&self) -> DialogInfo {
+        DialogInfo {
+            kind: self.kind(),
+            title: self.title.clone(),
+            message: self.msg.clone(),
         }
     }
 
@@ -122,25 +316,60 @@ impl DialogBox {
     /// It should be called after setting any necessary options and buttons on the dialog.
     /// This function is unsafe, because it uses FFI to call C code that might not be safe.
     pub fn show(&mut self) {
-        unsafe {
-            nvd_show_dialog(self.raw);
+        crate::init::check_thread();
+        if self.suppressed {
+            return;
         }
+        #[cfg(feature = "recorder")]
+        crate::recorder::record("dialog_box", &self.title, &self.msg, None);
+        crate::instrument::instrument(
+            || self.info(),
+            || match &self.cancel {
+                Some(token) => {
+                    let showing = crate::cancel::showing(unsafe { self.native_widget() });
+                    token.run(showing, || self.display());
+                }
+                None => self.display(),
+            },
+            |_| None,
+        );
     }
 
-    /// Returns the raw pointer to the dialog box created
-    /// from NvDialog directly.
-    ///
-    /// Marked as `unsafe` because accessing the internal struct is not officially
-    /// supported by NvDialog and may cause race conditions.
-    unsafe fn get_raw(&mut self) -> *mut NvdDialogBox {
+    fn display(&self) {
+        if self.raw.is_null() {
+            if let Some(backend) = crate::backend::fallback() {
+                backend.message(&self.title, &self.msg, self.dialog_type);
+            }
+            return;
+        }
+        crate::sound::play_alert(self.dialog_type, self.alert_sound);
+        with_owner(self.owner, || unsafe {
+            nvd_show_dialog(self.raw);
+        });
+    }
+}
+
+impl Object for DialogBox {
+    type NativeType = NvdDialogBox;
+    type ReturnValue = ();
+
+    fn get_raw(&self) -> *mut NvdDialogBox {
+        crate::ffi_check::check(self.raw);
         self.raw
     }
+
+    fn show(&mut self) -> Result<(), Error> {
+        DialogBox::show(self);
+        Ok(())
+    }
+
+    fn free(&mut self) {
+        unsafe { free_raw(std::mem::replace(&mut self.raw, std::ptr::null_mut())) };
+    }
 }
 
 impl Drop for DialogBox {
     fn drop(&mut self) {
-        unsafe {
-            nvd_free_object(self.raw as *mut c_void);
-        }
+        unsafe { free_raw(self.raw) };
     }
 }
@@ -0,0 +1,66 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{DialogType, FileDialogType, NotificationKind, QuestionDialogButtons};
+
+/// The kind of a dialog, along with the type-specific options it was created with.
+///
+/// Returned by the `kind()` method every dialog type has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogKind {
+    /// A [`DialogBox`](crate::DialogBox) of the given type.
+    DialogBox(DialogType),
+    /// A [`QuestionDialog`](crate::QuestionDialog) with the given buttons.
+    Question(QuestionDialogButtons),
+    /// A [`FileDialog`](crate::FileDialog) in the given mode.
+    File(FileDialogType),
+    /// An [`AboutDialog`](crate::AboutDialog).
+    About,
+    /// A [`Notification`](crate::Notification) of the given kind.
+    Notification(NotificationKind),
+}
+
+/// A snapshot of what a dialog is going to display.
+///
+/// Every dialog type can produce one through its `info()` method, which is useful for wrappers
+/// and loggers that need to inspect a dialog without knowing its concrete type.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DialogBox, DialogKind, DialogType};
+///
+/// let dialog = DialogBox::new("Title", "Message", DialogType::Warning).unwrap();
+/// let info = dialog.info();
+/// assert_eq!(info.kind, DialogKind::DialogBox(DialogType::Warning));
+/// println!("About to show '{}': {}", info.title, info.message);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogInfo {
+    /// The kind of the dialog.
+    pub kind: DialogKind,
+    /// The title of the dialog. For about dialogs, this is the application name.
+    pub title: String,
+    /// The message of the dialog. File dialogs have no message, so this is empty for them.
+    pub message: String,
+}
@@ -0,0 +1,139 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+/// The direction text and layouts flow in, set with [`set_text_direction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    /// Picks the direction of the user's language.
+    #[default]
+    Auto,
+    /// Left-to-right, as in most languages.
+    LeftToRight,
+    /// Right-to-left, as in Arabic, Hebrew or Persian. Text is aligned to the right and the order
+    /// of the buttons is mirrored.
+    RightToLeft,
+}
+
+impl TextDirection {
+    /// Returns the direction `self` stands for: [`TextDirection::Auto`] is replaced with the
+    /// direction of the user's language, the others are returned as-is.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto if locale_is_rtl() => Self::RightToLeft,
+            Self::Auto => Self::LeftToRight,
+            direction => direction,
+        }
+    }
+}
+
+/// Sets the direction of the dialogs created from now on.
+///
+/// Applications translated into right-to-left languages should call this after [`crate::init`],
+/// either with the direction of the language they display, or with [`TextDirection::Auto`] to
+/// follow the user's locale.
+///
+/// # Platform notes
+/// - On Linux, this sets GTK's default widget direction, which affects every GTK widget created
+///   afterwards, not only dialogs.
+/// - On Windows, this sets the default layout of the process, so windows the application creates
+///   afterwards are mirrored as well.
+/// - On macOS this does nothing, as AppKit always follows the user's language.
+pub fn set_text_direction(direction: TextDirection) {
+    if !crate::init::NATIVE_CODE {
+        return;
+    }
+    set_native_direction(direction.resolve() == TextDirection::RightToLeft);
+}
+
+/// Languages written from right to left, by ISO 639 code.
+const RTL_LANGUAGES: [&str; 10] = ["ar", "arc", "dv", "fa", "he", "ku", "ps", "sd", "ur", "yi"];
+
+#[cfg(not(target_os = "windows"))]
+fn locale_is_rtl() -> bool {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .find(|locale| !locale.is_empty())
+        .unwrap_or_default();
+    /* Locales look like `ar_EG.UTF-8@modifier`. */
+    let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+    RTL_LANGUAGES.contains(&language)
+}
+
+#[cfg(target_os = "windows")]
+fn locale_is_rtl() -> bool {
+    use std::ffi::c_int;
+
+    const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserDefaultLocaleName(name: *mut u16, length: c_int) -> c_int;
+    }
+
+    let mut name = [0u16; LOCALE_NAME_MAX_LENGTH];
+    let length = unsafe { GetUserDefaultLocaleName(name.as_mut_ptr(), name.len() as c_int) };
+    if length <= 1 {
+        return false;
+    }
+    /* Locale names look like `ar-EG`, and the length includes the terminator. */
+    let locale = String::from_utf16_lossy(&name[..length as usize - 1]);
+    let language = locale.split('-').next().unwrap_or_default();
+    RTL_LANGUAGES.contains(&language)
+}
+
+#[cfg(target_os = "linux")]
+fn set_native_direction(rtl: bool) {
+    use std::ffi::c_int;
+
+    const GTK_TEXT_DIR_LTR: c_int = 1;
+    const GTK_TEXT_DIR_RTL: c_int = 2;
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_widget_set_default_direction(direction: c_int);
+    }
+
+    unsafe {
+        gtk_widget_set_default_direction(if rtl {
+            GTK_TEXT_DIR_RTL
+        } else {
+            GTK_TEXT_DIR_LTR
+        })
+    };
+}
+
+#[cfg(target_os = "windows")]
+fn set_native_direction(rtl: bool) {
+    const LAYOUT_RTL: u32 = 0x1;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn SetProcessDefaultLayout(layout: u32) -> i32;
+    }
+
+    unsafe { SetProcessDefaultLayout(if rtl { LAYOUT_RTL } else { 0 }) };
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn set_native_direction(_rtl: bool) {}
@@ -0,0 +1,117 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+#[cfg(target_os = "macos")]
+mod ffi {
+    use std::ffi::{c_int, c_void};
+
+    #[repr(C)]
+    pub struct DispatchQueue {
+        _private: [u8; 0],
+    }
+
+    #[link(name = "System", kind = "dylib")]
+    extern "C" {
+        /* `dispatch_get_main_queue()` is a macro returning the address of this. */
+        pub static _dispatch_main_q: DispatchQueue;
+        pub fn dispatch_async_f(
+            queue: *const DispatchQueue,
+            context: *mut c_void,
+            work: extern "C" fn(*mut c_void),
+        );
+        pub fn pthread_main_np() -> c_int;
+    }
+}
+
+/// Runs `f` on the main thread and returns its result, blocking the calling thread until it
+/// has finished.
+///
+/// macOS doesn't allow any UI work outside the main thread, so dialogs created from worker
+/// threads must go through this function. If it is called from the main thread already, `f` runs
+/// immediately. Otherwise it is queued on the main dispatch queue with `dispatch_async_f`, which
+/// means the main thread must be running its run loop (or `dispatch_main`) for `f` to ever run.
+/// If `f` panics, the panic is resumed on the calling thread.
+///
+/// On other platforms `f` simply runs on the calling thread, so cross-platform code can use this
+/// unconditionally.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{run_on_main, DialogBox, DialogType};
+///
+/// std::thread::spawn(|| {
+///     run_on_main(|| {
+///         let mut dialog = DialogBox::new("Done", "Export finished", DialogType::Simple)?;
+///         dialog.show();
+///         Ok::<(), nvdialog_rs::Error>(())
+///     })
+/// });
+/// ```
+pub fn run_on_main<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    #[cfg(target_os = "macos")]
+    {
+        use std::{
+            ffi::c_void,
+            panic::{self, AssertUnwindSafe},
+            sync::mpsc,
+        };
+
+        type Job = Box<dyn FnOnce() + Send>;
+
+        extern "C" fn trampoline(context: *mut c_void) {
+            let job = unsafe { Box::from_raw(context as *mut Job) };
+            job();
+        }
+
+        if unsafe { ffi::pthread_main_np() } != 0 {
+            return f();
+        }
+
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let job: Job = Box::new(move || {
+            /* Unwinding out of `trampoline` would abort, so the panic is carried back instead. */
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+        });
+        unsafe {
+            ffi::dispatch_async_f(
+                &ffi::_dispatch_main_q,
+                Box::into_raw(Box::new(job)) as *mut c_void,
+                trampoline,
+            );
+        }
+        match receiver
+            .recv()
+            .expect("The main dispatch queue dropped the closure")
+        {
+            Ok(result) => result,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    f()
+}
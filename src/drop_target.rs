@@ -0,0 +1,476 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::Error;
+use std::path::PathBuf;
+
+/// A window asking the user to drag and drop files onto it, as an alternative to a file dialog
+/// for users who prefer drag and drop.
+///
+/// NvDialog has no such dialog, so the window is created directly with GTK on Linux and Win32 on
+/// Windows. It is closed as soon as files are dropped onto it.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::DropTargetDialog;
+///
+/// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+/// let paths = DropTargetDialog::new("Import photos")
+///     .message("Drop your photos here")
+///     .show()
+///     .expect("Failed to show drop target");
+/// for path in paths {
+///     println!("Importing {}", path.display());
+/// }
+/// ```
+pub struct DropTargetDialog {
+    title: String,
+    message: String,
+}
+
+impl DropTargetDialog {
+    /// Creates a new `DropTargetDialog` with the given `title`, showing "Drop files here".
+    pub fn new<S: AsRef<str>>(title: S) -> Self {
+        Self {
+            title: String::from(title.as_ref()),
+            message: String::from("Drop files here"),
+        }
+    }
+
+    /// Sets the text shown in the window, in place of "Drop files here".
+    pub fn message<S: AsRef<str>>(mut self, message: S) -> Self {
+        self.message = String::from(message.as_ref());
+        self
+    }
+
+    /// Returns the title of the window.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Shows the window and waits until files are dropped onto it or it is closed. Returns the
+    /// dropped paths, or an empty `Vec` if the window was closed without dropping anything.
+    ///
+    /// # Errors
+    /// Returns [`Error::NotYetInitialized`] if [`crate::init`] hasn't been called in this thread,
+    /// [`Error::ParametersError`] if the title or message contain a null byte, and
+    /// [`Error::Unsupported`] on macOS or if a fallback backend is active.
+    pub fn show(&self) -> Result<Vec<PathBuf>, Error> {
+        crate::init::check_thread();
+        crate::init::ensure_initialized()?;
+        if crate::backend::fallback().is_some() {
+            return Err(
+                Error::Unsupported.context("Drop targets are only supported by the native backend")
+            );
+        }
+        if !crate::init::native_available() {
+            return Ok(Vec::new());
+        }
+        show_native(&self.title, &self.message)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn show_native(title: &str, message: &str) -> Result<Vec<PathBuf>, Error> {
+    use std::ffi::{c_char, c_int, c_uint, c_ulong, c_void, CStr};
+
+    #[repr(C)]
+    struct GtkTargetEntry {
+        target: *const c_char,
+        flags: c_uint,
+        info: c_uint,
+    }
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_window_new(kind: c_int) -> *mut c_void;
+        fn gtk_window_set_title(window: *mut c_void, title: *const c_char);
+        fn gtk_window_set_default_size(window: *mut c_void, width: c_int, height: c_int);
+        fn gtk_label_new(text: *const c_char) -> *mut c_void;
+        fn gtk_container_add(container: *mut c_void, widget: *mut c_void);
+        fn gtk_drag_dest_set(
+            widget: *mut c_void,
+            flags: c_int,
+            targets: *const GtkTargetEntry,
+            n_targets: c_int,
+            actions: c_int,
+        );
+        fn gtk_selection_data_get_uris(data: *const c_void) -> *mut *mut c_char;
+        fn gtk_widget_show_all(widget: *mut c_void);
+        fn gtk_widget_destroy(widget: *mut c_void);
+        fn gtk_main();
+        fn gtk_main_quit();
+        fn g_signal_connect_data(
+            instance: *mut c_void,
+            signal: *const c_char,
+            handler: *const c_void,
+            data: *mut c_void,
+            destroy_data: *const c_void,
+            flags: c_int,
+        ) -> c_ulong;
+        fn g_filename_from_uri(
+            uri: *const c_char,
+            hostname: *mut *mut c_char,
+            error: *mut *mut c_void,
+        ) -> *mut c_char;
+        fn g_free(mem: *mut c_void);
+        fn g_strfreev(strings: *mut *mut c_char);
+    }
+
+    const GTK_WINDOW_TOPLEVEL: c_int = 0;
+    const GTK_DEST_DEFAULT_ALL: c_int = 0x07;
+    const GDK_ACTION_COPY: c_int = 1 << 1;
+
+    struct State {
+        window: *mut c_void,
+        paths: Vec<PathBuf>,
+    }
+
+    unsafe extern "C" fn on_drag_data_received(
+        _widget: *mut c_void,
+        _context: *mut c_void,
+        _x: c_int,
+        _y: c_int,
+        data: *const c_void,
+        _info: c_uint,
+        _time: c_uint,
+        state: *mut c_void,
+    ) {
+        let state = &mut *(state as *mut State);
+        let uris = gtk_selection_data_get_uris(data);
+        if uris.is_null() {
+            return;
+        }
+        let mut uri = uris;
+        while !(*uri).is_null() {
+            let filename = g_filename_from_uri(*uri, std::ptr::null_mut(), std::ptr::null_mut());
+            /* URIs that aren't local files, such as web links, are skipped. */
+            if !filename.is_null() {
+                let bytes = CStr::from_ptr(filename).to_bytes();
+                state
+                    .paths
+                    .push(PathBuf::from(crate::util::os_string_from_bytes(bytes)));
+                g_free(filename as *mut c_void);
+            }
+            uri = uri.add(1);
+        }
+        g_strfreev(uris);
+        if !state.paths.is_empty() {
+            gtk_widget_destroy(state.window);
+        }
+    }
+
+    unsafe extern "C" fn on_destroy(_widget: *mut c_void, _data: *mut c_void) {
+        gtk_main_quit();
+    }
+
+    crate::util::with_c_strings([title, message], |[title, message]| unsafe {
+        let window = gtk_window_new(GTK_WINDOW_TOPLEVEL);
+        gtk_window_set_title(window, title);
+        gtk_window_set_default_size(window, 360, 240);
+        gtk_container_add(window, gtk_label_new(message));
+
+        let targets = [GtkTargetEntry {
+            target: c"text/uri-list".as_ptr(),
+            flags: 0,
+            info: 0,
+        }];
+        gtk_drag_dest_set(
+            window,
+            GTK_DEST_DEFAULT_ALL,
+            targets.as_ptr(),
+            targets.len() as c_int,
+            GDK_ACTION_COPY,
+        );
+
+        /* `gtk_main` only returns once the window is destroyed, so `state` outlives it. */
+        let mut state = State {
+            window,
+            paths: Vec::new(),
+        };
+        let received: unsafe extern "C" fn(
+            *mut c_void,
+            *mut c_void,
+            c_int,
+            c_int,
+            *const c_void,
+            c_uint,
+            c_uint,
+            *mut c_void,
+        ) = on_drag_data_received;
+        let destroyed: unsafe extern "C" fn(*mut c_void, *mut c_void) = on_destroy;
+        g_signal_connect_data(
+            window,
+            c"drag-data-received".as_ptr(),
+            received as *const c_void,
+            &mut state as *mut State as *mut c_void,
+            std::ptr::null(),
+            0,
+        );
+        g_signal_connect_data(
+            window,
+            c"destroy".as_ptr(),
+            destroyed as *const c_void,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            0,
+        );
+
+        gtk_widget_show_all(window);
+        gtk_main();
+        state.paths
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn show_native(title: &str, message: &str) -> Result<Vec<PathBuf>, Error> {
+    use std::{
+        cell::RefCell,
+        ffi::{c_void, OsString},
+        os::windows::ffi::OsStringExt,
+    };
+
+    type Hwnd = *mut c_void;
+    type WndProc = unsafe extern "system" fn(Hwnd, u32, usize, isize) -> isize;
+
+    #[repr(C)]
+    struct WndClassExW {
+        size: u32,
+        style: u32,
+        wnd_proc: WndProc,
+        cls_extra: i32,
+        wnd_extra: i32,
+        instance: *mut c_void,
+        icon: *mut c_void,
+        cursor: *mut c_void,
+        background: *mut c_void,
+        menu_name: *const u16,
+        class_name: *const u16,
+        icon_small: *mut c_void,
+    }
+
+    #[repr(C)]
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    #[repr(C)]
+    struct PaintStruct {
+        hdc: *mut c_void,
+        erase: i32,
+        paint: Rect,
+        restore: i32,
+        inc_update: i32,
+        reserved: [u8; 32],
+    }
+
+    #[repr(C)]
+    struct Msg {
+        hwnd: Hwnd,
+        message: u32,
+        wparam: usize,
+        lparam: isize,
+        time: u32,
+        pt: [i32; 2],
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn RegisterClassExW(class: *const WndClassExW) -> u16;
+        fn CreateWindowExW(
+            ex_style: u32,
+            class_name: *const u16,
+            window_name: *const u16,
+            style: u32,
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            parent: Hwnd,
+            menu: *mut c_void,
+            instance: *mut c_void,
+            param: *mut c_void,
+        ) -> Hwnd;
+        fn DefWindowProcW(hwnd: Hwnd, msg: u32, wparam: usize, lparam: isize) -> isize;
+        fn ShowWindow(hwnd: Hwnd, cmd: i32) -> i32;
+        fn DestroyWindow(hwnd: Hwnd) -> i32;
+        fn GetMessageW(msg: *mut Msg, hwnd: Hwnd, min: u32, max: u32) -> i32;
+        fn TranslateMessage(msg: *const Msg) -> i32;
+        fn DispatchMessageW(msg: *const Msg) -> isize;
+        fn PostQuitMessage(code: i32);
+        fn BeginPaint(hwnd: Hwnd, paint: *mut PaintStruct) -> *mut c_void;
+        fn EndPaint(hwnd: Hwnd, paint: *const PaintStruct) -> i32;
+        fn GetClientRect(hwnd: Hwnd, rect: *mut Rect) -> i32;
+        fn DrawTextW(
+            hdc: *mut c_void,
+            text: *const u16,
+            len: i32,
+            rect: *mut Rect,
+            format: u32,
+        ) -> i32;
+        fn LoadCursorW(instance: *mut c_void, name: *const u16) -> *mut c_void;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleW(name: *const u16) -> *mut c_void;
+    }
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn DragQueryFileW(drop: *mut c_void, file: u32, buffer: *mut u16, len: u32) -> u32;
+        fn DragFinish(drop: *mut c_void);
+    }
+
+    const WM_DESTROY: u32 = 0x0002;
+    const WM_PAINT: u32 = 0x000F;
+    const WM_DROPFILES: u32 = 0x0233;
+    const WS_OVERLAPPEDWINDOW: u32 = 0x00CF_0000;
+    const WS_EX_ACCEPTFILES: u32 = 0x0000_0010;
+    const CW_USEDEFAULT: i32 = 0x8000_0000_u32 as i32;
+    const SW_SHOW: i32 = 5;
+    const COLOR_WINDOW: usize = 5;
+    const IDC_ARROW: usize = 32512;
+    const DT_CENTER: u32 = 0x01;
+    const DT_VCENTER: u32 = 0x04;
+    const DT_SINGLELINE: u32 = 0x20;
+
+    thread_local! {
+        static MESSAGE: RefCell<Vec<u16>> = const { RefCell::new(Vec::new()) };
+        static DROPPED: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+    }
+
+    unsafe extern "system" fn wnd_proc(
+        hwnd: Hwnd,
+        msg: u32,
+        wparam: usize,
+        lparam: isize,
+    ) -> isize {
+        match msg {
+            WM_PAINT => {
+                let mut paint: PaintStruct = std::mem::zeroed();
+                let hdc = BeginPaint(hwnd, &mut paint);
+                let mut rect: Rect = std::mem::zeroed();
+                GetClientRect(hwnd, &mut rect);
+                MESSAGE.with(|message| {
+                    let message = message.borrow();
+                    DrawTextW(
+                        hdc,
+                        message.as_ptr(),
+                        message.len() as i32,
+                        &mut rect,
+                        DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+                    );
+                });
+                EndPaint(hwnd, &paint);
+                0
+            }
+            WM_DROPFILES => {
+                let drop = wparam as *mut c_void;
+                let count = DragQueryFileW(drop, u32::MAX, std::ptr::null_mut(), 0);
+                let paths: Vec<PathBuf> = (0..count)
+                    .map(|i| {
+                        let len = DragQueryFileW(drop, i, std::ptr::null_mut(), 0);
+                        let mut buffer = vec![0u16; len as usize + 1];
+                        let len = DragQueryFileW(drop, i, buffer.as_mut_ptr(), len + 1);
+                        PathBuf::from(OsString::from_wide(&buffer[..len as usize]))
+                    })
+                    .collect();
+                DragFinish(drop);
+                DROPPED.with(|dropped| dropped.borrow_mut().extend(paths));
+                DestroyWindow(hwnd);
+                0
+            }
+            WM_DESTROY => {
+                PostQuitMessage(0);
+                0
+            }
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+
+    if title.contains('\0') || message.contains('\0') {
+        return Err(Error::ParametersError.context("String contains a null byte"));
+    }
+    let wide = |s: &str| -> Vec<u16> { s.encode_utf16().chain(std::iter::once(0)).collect() };
+    let class_name = wide("NvDialogRsDropTarget");
+    let title = wide(title);
+    MESSAGE.with(|m| *m.borrow_mut() = message.encode_utf16().collect());
+    DROPPED.with(|dropped| dropped.borrow_mut().clear());
+
+    unsafe {
+        let instance = GetModuleHandleW(std::ptr::null());
+        let class = WndClassExW {
+            size: std::mem::size_of::<WndClassExW>() as u32,
+            style: 0,
+            wnd_proc,
+            cls_extra: 0,
+            wnd_extra: 0,
+            instance,
+            icon: std::ptr::null_mut(),
+            cursor: LoadCursorW(std::ptr::null_mut(), IDC_ARROW as *const u16),
+            background: (COLOR_WINDOW + 1) as *mut c_void,
+            menu_name: std::ptr::null(),
+            class_name: class_name.as_ptr(),
+            icon_small: std::ptr::null_mut(),
+        };
+        /* Fails harmlessly when the class is already registered by an earlier call. */
+        RegisterClassExW(&class);
+
+        let hwnd = CreateWindowExW(
+            WS_EX_ACCEPTFILES,
+            class_name.as_ptr(),
+            title.as_ptr(),
+            WS_OVERLAPPEDWINDOW,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            360,
+            240,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            instance,
+            std::ptr::null_mut(),
+        );
+        if hwnd.is_null() {
+            let code = std::io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            return Err(Error::backend(code, Some("CreateWindowExW failed"))
+                .context("Failed to create the drop target window"));
+        }
+        ShowWindow(hwnd, SW_SHOW);
+
+        let mut msg: Msg = std::mem::zeroed();
+        while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+    Ok(DROPPED.with(|dropped| std::mem::take(&mut *dropped.borrow_mut())))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn show_native(_title: &str, _message: &str) -> Result<Vec<PathBuf>, Error> {
+    Err(Error::Unsupported.context("Drop targets are only supported on Linux and Windows"))
+}
@@ -0,0 +1,324 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{
+    util::{os_str_bytes, os_string_from_bytes, with_c_strings},
+    Error,
+};
+use nvdialog_sys::ffi::*;
+use std::{
+    borrow::Cow,
+    ffi::{c_char, CStr, OsStr, OsString},
+    fmt,
+    ops::Deref,
+    str::{Bytes, Chars},
+};
+
+/// A string owned by NvDialog (`NvdDynamicString`), along with a Rust copy of its contents.
+///
+/// NvDialog uses dynamic strings to hand text back to the caller, for example the text entered
+/// in an input box. The Rust copy is what [`DynamicString::as_str`] and the `Deref`
+/// implementation return, so reading the string doesn't go through the FFI each time.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::DynamicString;
+///
+/// let string = DynamicString::from_static("Hello world!").unwrap();
+/// assert_eq!(string.as_str(), "Hello world!");
+/// ```
+pub struct DynamicString {
+    raw: *mut NvdDynamicString,
+    mirror: Cow<'static, str>,
+}
+
+/// Returns `true` if strings can be created through NvDialog.
+fn native_strings() -> bool {
+    crate::init::NATIVE_CODE && nvdialog_sys::available::DYNAMIC_STRING
+}
+
+impl DynamicString {
+    /// Creates a new `DynamicString` holding a copy of `string`.
+    ///
+    /// # Errors
+    /// Returns [`Error::ParametersError`] if `string` contains a null byte, or
+    /// [`Error::OutOfMemory`] if NvDialog failed to allocate the string.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_string_new`.
+    pub fn new<S: AsRef<str>>(string: S) -> Result<Self, Error> {
+        Self::with_mirror(Cow::Owned(String::from(string.as_ref())))
+    }
+
+    /// Creates a new `DynamicString` from a string literal. Unlike [`DynamicString::new`], the
+    /// Rust side borrows `string` instead of keeping an owned copy of it.
+    ///
+    /// # Errors
+    /// Same as [`DynamicString::new`].
+    pub fn from_static(string: &'static str) -> Result<Self, Error> {
+        Self::with_mirror(Cow::Borrowed(string))
+    }
+
+    /// Creates a new `DynamicString` from an OS string, such as a path or a command-line argument.
+    ///
+    /// On Unix, the native string holds the exact bytes of `string` even if they aren't valid
+    /// UTF-8, and [`DynamicString::to_os_string`] gives them back unchanged. [`DynamicString::as_str`]
+    /// shows invalid sequences as `U+FFFD`.
+    ///
+    /// # Errors
+    /// Same as [`DynamicString::new`].
+    pub fn from_os_str(string: &OsStr) -> Result<Self, Error> {
+        let c_string = crate::try_c_string!(os_str_bytes(string).into_owned())?;
+        if !native_strings() {
+            return Ok(Self::detached(string.to_string_lossy().into_owned().into()));
+        }
+        let raw = crate::ffi_check::track(unsafe { nvd_string_new(c_string.as_ptr()) });
+        if raw.is_null() {
+            return Err(Error::OutOfMemory.context("Failed to create dynamic string"));
+        }
+        Ok(Self {
+            raw,
+            mirror: Cow::Owned(string.to_string_lossy().into_owned()),
+        })
+    }
+
+    fn with_mirror(mirror: Cow<'static, str>) -> Result<Self, Error> {
+        if !native_strings() {
+            if mirror.contains('\0') {
+                return Err(Error::ParametersError.context("String contains a null byte"));
+            }
+            return Ok(Self::detached(mirror));
+        }
+        let raw = crate::ffi_check::track(with_c_strings([&mirror], |[s]| unsafe {
+            nvd_string_new(s)
+        })?);
+        if raw.is_null() {
+            return Err(Error::OutOfMemory.context("Failed to create dynamic string"));
+        }
+        Ok(Self { raw, mirror })
+    }
+
+    /// A string without a native buffer, used when NvDialog can't be called or doesn't have
+    /// dynamic strings.
+    fn detached(mirror: Cow<'static, str>) -> Self {
+        Self {
+            raw: std::ptr::null_mut(),
+            mirror,
+        }
+    }
+
+    /// Returns the contents of the string.
+    pub fn as_str(&self) -> &str {
+        &self.mirror
+    }
+
+    /// Returns the length of the string in bytes, like [`str::len`].
+    pub fn len(&self) -> usize {
+        self.checked_mirror().len()
+    }
+
+    /// Returns `true` if the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.checked_mirror().is_empty()
+    }
+
+    /// Returns an iterator over the bytes of the string, like [`str::bytes`].
+    pub fn bytes(&self) -> Bytes<'_> {
+        self.checked_mirror().bytes()
+    }
+
+    /// Returns an iterator over the characters of the string, like [`str::chars`].
+    pub fn chars(&self) -> Chars<'_> {
+        self.checked_mirror().chars()
+    }
+
+    /// Returns the Rust copy of the string. In debug builds, this first checks that it still
+    /// matches the native string, which would mean something changed the native buffer behind
+    /// this crate's back.
+    fn checked_mirror(&self) -> &str {
+        #[cfg(debug_assertions)]
+        if !self.raw.is_null() {
+            let native = unsafe { CStr::from_ptr(nvd_string_to_cstr(self.raw)) };
+            debug_assert_eq!(
+                String::from_utf8_lossy(native.to_bytes()),
+                self.mirror,
+                "DynamicString is out of sync with its native buffer"
+            );
+        }
+        &self.mirror
+    }
+
+    /// Returns the contents of the string as an OS string. On Unix this is lossless: the bytes of
+    /// the native string are returned as-is, even if they aren't valid UTF-8.
+    pub fn to_os_string(&self) -> OsString {
+        if self.raw.is_null() {
+            return OsString::from(self.as_str());
+        }
+        os_string_from_bytes(unsafe { CStr::from_ptr(self.as_ptr()) }.to_bytes())
+    }
+
+    /// Returns a pointer to the native, null-terminated copy of the string, or a null pointer if
+    /// there is none. The pointer is valid for as long as `self` is.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_string_to_cstr`.
+    pub fn as_ptr(&self) -> *const c_char {
+        if self.raw.is_null() {
+            return std::ptr::null();
+        }
+        unsafe { nvd_string_to_cstr(self.raw) }
+    }
+
+    /// Takes ownership of a string returned by NvDialog. A null pointer gives an empty string.
+    /// Invalid UTF-8 in the native string is replaced with `U+FFFD`.
+    ///
+    /// # Safety
+    /// `raw` must be null, or a valid `NvdDynamicString` that nothing else frees.
+    pub unsafe fn from_raw(raw: *mut NvdDynamicString) -> Self {
+        crate::ffi_check::track(raw);
+        if raw.is_null() {
+            return Self {
+                raw,
+                mirror: Cow::Borrowed(""),
+            };
+        }
+        let bytes = CStr::from_ptr(nvd_string_to_cstr(raw)).to_bytes();
+        /* Only allocates once, whether or not the string had to be repaired. */
+        let mirror = match String::from_utf8_lossy(bytes) {
+            Cow::Borrowed(valid) => String::from(valid),
+            Cow::Owned(repaired) => repaired,
+        };
+        Self {
+            raw,
+            mirror: Cow::Owned(mirror),
+        }
+    }
+
+    /// Returns the underlying `NvdDynamicString`, which is still owned by `self`.
+    pub(crate) fn as_raw(&self) -> *mut NvdDynamicString {
+        self.raw
+    }
+}
+
+impl Clone for DynamicString {
+    /// Copies the string, including its native buffer.
+    ///
+    /// # Panics
+    /// Panics if NvDialog fails to allocate the copy.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_duplicate_string`.
+    fn clone(&self) -> Self {
+        if self.raw.is_null() {
+            return Self {
+                raw: self.raw,
+                mirror: self.mirror.clone(),
+            };
+        }
+        crate::ffi_check::check(self.raw);
+        let raw = crate::ffi_check::track(unsafe { nvd_duplicate_string(self.raw) });
+        assert!(!raw.is_null(), "Failed to copy dynamic string");
+        Self {
+            raw,
+            mirror: self.mirror.clone(),
+        }
+    }
+}
+
+impl Deref for DynamicString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for DynamicString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for DynamicString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for DynamicString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Drop for DynamicString {
+    fn drop(&mut self) {
+        if !self.raw.is_null() {
+            crate::ffi_check::release(self.raw);
+            unsafe { nvd_delete_string(self.raw) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_text() {
+        let string = DynamicString::new("Grüße").unwrap();
+        assert_eq!(string.as_str(), "Grüße");
+        assert_eq!(string.len(), "Grüße".len());
+        assert_eq!(string.chars().count(), 5);
+        assert!(!string.is_empty());
+        assert_eq!(string.to_string(), "Grüße");
+        assert_eq!(format!("{string:?}"), "\"Grüße\"");
+    }
+
+    #[test]
+    fn round_trips_through_clones_and_os_strings() {
+        let string = DynamicString::from_static("Report.odt").unwrap();
+        let copy = string.clone();
+        drop(string);
+        assert_eq!(copy.as_str(), "Report.odt");
+        assert_eq!(copy.to_os_string(), OsString::from("Report.odt"));
+
+        let path = DynamicString::from_os_str(OsStr::new("/tmp/Report.odt")).unwrap();
+        assert_eq!(path.to_os_string(), OsString::from("/tmp/Report.odt"));
+    }
+
+    #[test]
+    fn rejects_null_bytes() {
+        assert!(DynamicString::new("Report\0.odt").is_err());
+        assert!(DynamicString::from_static("\0").is_err());
+        assert!(DynamicString::from_os_str(OsStr::new("a\0b")).is_err());
+    }
+
+    #[test]
+    fn null_pointers_give_empty_strings() {
+        let string = unsafe { DynamicString::from_raw(std::ptr::null_mut()) };
+        assert!(string.is_empty());
+        assert!(string.as_ptr().is_null());
+    }
+}
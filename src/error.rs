@@ -46,17 +46,24 @@ use thiserror::Error;
 /// }
 /// ```
 ///
+/// ## Context
+/// Errors returned by failing NvDialog calls are wrapped in [`Error::Context`], which describes
+/// the operation that failed, so messages that bubble up through `anyhow` or `eyre` read like
+/// `Failed to create dialog box: No display found`. Use [`Error::root`] to match on the
+/// underlying error. Errors also convert into [`std::io::Error`].
+///
 /// ## Notes
-/// - Stringified explanations of the errors are not done with NvDialog's `nvd_stringify_error()` function,
-/// but using the [`thiserror`](https://crates.io/crates/thiserror) crate instead.
+/// - Stringified explanations of the known errors are not done with NvDialog's `nvd_stringify_error()`
+///   function, but using the [`thiserror`](https://crates.io/crates/thiserror) crate instead. It is only
+///   used for the `detail` of codes the crate doesn't know about.
 /// - While you could do this, you shouldn't manually call `Error::from(your_number)`, because the trait is
-/// implemented for internal usage mainly.
-#[derive(Debug, Error, Clone, Copy)]
+///   implemented for internal usage mainly.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
 pub enum Error {
     #[error("No error")]
-    NoError = 0x0,
+    NoError,
     #[error("No display found")]
-    NoDisplay = 0xff,
+    NoDisplay,
     #[error("Backend failure occured")]
     BackendFailed,
     #[error("Invalid parameter passed")]
@@ -75,30 +82,203 @@ pub enum Error {
     InternalError,
     #[error("Already initialized NvDialog")]
     AlreadyInitialized,
+    /// COM was already initialized on the thread with an apartment model the file dialogs can't
+    /// work with. See [`ComApartment`](crate::ComApartment).
+    #[error("COM is already initialized with an incompatible apartment model")]
+    ComApartmentConflict,
+    /// The capability that was requested isn't available, either with the active backend or in
+    /// the version of NvDialog the crate was built against.
+    #[error("Operation not supported")]
+    Unsupported,
+    /// Notifications were requested on macOS from a process that doesn't run from an app bundle,
+    /// where they would be silently dropped. See [`is_app_bundle`](crate::is_app_bundle).
+    #[error("Notifications require the application to run from an app bundle")]
+    RequiresAppBundle,
+    /// The libnvdialog found at runtime isn't compatible with the headers the crate was built
+    /// against. See [`NATIVE_VERSION`](crate::NATIVE_VERSION).
+    #[error("NvDialog {runtime} is incompatible with the version {built} the crate was built for")]
+    VersionMismatch {
+        built: crate::Version,
+        runtime: crate::Version,
+    },
+    /// An error code NvDialog returned that this crate doesn't know about, along with NvDialog's
+    /// own description of it when available.
+    #[error("NvDialog backend error {code}{}", .detail.as_ref().map(|d| format!(": {d}")).unwrap_or_default())]
+    Backend { code: i32, detail: Option<String> },
+    /// Another error, along with a description of the operation that failed.
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+/* Values of `NvdError`, see `include/nvdialog_types.h`. */
+const NVD_NO_ERROR: i32 = 0x0;
+const NVD_NO_DISPLAY: i32 = 0xff;
+
+impl Error {
+    /// Creates an [`Error::Backend`] for the given NvDialog error code and description.
+    pub fn backend<S: Into<String>>(code: i32, detail: Option<S>) -> Self {
+        Self::Backend {
+            code,
+            detail: detail.map(Into::into),
+        }
+    }
+
+    /// Wraps this error in an [`Error::Context`] describing the operation that failed.
+    ///
+    /// # Examples
+    /// ```
+    /// use nvdialog_rs::Error;
+    ///
+    /// let error = Error::NoDisplay.context("Failed to create dialog box");
+    /// assert_eq!(error.to_string(), "Failed to create dialog box: No display found");
+    /// assert_eq!(error.root(), &Error::NoDisplay);
+    /// ```
+    pub fn context<S: Into<String>>(self, context: S) -> Self {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the innermost error, skipping any [`Error::Context`] around it. Match on this
+    /// to find out what went wrong regardless of the operation that failed.
+    pub fn root(&self) -> &Error {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Returns the `NvdError` code this error corresponds to, if it came from NvDialog.
+    pub fn code(&self) -> Option<i32> {
+        match self.root() {
+            Self::NoError => Some(NVD_NO_ERROR),
+            Self::NoDisplay => Some(NVD_NO_DISPLAY),
+            Self::BackendFailed => Some(NVD_NO_DISPLAY + 1),
+            Self::ParametersError => Some(NVD_NO_DISPLAY + 2),
+            Self::NotYetInitialized => Some(NVD_NO_DISPLAY + 3),
+            Self::InvalidBackend => Some(NVD_NO_DISPLAY + 4),
+            Self::InaccessibleFile => Some(NVD_NO_DISPLAY + 5),
+            Self::EmptyString => Some(NVD_NO_DISPLAY + 6),
+            Self::OutOfMemory => Some(NVD_NO_DISPLAY + 7),
+            Self::InternalError => Some(NVD_NO_DISPLAY + 8),
+            Self::AlreadyInitialized => Some(NVD_NO_DISPLAY + 9),
+            Self::Backend { code, .. } => Some(*code),
+            Self::ComApartmentConflict
+            | Self::Unsupported
+            | Self::RequiresAppBundle
+            | Self::VersionMismatch { .. }
+            | Self::Context { .. } => None,
+        }
+    }
+
+    /// Builds an error from NvDialog's current error state (`nvd_get_error`), with the
+    /// description from `nvd_stringify_error` for codes this crate doesn't know about.
+    pub(crate) fn last_native() -> Self {
+        use nvdialog_sys::ffi::{nvd_get_error, nvd_stringify_error};
+
+        let code = unsafe { nvd_get_error() };
+        match Self::from(code as i32) {
+            Self::Backend { code: raw, .. } => {
+                let detail = unsafe {
+                    let description = nvd_stringify_error(code);
+                    (!description.is_null()).then(|| {
+                        std::ffi::CStr::from_ptr(description)
+                            .to_string_lossy()
+                            .into_owned()
+                    })
+                };
+                Self::Backend { code: raw, detail }
+            }
+            error => error,
+        }
+    }
 }
 
 impl From<i32> for Error {
     fn from(value: i32) -> Error {
-        let mut result: Error = Error::NoError;
-        [
-            Self::NoError,
-            Self::NoDisplay,
-            Self::BackendFailed,
-            Self::ParametersError,
-            Self::NotYetInitialized,
-            Self::InvalidBackend,
-            Self::InaccessibleFile,
-            Self::EmptyString,
-            Self::OutOfMemory,
-            Self::InternalError,
-            Self::AlreadyInitialized,
-        ]
-        .into_iter()
-        .for_each(|member| {
-            if member as i32 == value {
-                result = member;
-            }
-        });
-        result
+        match value {
+            NVD_NO_ERROR => Self::NoError,
+            NVD_NO_DISPLAY => Self::NoDisplay,
+            _ => match value.wrapping_sub(NVD_NO_DISPLAY) {
+                1 => Self::BackendFailed,
+                2 => Self::ParametersError,
+                3 => Self::NotYetInitialized,
+                4 => Self::InvalidBackend,
+                5 => Self::InaccessibleFile,
+                6 => Self::EmptyString,
+                7 => Self::OutOfMemory,
+                8 => Self::InternalError,
+                9 => Self::AlreadyInitialized,
+                _ => Self::Backend {
+                    code: value,
+                    detail: None,
+                },
+            },
+        }
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        use std::io::ErrorKind;
+
+        let kind = match error.root() {
+            Error::NoDisplay => ErrorKind::NotConnected,
+            Error::ParametersError | Error::EmptyString => ErrorKind::InvalidInput,
+            Error::InaccessibleFile => ErrorKind::PermissionDenied,
+            Error::OutOfMemory => ErrorKind::OutOfMemory,
+            Error::AlreadyInitialized => ErrorKind::AlreadyExists,
+            Error::Unsupported | Error::RequiresAppBundle => ErrorKind::Unsupported,
+            _ => ErrorKind::Other,
+        };
+        std::io::Error::new(kind, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_codes_round_trip() {
+        for code in std::iter::once(NVD_NO_ERROR).chain(NVD_NO_DISPLAY..=NVD_NO_DISPLAY + 9) {
+            let error = Error::from(code);
+            assert!(
+                !matches!(error, Error::Backend { .. }),
+                "{code} isn't known"
+            );
+            assert_eq!(error.code(), Some(code));
+        }
+    }
+
+    #[test]
+    fn unknown_codes_round_trip() {
+        let codes = [
+            i32::MIN,
+            i32::MIN + 1,
+            i32::MIN + NVD_NO_DISPLAY,
+            -1,
+            1,
+            NVD_NO_DISPLAY - 1,
+            NVD_NO_DISPLAY + 10,
+            i32::MAX,
+        ];
+        for code in codes {
+            assert_eq!(Error::from(code), Error::backend(code, None::<String>));
+            assert_eq!(Error::from(code).code(), Some(code));
+        }
+    }
+
+    #[test]
+    fn context_keeps_the_code() {
+        let error = Error::from(NVD_NO_DISPLAY + 3).context("Failed to create dialog box");
+        assert_eq!(error.root(), &Error::NotYetInitialized);
+        assert_eq!(error.code(), Some(NVD_NO_DISPLAY + 3));
+        assert_eq!(Error::Unsupported.code(), None);
     }
 }
@@ -0,0 +1,97 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::DialogEvent;
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_core::Stream;
+use std::{
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+static SUBSCRIBERS: Mutex<Vec<UnboundedSender<DialogEvent>>> = Mutex::new(Vec::new());
+
+/// A [`Stream`] of the decisions users take in dialogs, created with [`event_stream`].
+///
+/// It yields the same [`DialogEvent`]s as the logger registered with
+/// [`set_decision_logger`](crate::set_decision_logger): every reply to a question dialog
+/// (including [`Reply::Cancelled`](crate::Reply::Cancelled) and
+/// [`Reply::Dismissed`](crate::Reply::Dismissed)) and every file dialog outcome, with `None` when
+/// it was cancelled. NvDialog writes notification actions to the pointer given to
+/// [`Notification::add_action`](crate::Notification::add_action) without telling anyone, so they
+/// can't be part of the stream.
+///
+/// The stream never ends on its own. Events are buffered until they are polled, so a stream that
+/// is kept around but never polled grows without bound; drop it when it isn't needed anymore.
+pub struct EventStream {
+    receiver: UnboundedReceiver<DialogEvent>,
+}
+
+impl Stream for EventStream {
+    type Item = DialogEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<DialogEvent>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+/// Returns a new [`EventStream`], receiving the events of dialogs in every thread from now on.
+/// Requires the `async` feature.
+///
+/// # Examples
+/// ```no_run
+/// use futures::StreamExt;
+///
+/// # async fn run() {
+/// let mut events = nvdialog_rs::event_stream();
+/// while let Some(event) = events.next().await {
+///     println!("'{}' -> {:?}", event.dialog.title, event.decision);
+/// }
+/// # }
+/// ```
+pub fn event_stream() -> EventStream {
+    let (sender, receiver) = unbounded();
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(sender);
+    EventStream { receiver }
+}
+
+/// Returns `true` if any [`EventStream`] may still be listening.
+pub(crate) fn has_subscribers() -> bool {
+    !SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_empty()
+}
+
+/// Sends `event` to every [`EventStream`], forgetting the ones that were dropped.
+pub(crate) fn publish(event: &DialogEvent) {
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|sender| sender.unbounded_send(event.clone()).is_ok());
+}
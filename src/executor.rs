@@ -0,0 +1,319 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{CancelToken, FileDialog};
+use futures_channel::oneshot;
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    pin::Pin,
+    sync::mpsc::{channel, Sender},
+    task::{Context, Poll},
+    thread,
+};
+
+/// A job passed to a [`BlockingExecutor`].
+pub type BlockingJob = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs blocking jobs away from an async runtime's worker threads, so that [`show_async`] works
+/// with any runtime.
+///
+/// Dialogs block until they are closed, so async code must not show them from a runtime's worker
+/// threads. Implement this trait with your runtime's `spawn_blocking` equivalent, or use
+/// [`DialogThread`], which works with every runtime.
+///
+/// Keep NvDialog's threading rules in mind: dialogs must be created and used on the thread that
+/// initialized NvDialog. Executors that run jobs on a pool of threads are only suitable where the
+/// platform tolerates that, which is why [`DialogThread`] runs every job on the same thread.
+///
+/// # Examples
+/// An executor for `smol`, using its `blocking` crate:
+/// ```ignore
+/// use nvdialog_rs::{BlockingExecutor, BlockingJob};
+///
+/// struct Smol;
+///
+/// impl BlockingExecutor for Smol {
+///     fn spawn_blocking(&self, job: BlockingJob) {
+///         blocking::unblock(job).detach();
+///     }
+/// }
+/// ```
+pub trait BlockingExecutor {
+    /// Starts running `job` somewhere it may block, without waiting for it to finish.
+    fn spawn_blocking(&self, job: BlockingJob);
+}
+
+/// A dedicated thread that initializes NvDialog and runs every job given to it, one after the
+/// other.
+///
+/// This is the [`BlockingExecutor`] to use when the platform requires all dialogs to be on one
+/// thread, which is the case for GTK. It doesn't work on macOS, where dialogs must be shown on
+/// the main thread; use [`run_on_main`](crate::run_on_main) from the `macos-dispatch` feature
+/// there. The thread stops once every handle to it has been dropped.
+#[derive(Clone)]
+pub struct DialogThread {
+    jobs: Sender<BlockingJob>,
+}
+
+impl DialogThread {
+    /// Spawns the thread. NvDialog is initialized on it before the first job runs.
+    pub fn spawn() -> Self {
+        let (jobs, receiver) = channel::<BlockingJob>();
+        thread::Builder::new()
+            .name("nvdialog".to_owned())
+            .spawn(move || {
+                /* Jobs fail on their own with `NotYetInitialized` if this did. */
+                let _ = crate::init_with(crate::InitOptions::new().idempotent(true));
+                for job in receiver {
+                    /* A panicking job only drops its reply, the thread keeps serving. */
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                }
+            })
+            .expect("Failed to spawn the dialog thread");
+        Self { jobs }
+    }
+}
+
+impl BlockingExecutor for DialogThread {
+    fn spawn_blocking(&self, job: BlockingJob) {
+        let _ = self.jobs.send(job);
+    }
+}
+
+/// Runs `show` on `executor` and resolves to its result, without blocking the async task.
+/// Requires the `async` feature.
+///
+/// `show` should create and show a dialog and return its result.
+///
+/// # Panics
+/// The returned future panics if `show` panicked, or if the executor dropped it without running
+/// it.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{DialogThread, QuestionDialog, QuestionDialogButtons};
+///
+/// # async fn run() {
+/// let dialogs = DialogThread::spawn();
+/// let reply = nvdialog_rs::show_async(&dialogs, || {
+///     QuestionDialog::new("Quit", "Quit without saving?", QuestionDialogButtons::YesNo).get_reply()
+/// })
+/// .await;
+/// # }
+/// ```
+pub fn show_async<E, F, T>(executor: &E, show: F) -> impl Future<Output = T> + Send + 'static
+where
+    E: BlockingExecutor + ?Sized,
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    executor.spawn_blocking(Box::new(move || {
+        let _ = sender.send(show());
+    }));
+    async move {
+        receiver
+            .await
+            .expect("The dialog was dropped by the executor before it finished")
+    }
+}
+
+/// The future returned by [`show_file_dialog_async`].
+#[must_use = "futures do nothing unless polled, and dropping this one closes the dialog"]
+pub struct FileDialogFuture {
+    receiver: oneshot::Receiver<Option<PathBuf>>,
+    token: CancelToken,
+    done: bool,
+}
+
+impl Future for FileDialogFuture {
+    type Output = Option<PathBuf>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<PathBuf>> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Ready(file) => {
+                self.done = true;
+                Poll::Ready(
+                    file.expect("The dialog was dropped by the executor before it finished"),
+                )
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for FileDialogFuture {
+    fn drop(&mut self) {
+        if !self.done {
+            /* A dialog that can't be closed is left to the user, and its answer discarded. */
+            let _ = self.token.cancel();
+        }
+    }
+}
+
+/// Shows the file dialog created by `build` on `executor`, and resolves to the chosen file like
+/// [`FileDialog::retrieve_filename`]. Requires the `async` feature.
+///
+/// Unlike [`show_async`], dropping the returned future before the dialog was closed cancels it
+/// like a [`CancelToken`] would, which happens when the task awaiting it is aborted or loses a
+/// `select!`. If the dialog wasn't shown yet, it never is, and `build` isn't called.
+///
+/// # Panics
+/// The returned future panics if the executor dropped the job without running it.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{DialogThread, FileDialog, FileDialogType};
+///
+/// # async fn run() {
+/// let dialogs = DialogThread::spawn();
+/// let file = nvdialog_rs::show_file_dialog_async(&dialogs, || {
+///     FileDialog::new("Open", FileDialogType::OpenFile, None::<Vec<&str>>)
+/// });
+/// // Dropping `file` here instead of awaiting it would close the dialog.
+/// if let Some(path) = file.await {
+///     println!("Opening {}", path.display());
+/// }
+/// # }
+/// ```
+pub fn show_file_dialog_async<E, F>(executor: &E, build: F) -> FileDialogFuture
+where
+    E: BlockingExecutor + ?Sized,
+    F: FnOnce() -> FileDialog + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+    let token = CancelToken::new();
+    let job_token = token.clone();
+    executor.spawn_blocking(Box::new(move || {
+        if job_token.is_cancelled() {
+            return;
+        }
+        let file = build().cancel_token(job_token).retrieve_filename();
+        let _ = sender.send(file);
+    }));
+    FileDialogFuture {
+        receiver,
+        token,
+        done: false,
+    }
+}
+
+/* `strict-threading` only lets one thread of the process initialize NvDialog, which every test
+ * does with `MockBackend::install`. */
+#[cfg(all(test, feature = "mock", not(feature = "strict-threading")))]
+mod tests {
+    use super::*;
+    use crate::{FileDialogType, MockBackend};
+    use std::{
+        cell::RefCell,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::{Wake, Waker},
+        time::Duration,
+    };
+
+    /// Keeps the jobs it is given until the test runs them.
+    #[derive(Default)]
+    struct Manual(RefCell<Vec<BlockingJob>>);
+
+    impl BlockingExecutor for Manual {
+        fn spawn_blocking(&self, job: BlockingJob) {
+            self.0.borrow_mut().push(job);
+        }
+    }
+
+    impl Manual {
+        fn take(&self) -> BlockingJob {
+            self.0.borrow_mut().pop().expect("No job was spawned")
+        }
+    }
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn open() -> FileDialog {
+        FileDialog::new("Open", FileDialogType::OpenFile, None::<Vec<&str>>)
+    }
+
+    #[test]
+    fn dropped_before_shown_never_builds_the_dialog() {
+        let executor = Manual::default();
+        let built = Arc::new(AtomicBool::new(false));
+        let job_built = built.clone();
+        let file = show_file_dialog_async(&executor, move || {
+            job_built.store(true, Ordering::SeqCst);
+            open()
+        });
+        drop(file);
+        executor.take()();
+        assert!(!built.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn dropped_while_shown_closes_the_dialog() {
+        let executor = Manual::default();
+        let file = show_file_dialog_async(&executor, open);
+        let job = executor.take();
+        let (closed, on_close) = channel();
+        let dialog = thread::spawn(move || {
+            let mock = MockBackend::install();
+            mock.on_file_dialog().times(1).until_closed();
+            job();
+            let _ = closed.send(());
+            mock.verify();
+        });
+        while !file.token.is_showing() && !dialog.is_finished() {
+            thread::yield_now();
+        }
+        drop(file);
+        on_close
+            .recv_timeout(Duration::from_secs(10))
+            .expect("The dialog wasn't closed");
+        dialog.join().unwrap();
+    }
+
+    #[test]
+    fn dropped_after_completion_does_nothing() {
+        let mock = MockBackend::install();
+        mock.on_file_dialog().return_path("/tmp/picture.png");
+        let executor = Manual::default();
+        let mut file = show_file_dialog_async(&executor, open);
+        executor.take()();
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let chosen = Pin::new(&mut file).poll(&mut Context::from_waker(&waker));
+        assert_eq!(chosen, Poll::Ready(Some(PathBuf::from("/tmp/picture.png"))));
+        let token = file.token.clone();
+        drop(file);
+        assert!(!token.is_cancelled());
+        assert_eq!(mock.dialogs().len(), 1);
+    }
+}
@@ -0,0 +1,262 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! A fallback backend that drives `zenity`, `kdialog` or `osascript` as subprocesses, used by the
+//! `external-tools` feature when NvDialog's native backends fail to initialize.
+
+use crate::{DialogType, FileDialogType, QuestionDialogButtons, Reply};
+use std::{cell::Cell, env, path::PathBuf, process::Command};
+
+/// A command line tool dialogs can be delegated to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tool {
+    Zenity,
+    Kdialog,
+    Osascript,
+}
+
+thread_local! {
+    static ACTIVE: Cell<Option<Tool>> = const { Cell::new(None) };
+}
+
+/// Returns the tool dialogs are delegated to in the current thread, if any.
+pub(crate) fn active() -> Option<Tool> {
+    ACTIVE.with(|active| active.get())
+}
+
+pub(crate) fn set_active(tool: Option<Tool>) {
+    ACTIVE.with(|active| active.set(tool));
+}
+
+/// Finds the most suitable tool installed on this machine.
+pub(crate) fn detect() -> Option<Tool> {
+    let candidates: &[Tool] = if cfg!(target_os = "macos") {
+        &[Tool::Osascript]
+    } else if env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktop| desktop.contains("KDE")) {
+        &[Tool::Kdialog, Tool::Zenity]
+    } else {
+        &[Tool::Zenity, Tool::Kdialog]
+    };
+    candidates.iter().copied().find(|tool| in_path(tool.program()))
+}
+
+fn in_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// Quotes `s` as an AppleScript string literal.
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Shows a notification through the macOS notification center with `osascript`, for processes
+/// that don't run from an app bundle.
+pub(crate) fn notify(title: &str, msg: &str) -> Result<(), crate::Error> {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(msg),
+        applescript_string(title)
+    );
+    match Tool::Osascript.osascript(script) {
+        (Some(0), _) => Ok(()),
+        (code, _) => Err(crate::Error::backend(
+            code.unwrap_or(-1),
+            Some("osascript failed to display the notification"),
+        )
+        .context("Failed to send notification")),
+    }
+}
+
+impl Tool {
+    fn program(self) -> &'static str {
+        match self {
+            Self::Zenity => "zenity",
+            Self::Kdialog => "kdialog",
+            Self::Osascript => "osascript",
+        }
+    }
+
+    /// Runs the tool, returning its exit code (`None` if it couldn't run or was killed) and
+    /// its trimmed standard output.
+    fn run(self, args: &[String]) -> (Option<i32>, String) {
+        match Command::new(self.program()).args(args).output() {
+            Ok(output) => (
+                output.status.code(),
+                String::from_utf8_lossy(&output.stdout).trim_end().to_owned(),
+            ),
+            Err(_) => (None, String::new()),
+        }
+    }
+
+    fn osascript(self, script: String) -> (Option<i32>, String) {
+        self.run(&["-e".to_owned(), script])
+    }
+
+    pub(crate) fn message(self, title: &str, msg: &str, dialog_type: DialogType) {
+        let (title, msg) = (title.to_owned(), msg.to_owned());
+        match self {
+            Self::Zenity => {
+                let kind = match dialog_type {
+                    DialogType::Simple => "--info",
+                    DialogType::Warning => "--warning",
+                    DialogType::Error => "--error",
+                };
+                self.run(&[
+                    kind.to_owned(),
+                    "--no-markup".to_owned(),
+                    "--title".to_owned(),
+                    title,
+                    "--text".to_owned(),
+                    msg,
+                ]);
+            }
+            Self::Kdialog => {
+                let kind = match dialog_type {
+                    DialogType::Simple => "--msgbox",
+                    DialogType::Warning => "--sorry",
+                    DialogType::Error => "--error",
+                };
+                self.run(&[kind.to_owned(), msg, "--title".to_owned(), title]);
+            }
+            Self::Osascript => {
+                let icon = match dialog_type {
+                    DialogType::Simple => "",
+                    DialogType::Warning => " with icon caution",
+                    DialogType::Error => " with icon stop",
+                };
+                self.osascript(format!(
+                    "display dialog {} with title {} buttons {{\"OK\"}} default button 1{icon}",
+                    applescript_string(&msg),
+                    applescript_string(&title),
+                ));
+            }
+        }
+    }
+
+    pub(crate) fn question(self, title: &str, msg: &str, buttons: &QuestionDialogButtons) -> Reply {
+        if *buttons == QuestionDialogButtons::Yes {
+            self.message(title, msg, DialogType::Simple);
+            return Reply::Accepted;
+        }
+        let cancel = *buttons == QuestionDialogButtons::YesNoCancel;
+        let (title, msg) = (title.to_owned(), msg.to_owned());
+        match self {
+            Self::Zenity => {
+                let mut args = vec![
+                    "--question".to_owned(),
+                    "--no-markup".to_owned(),
+                    "--title".to_owned(),
+                    title,
+                    "--text".to_owned(),
+                    msg,
+                ];
+                if cancel {
+                    args.extend(["--extra-button".to_owned(), "Cancel".to_owned()]);
+                }
+                /* The extra button prints its label and exits with 1, like "No" does. */
+                match self.run(&args) {
+                    (Some(0), _) => Reply::Accepted,
+                    (Some(1), out) if out == "Cancel" => Reply::Cancelled,
+                    (Some(1), out) if out.is_empty() => Reply::Rejected,
+                    _ => Reply::Cancelled,
+                }
+            }
+            Self::Kdialog => {
+                let kind = if cancel { "--yesnocancel" } else { "--yesno" };
+                match self.run(&[kind.to_owned(), msg, "--title".to_owned(), title]).0 {
+                    Some(0) => Reply::Accepted,
+                    Some(1) => Reply::Rejected,
+                    _ => Reply::Cancelled,
+                }
+            }
+            Self::Osascript => {
+                let buttons = if cancel {
+                    "{\"Cancel\", \"No\", \"Yes\"}"
+                } else {
+                    "{\"No\", \"Yes\"}"
+                };
+                /* Pressing a button named "Cancel" makes the script fail instead of returning. */
+                match self.osascript(format!(
+                    "button returned of (display dialog {} with title {} buttons {buttons} \
+                     default button \"Yes\")",
+                    applescript_string(&msg),
+                    applescript_string(&title),
+                )) {
+                    (Some(0), out) if out == "Yes" => Reply::Accepted,
+                    (Some(0), out) if out == "No" => Reply::Rejected,
+                    _ => Reply::Cancelled,
+                }
+            }
+        }
+    }
+
+    pub(crate) fn file(
+        self,
+        title: &str,
+        dialog_type: FileDialogType,
+        extensions: &[String],
+    ) -> Option<PathBuf> {
+        let title = title.to_owned();
+        let patterns = extensions
+            .iter()
+            .map(|extension| format!("*.{extension}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (code, out) = match (self, dialog_type) {
+            (Self::Zenity, _) => {
+                let mut args = vec!["--file-selection".to_owned(), "--title".to_owned(), title];
+                if dialog_type == FileDialogType::SaveFile {
+                    args.push("--save".to_owned());
+                } else if !patterns.is_empty() {
+                    args.extend(["--file-filter".to_owned(), patterns]);
+                }
+                self.run(&args)
+            }
+            (Self::Kdialog, FileDialogType::OpenFile) => {
+                let mut args = vec!["--getopenfilename".to_owned(), ".".to_owned()];
+                if !patterns.is_empty() {
+                    args.push(patterns);
+                }
+                args.extend(["--title".to_owned(), title]);
+                self.run(&args)
+            }
+            (Self::Kdialog, FileDialogType::SaveFile) => self.run(&[
+                "--getsavefilename".to_owned(),
+                ".".to_owned(),
+                "--title".to_owned(),
+                title,
+            ]),
+            (Self::Osascript, FileDialogType::OpenFile) => self.osascript(format!(
+                "POSIX path of (choose file with prompt {})",
+                applescript_string(&title)
+            )),
+            (Self::Osascript, FileDialogType::SaveFile) => self.osascript(format!(
+                "POSIX path of (choose file name with prompt {})",
+                applescript_string(&title)
+            )),
+        };
+        (code == Some(0) && !out.is_empty()).then(|| PathBuf::from(out))
+    }
+}
@@ -0,0 +1,90 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Bookkeeping of the native objects this crate owns, for the `debug-assert-ffi` feature.
+//!
+//! Every object NvDialog hands out is registered with [`track`] and unregistered with
+//! [`release`] right before it is freed. Escape hatches returning native pointers go through
+//! [`check`]. Freeing an object twice, or using one that was already freed, then panics with the
+//! address involved instead of corrupting memory silently. Outside of debug builds with the
+//! feature enabled, these functions compile to nothing.
+
+#[cfg(all(feature = "debug-assert-ffi", debug_assertions))]
+use std::{collections::BTreeSet, sync::Mutex};
+
+#[cfg(all(feature = "debug-assert-ffi", debug_assertions))]
+static LIVE: Mutex<BTreeSet<usize>> = Mutex::new(BTreeSet::new());
+
+/// Registers `raw` as a live native object, and returns it. `NULL` is ignored.
+#[inline]
+pub(crate) fn track<T>(raw: *mut T) -> *mut T {
+    #[cfg(all(feature = "debug-assert-ffi", debug_assertions))]
+    if !raw.is_null() {
+        let inserted = LIVE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(raw as usize);
+        assert!(
+            inserted,
+            "nvdialog-rs: native object {raw:p} is owned twice; it will be freed twice"
+        );
+    }
+    raw
+}
+
+/// Unregisters `raw`, which is about to be freed. `NULL` is ignored.
+///
+/// # Panics
+/// Panics if `raw` isn't live, meaning it was already freed.
+#[inline]
+pub(crate) fn release<T>(raw: *mut T) {
+    #[cfg(all(feature = "debug-assert-ffi", debug_assertions))]
+    if !raw.is_null() {
+        let removed = LIVE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(raw as usize));
+        assert!(removed, "nvdialog-rs: double free of native object {raw:p}");
+    }
+    #[cfg(not(all(feature = "debug-assert-ffi", debug_assertions)))]
+    let _ = raw;
+}
+
+/// Checks that `raw` is still live before it is used. `NULL` is ignored.
+///
+/// # Panics
+/// Panics if `raw` was already freed.
+#[inline]
+pub(crate) fn check<T>(raw: *mut T) {
+    #[cfg(all(feature = "debug-assert-ffi", debug_assertions))]
+    if !raw.is_null() {
+        let live = LIVE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&(raw as usize));
+        assert!(live, "nvdialog-rs: use after free of native object {raw:p}");
+    }
+    #[cfg(not(all(feature = "debug-assert-ffi", debug_assertions)))]
+    let _ = raw;
+}
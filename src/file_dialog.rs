@@ -22,13 +22,19 @@
  * IN THE SOFTWARE.
  */
 
-use crate::c_string;
+use crate::{
+    util::{free_raw, os_str_bytes, os_string_from_bytes, with_owner, with_truncated_c_strings},
+    CancelToken, DialogInfo, DialogKind, Error, FileDialogSpec, Object,
+};
 use nvdialog_sys::ffi::*;
 use std::{
-    ffi::{c_char, CStr},
-    path::PathBuf,
+    cell::Cell,
+    ffi::{c_char, c_void, CStr, CString, OsStr},
+    io,
+    path::{Path, PathBuf},
     ptr::null_mut,
 };
+use thiserror::Error;
 
 /// # Mode of the file dialog
 /// A file dialog may either be used for getting a file (`OpenFile`) or
@@ -43,18 +49,59 @@ use std::{
 /// }
 ///
 /// fn main() {
-///     let dialog = FileDialog::new(
+///     let mut dialog = FileDialog::new(
 ///             "Choose a file",
-///             &FileDialogType::OpenFile
+///             FileDialogType::OpenFile,
+///             Some(["txt", "md"]),
 ///     );
 ///     println!("Filename: {:?}", dialog.retrieve_filename());
 /// }
 /// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum FileDialogType {
     OpenFile,
     SaveFile,
 }
 
+/// Why a file chosen in a [`FileDialog`] was rejected by the checks enabled with
+/// [`FileDialog::validate`], [`FileDialog::canonicalize`] or [`FileDialog::restrict_to`].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum FileValidationError {
+    /// The file doesn't exist, for example because it was deleted while the dialog was open.
+    #[error("{} doesn't exist", .0.display())]
+    NotFound(PathBuf),
+    /// The file exists but can't be opened for reading.
+    #[error("{} can't be read: {}", .path.display(), io::Error::from(*.kind))]
+    NotReadable { path: PathBuf, kind: io::ErrorKind },
+    /// The file's extension isn't one of those the dialog was created with.
+    #[error("{} doesn't have one of the expected extensions", .0.display())]
+    ExtensionMismatch(PathBuf),
+    /// Once symbolic links are resolved, the file isn't inside the directory set with
+    /// [`FileDialog::restrict_to`].
+    #[error("{} is outside of the allowed directory", .0.display())]
+    OutsideRoot(PathBuf),
+}
+
+impl FileValidationError {
+    fn from_io(path: &Path, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => Self::NotFound(path.to_path_buf()),
+            kind => Self::NotReadable {
+                path: path.to_path_buf(),
+                kind,
+            },
+        }
+    }
+}
+
+impl From<FileValidationError> for Error {
+    fn from(error: FileValidationError) -> Self {
+        Error::InaccessibleFile.context(error.to_string())
+    }
+}
+
 /// A struct representing a file dialog window.
 ///
 /// This struct is used to display a file dialog window to the user,
@@ -69,7 +116,7 @@ pub enum FileDialogType {
 /// use nvdialog::FileDialog;
 /// use nvdialog::FileDialogType;
 ///
-/// let mut file_dialog = FileDialog::new("Title", FileDialogType::OpenFile);
+/// let mut file_dialog = FileDialog::new("Title", FileDialogType::OpenFile, Some(["txt"]));
 /// let path = PathBuf::new();
 /// if let Some(filename) = file_dialog.retrieve_filename() {
 ///     path = filename;
@@ -82,7 +129,19 @@ pub enum FileDialogType {
 /// Matches with `NvdFileDialog`.
 pub struct FileDialog {
     raw: *mut NvdFileDialog,
+    title: String,
+    dialog_type: FileDialogType,
     location_chosen: Option<String>,
+    extensions: Vec<String>,
+    owner: *mut c_void,
+    suppressed: bool,
+    add_to_recent: bool,
+    /* Whether the last file came from the native dialog, as only those go to the recent list. */
+    picked_natively: Cell<bool>,
+    validate: bool,
+    canonicalize: bool,
+    root: Option<PathBuf>,
+    cancel: Option<CancelToken>,
 }
 
 impl FileDialog {
@@ -105,62 +164,300 @@ impl FileDialog {
     /// the case of `FileDialogType::SaveFile`, the dialog defaults to
     /// suggesting a filename of "filename".
     ///
+    /// If [`crate::init`] hasn't been called in this thread, no native dialog is
+    /// created and [`FileDialog::retrieve_filename`] returns `None` without showing anything.
+    /// If `title` contains a null byte, only the text before it is shown.
+    ///
+    /// The dialog goes through the interceptor registered with
+    /// [`set_dialog_interceptor`](crate::set_dialog_interceptor) first, if any.
+    ///
     /// # Examples
     ///
     /// Creating a new `FileDialog` instance for opening a file:
     ///
     /// ```
-    /// let file_dialog = FileDialog::new("Open File", FileDialogType::OpenFile);
+    /// let file_dialog = FileDialog::new("Open File", FileDialogType::OpenFile, Some(["txt"]));
     /// ```
     ///
-    /// Creating a new `FileDialog` instance for saving a file:
+    /// Creating a new `FileDialog` instance for saving a file, without filtering the files shown:
     ///
     /// ```
-    /// let file_dialog = FileDialog::new("Save File", FileDialogType::SaveFile);
+    /// let file_dialog = FileDialog::new("Save File", FileDialogType::SaveFile, None::<[&str; 0]>);
     /// ```
     pub fn new<S: AsRef<str>>(
         title: S,
         type_of_dialog: FileDialogType,
         file_extensions: Option<impl IntoIterator<Item = S>>,
     ) -> Self {
+        let extensions = file_extensions
+            .into_iter()
+            .flatten()
+            .map(|extension| String::from(extension.as_ref()))
+            .collect();
+        Self::create(
+            title.as_ref(),
+            type_of_dialog,
+            extensions,
+            OsStr::new("filename"),
+        )
+    }
+
+    /// Creates a new save dialog that suggests `suggested_name` as the name of the file.
+    ///
+    /// `suggested_name` can come straight from the filesystem: on Unix it is passed to the
+    /// toolkit byte for byte, even if it isn't valid UTF-8. If it contains a null byte, only the
+    /// part before it is suggested. Otherwise this behaves like [`FileDialog::new`] with
+    /// [`FileDialogType::SaveFile`].
+    ///
+    /// # Examples
+    /// ```
+    /// use nvdialog_rs::FileDialog;
+    /// use std::path::Path;
+    ///
+    /// let document = Path::new("/home/user/Documents/report.odt");
+    /// let mut dialog = FileDialog::new_save("Save a copy", document.file_name().unwrap());
+    /// ```
+    pub fn new_save<S: AsRef<str>, N: AsRef<OsStr>>(title: S, suggested_name: N) -> Self {
+        Self::create(
+            title.as_ref(),
+            FileDialogType::SaveFile,
+            Vec::new(),
+            suggested_name.as_ref(),
+        )
+    }
+
+    fn create(
+        title: &str,
+        type_of_dialog: FileDialogType,
+        extensions: Vec<String>,
+        suggested_name: &OsStr,
+    ) -> Self {
+        crate::init::check_thread();
+        let (spec, suppressed) = crate::intercept::intercept(FileDialogSpec {
+            title: String::from(title),
+            mode: type_of_dialog,
+            extensions,
+        });
+        let mut dialog = Self {
+            raw: null_mut(),
+            title: spec.title,
+            dialog_type: spec.mode,
+            location_chosen: None,
+            extensions: spec.extensions,
+            owner: null_mut(),
+            suppressed,
+            add_to_recent: false,
+            picked_natively: Cell::new(false),
+            validate: false,
+            canonicalize: false,
+            root: None,
+            cancel: None,
+        };
+
+        #[cfg(feature = "auto-reply")]
+        if crate::auto_reply_enabled() {
+            return dialog;
+        }
+
+        if suppressed || !crate::init::native_available() {
+            return dialog;
+        }
+
         /* Just converting this into a format NvDialog will understand */
-        let mut extensions = String::new();
-        if file_extensions.is_some() {
-            for extension in file_extensions.unwrap() {
-                extensions += extension.as_ref();
-                extensions += ";";
-                extensions += "\0";
-            }
+        let mut filter = String::new();
+        for extension in &dialog.extensions {
+            filter += extension;
+            filter += ";";
+            filter += "\0";
         }
-        match type_of_dialog {
+        dialog.raw = match dialog.dialog_type {
             FileDialogType::OpenFile => {
-                let t = c_string!(title.as_ref());
-                Self {
-                raw: unsafe {
+                with_truncated_c_strings([dialog.title.as_str()], |[t]| unsafe {
                     nvd_open_file_dialog_new(
-                        t.as_ptr(),
-                        if extensions.is_empty() {
+                        t,
+                        if filter.is_empty() {
                             null_mut()
                         } else {
-                            extensions.as_ptr() as *const c_char
+                            filter.as_ptr() as *const c_char
                         },
                     )
-                },
-                location_chosen: None,
-            }},
+                })
+            }
             FileDialogType::SaveFile => {
-                let t = c_string!(title.as_ref());
-                let f = c_string!("filename");
-                Self {
-                    raw: unsafe {
-                        nvd_save_file_dialog_new(
-                            t.as_ptr(),
-                            f.as_ptr()
-                        )
-                    },
-                    location_chosen: None,
-                }
+                let name = os_str_bytes(suggested_name);
+                let name = name.split(|byte| *byte == 0).next().unwrap_or_default();
+                let name = CString::new(name).unwrap_or_default();
+                with_truncated_c_strings([dialog.title.as_str()], |[t]| unsafe {
+                    nvd_save_file_dialog_new(t, name.as_ptr())
+                })
             }
+        };
+        crate::ffi_check::track(dialog.raw);
+        dialog
+    }
+
+    /// Creates a new `FileDialog` whose title is resolved from a message key through the
+    /// registered [`Localizer`](crate::Localizer), with `args` substituted in.
+    ///
+    /// Keys without a translation are shown as-is. See [`crate::localize`] for details.
+    #[cfg(feature = "i18n")]
+    pub fn new_localized(
+        title_key: &str,
+        args: &[(&str, &str)],
+        type_of_dialog: FileDialogType,
+        file_extensions: Option<impl IntoIterator<Item = String>>,
+    ) -> Self {
+        Self::new(crate::localize(title_key, args), type_of_dialog, file_extensions)
+    }
+
+    /// Registers the file the user picks with the platform's recent documents, so that it shows
+    /// up in the "Recent" sections of the file manager and the Start menu, or in the Dock menu of
+    /// the application on macOS.
+    ///
+    /// This is off by default. Only files chosen in the native dialog are registered, not those
+    /// returned by fallback backends or scripted replies, and only once they pass the checks
+    /// enabled with [`FileDialog::validate`] and [`FileDialog::restrict_to`]. With
+    /// [`FileDialog::canonicalize`], the canonical path is the one registered.
+    ///
+    /// # FFI
+    /// Uses `GtkRecentManager` on Linux, `SHAddToRecentDocs` on Windows and
+    /// `NSDocumentController` on macOS.
+    pub fn add_to_recent(mut self, add: bool) -> Self {
+        self.add_to_recent = add;
+        self
+    }
+
+    /// Checks the file the user picks before returning it, so that applications aren't handed
+    /// paths that fail as soon as they are opened: the file of an open dialog must exist and be
+    /// readable, and with both kinds of dialogs its extension must be one of those the dialog was
+    /// created with, if any.
+    ///
+    /// [`FileDialog::try_retrieve_filename`] then returns a [`FileValidationError`] for files
+    /// that fail the checks, and [`FileDialog::retrieve_filename`] returns `None`.
+    pub fn validate(mut self, enabled: bool) -> Self {
+        self.validate = enabled;
+        self
+    }
+
+    /// Returns the chosen file as an absolute path, with `.` and `..` components removed and
+    /// symbolic links resolved, instead of the path the toolkit reported.
+    ///
+    /// The file of an open dialog must exist for this to succeed, and the directory of the file of
+    /// a save dialog; [`FileDialog::try_retrieve_filename`] returns a [`FileValidationError`]
+    /// otherwise.
+    pub fn canonicalize(mut self, enabled: bool) -> Self {
+        self.canonicalize = enabled;
+        self
+    }
+
+    /// Only accepts files inside `root` or its subdirectories, for applications that shouldn't
+    /// touch files elsewhere. This enables [`FileDialog::canonicalize`], so that symbolic links
+    /// and `..` components can't be used to escape `root`.
+    ///
+    /// [`FileDialog::try_retrieve_filename`] returns [`FileValidationError::OutsideRoot`] for
+    /// files outside of `root`, and [`FileDialog::retrieve_filename`] returns `None`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::{FileDialog, FileDialogType};
+    ///
+    /// let mut dialog = FileDialog::new("Import a level", FileDialogType::OpenFile, Some(["level"]))
+    ///     .restrict_to("/home/user/.local/share/game/levels");
+    /// if let Ok(Some(level)) = dialog.try_retrieve_filename() {
+    ///     println!("Importing {}", level.display());
+    /// }
+    /// ```
+    pub fn restrict_to<P: AsRef<Path>>(mut self, root: P) -> Self {
+        self.root = Some(root.as_ref().to_path_buf());
+        self.canonicalize = true;
+        self
+    }
+
+    /// Lets `token` close this dialog while it is shown, in which case no file is returned. See
+    /// [`CancelToken`].
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Shows a preview of the selected file next to the file list, when it is an image, for
+    /// flows such as picking an icon.
+    ///
+    /// This only changes GTK file dialogs: the Windows and macOS dialogs have their own preview
+    /// (the Explorer preview pane and Quick Look), which the user controls. It does nothing if the
+    /// backend doesn't create the dialog's window before it is shown.
+    ///
+    /// # FFI
+    /// Sets a `GtkImage` as the preview widget of the `GtkFileChooser`, updated on
+    /// `update-preview`.
+    pub fn image_preview(self, enabled: bool) -> Self {
+        #[cfg(target_os = "linux")]
+        if enabled && crate::init::NATIVE_CODE {
+            let widget = unsafe { self.native_widget() };
+            if !widget.is_null() {
+                unsafe { attach_image_preview(widget) };
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = enabled;
+        self
+    }
+
+    /// Makes the window with the handle `hwnd` the owner of this file dialog, so that it stays on top
+    /// of the application's window and doesn't get its own taskbar entry. Pass a null pointer to
+    /// go back to NvDialog's global parent window.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_set_parent`, which is called right before the file dialog is shown and
+    /// undone afterwards.
+    #[cfg(target_os = "windows")]
+    pub fn set_owner_hwnd(&mut self, hwnd: *mut c_void) {
+        self.owner = hwnd;
+    }
+
+    /// Returns the toolkit's own object behind this file dialog: a `GtkWidget*` with GTK, an `HWND`
+    /// on Windows or an `NSWindow*` on macOS. Returns a null pointer if no native file dialog was
+    /// created, or if the backend doesn't create the window before it is shown.
+    ///
+    /// This is unsupported territory, meant for tweaking properties this crate has no API for.
+    ///
+    /// # Safety
+    /// The pointer is owned by NvDialog and invalid once the file dialog is dropped. Changing the
+    /// object in ways NvDialog doesn't expect (destroying it, replacing its contents...) is
+    /// undefined behavior.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_file_dialog_get_raw`.
+    pub unsafe fn native_widget(&self) -> *mut c_void {
+        if self.raw.is_null() {
+            return std::ptr::null_mut();
+        }
+        crate::ffi_check::check(self.raw);
+        nvd_file_dialog_get_raw(self.raw)
+    }
+
+    /// Returns the title of the file dialog.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// File dialogs have no message, so this always returns an empty string. It exists for
+    /// consistency with the other dialog types.
+    pub fn message(&self) -> &str {
+        ""
+    }
+
+    /// Returns the kind of the dialog, which is always [`DialogKind::File`].
+    pub fn kind(&self) -> DialogKind {
+        DialogKind::File(self.dialog_type)
+    }
+
+    /// Returns a [`DialogInfo`] describing what the file dialog is going to display.
+    pub fn info(&self) -> DialogInfo {
+        DialogInfo {
+            kind: self.kind(),
+            title: self.title.clone(),
+            message: String::new(),
         }
     }
 
@@ -172,26 +469,18 @@ impl FileDialog {
     /// function from the underlying C API. If the returned pointer from NvDialog is `NULL`,
     /// this function returns `None`. If the pointer is not null, this function constructs a
     /// `CStr` instance from the raw buffer, and constructs a `PathBuf`
-    /// instance from the `CStr`.
-    ///
-    /// # Safety
-    /// This function constructs the returned [`PathBuf`](std::path::PathBuf) using C bytes, which may
-    /// cause a panic if the C data contains non-valid UTF-8 characters.
+    /// instance from its bytes. On Unix, paths that aren't valid UTF-8 are returned unchanged.
     ///
     /// # Returns
     ///
     /// - `Some(PathBuf)` if a file was selected and the selected file name
     ///   could be converted to a `PathBuf` instance.
-    /// - `None` if no file was selected.
-    ///
-    /// # Panics
-    /// This function may panic with the message "Invalid UTF-8 data" if the
-    /// raw buffer returned from the underlying C API contains invalid
-    /// UTF-8 data.
+    /// - `None` if no file was selected, or if the file failed one of the checks enabled with
+    ///   [`FileDialog::validate`], [`FileDialog::canonicalize`] or [`FileDialog::restrict_to`].
     ///
     /// # Examples
     /// ```
-    /// let mut file_dialog = FileDialog::new("Open File", FileDialogType::OpenFile);
+    /// let mut file_dialog = FileDialog::new("Open File", FileDialogType::OpenFile, Some(["txt"]));
     ///
     /// if let Some(path) = file_dialog.retrieve_filename() {
     ///     // A file was selected. Do something with the file...
@@ -200,16 +489,251 @@ impl FileDialog {
     /// }
     /// ```
     pub fn retrieve_filename(&mut self) -> Option<PathBuf> {
-        let raw_buffer: *mut c_char = null_mut();
-        unsafe {
-            nvd_get_file_location(self.raw, &raw_buffer as *const _ as *mut _);
+        self.try_retrieve_filename().ok().flatten()
+    }
+
+    /// Same as [`FileDialog::retrieve_filename`], but reports why the chosen file was rejected
+    /// when [`FileDialog::validate`], [`FileDialog::canonicalize`] or [`FileDialog::restrict_to`]
+    /// is enabled.
+    ///
+    /// # Errors
+    /// Returns a [`FileValidationError`] if the chosen file fails one of the enabled checks.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::{FileDialog, FileDialogType};
+    ///
+    /// let mut dialog = FileDialog::new("Open a picture", FileDialogType::OpenFile, Some(["png", "jpg"]))
+    ///     .validate(true);
+    /// match dialog.try_retrieve_filename() {
+    ///     Ok(Some(path)) => println!("Opening {}", path.display()),
+    ///     Ok(None) => println!("No file selected."),
+    ///     Err(e) => eprintln!("Can't open this file: {e}"),
+    /// }
+    /// ```
+    pub fn try_retrieve_filename(&mut self) -> Result<Option<PathBuf>, FileValidationError> {
+        let Some(mut path) = self.pick() else {
+            return Ok(None);
+        };
+        if self.canonicalize {
+            path = self.canonical(&path)?;
+        }
+        if let Some(root) = &self.root {
+            /* Without a canonical root, a symbolic link in it would reject every file. */
+            let root = root.canonicalize().unwrap_or_else(|_| root.clone());
+            if !path.starts_with(root) {
+                return Err(FileValidationError::OutsideRoot(path));
+            }
+        }
+        if self.validate {
+            self.check(&path)?;
+        }
+        if self.add_to_recent && self.picked_natively.get() {
+            crate::recent::add(&path);
+        }
+        Ok(Some(path))
+    }
+
+    /// Canonicalizes `path` as described in [`FileDialog::canonicalize`].
+    fn canonical(&self, path: &Path) -> Result<PathBuf, FileValidationError> {
+        match path.canonicalize() {
+            Ok(path) => Ok(path),
+            /* The file of a save dialog usually doesn't exist yet, but its directory must. */
+            Err(e)
+                if e.kind() == io::ErrorKind::NotFound
+                    && self.dialog_type == FileDialogType::SaveFile =>
+            {
+                let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                    return Err(FileValidationError::from_io(path, e));
+                };
+                parent
+                    .canonicalize()
+                    .map(|parent| parent.join(name))
+                    .map_err(|e| FileValidationError::from_io(path, e))
+            }
+            Err(e) => Err(FileValidationError::from_io(path, e)),
+        }
+    }
+
+    /// Checks `path` as described in [`FileDialog::validate`].
+    fn check(&self, path: &Path) -> Result<(), FileValidationError> {
+        if self.dialog_type == FileDialogType::OpenFile {
+            if let Err(e) = std::fs::File::open(path) {
+                return Err(FileValidationError::from_io(path, e));
+            }
+        }
+        if self.extensions.is_empty() {
+            return Ok(());
+        }
+        let extension = path.extension().map(|e| e.to_string_lossy());
+        let matches = extension.is_some_and(|extension| {
+            self.extensions.iter().any(|expected| {
+                expected
+                    .trim_start_matches("*.")
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(&extension)
+            })
+        });
+        if matches {
+            Ok(())
+        } else {
+            Err(FileValidationError::ExtensionMismatch(path.to_path_buf()))
+        }
+    }
+
+    fn pick(&mut self) -> Option<PathBuf> {
+        crate::init::check_thread();
+        if self.suppressed {
+            return None;
+        }
+        let file = crate::instrument::instrument(
+            || self.info(),
+            || match &self.cancel {
+                Some(token) => token
+                    .run(self.showing(), || self.fetch_filename())
+                    .flatten(),
+                None => self.fetch_filename(),
+            },
+            |file| Some(crate::Decision::File(file.clone())),
+        );
+        crate::audit::log_decision(|| crate::DialogEvent {
+            dialog: self.info(),
+            decision: crate::Decision::File(file.clone()),
+        });
+        #[cfg(feature = "recorder")]
+        crate::recorder::record(
+            "file",
+            &self.title,
+            "",
+            file.as_ref().map(|path| path.to_string_lossy().into_owned()),
+        );
+        file
+    }
+
+    /// Returns what closes this dialog while it is shown, for its [`CancelToken`].
+    fn showing(&self) -> crate::cancel::Showing {
+        #[cfg(feature = "mock")]
+        if crate::backend::fallback() == Some(crate::Backend::Mock) {
+            return crate::cancel::Showing::Mock(crate::mock::hold_next_file_dialog());
+        }
+        crate::cancel::showing(unsafe { self.native_widget() })
+    }
+
+    fn fetch_filename(&self) -> Option<PathBuf> {
+        self.picked_natively.set(false);
+        #[cfg(feature = "recorder")]
+        if let Some(file) = crate::recorder::replay_file() {
+            return file;
         }
+        #[cfg(feature = "auto-reply")]
+        if let Some(file) = crate::auto_reply::next_file() {
+            return file;
+        }
+        #[cfg(feature = "noop")]
+        if crate::backend::fallback().is_none() {
+            return crate::noop::file();
+        }
+
+        if self.raw.is_null() {
+            if let Some(backend) = crate::backend::fallback() {
+                return backend.file(&self.title, self.dialog_type, &self.extensions);
+            }
+            return None;
+        }
+        let mut raw_buffer: *const c_char = std::ptr::null();
+        with_owner(self.owner, || unsafe {
+            nvd_get_file_location(self.raw, &mut raw_buffer);
+        });
         if raw_buffer.is_null() {
             return None;
         }
         let filename = unsafe { CStr::from_ptr(raw_buffer) };
-        Some(PathBuf::from(
-            filename.to_str().expect("Invalid UTF-8 data"),
-        ))
+        self.picked_natively.set(true);
+        Some(PathBuf::from(os_string_from_bytes(filename.to_bytes())))
     }
 }
+
+impl Object for FileDialog {
+    type NativeType = NvdFileDialog;
+    type ReturnValue = Option<PathBuf>;
+
+    fn get_raw(&self) -> *mut NvdFileDialog {
+        crate::ffi_check::check(self.raw);
+        self.raw
+    }
+
+    fn show(&mut self) -> Result<Option<PathBuf>, Error> {
+        Ok(self.try_retrieve_filename()?)
+    }
+
+    fn free(&mut self) {
+        unsafe { free_raw(std::mem::replace(&mut self.raw, std::ptr::null_mut())) };
+    }
+}
+
+impl Drop for FileDialog {
+    fn drop(&mut self) {
+        unsafe { free_raw(self.raw) };
+    }
+}
+
+/// Sets an image preview on the `GtkFileChooser` `chooser`, hidden for files that aren't images.
+#[cfg(target_os = "linux")]
+unsafe fn attach_image_preview(chooser: *mut c_void) {
+    use std::ffi::{c_int, c_ulong};
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_image_new() -> *mut c_void;
+        fn gtk_image_set_from_pixbuf(image: *mut c_void, pixbuf: *mut c_void);
+        fn gtk_file_chooser_set_preview_widget(chooser: *mut c_void, widget: *mut c_void);
+        fn gtk_file_chooser_set_preview_widget_active(chooser: *mut c_void, active: c_int);
+        fn gtk_file_chooser_get_preview_filename(chooser: *mut c_void) -> *mut c_char;
+        fn gdk_pixbuf_new_from_file_at_size(
+            filename: *const c_char,
+            width: c_int,
+            height: c_int,
+            error: *mut *mut c_void,
+        ) -> *mut c_void;
+        fn g_signal_connect_data(
+            instance: *mut c_void,
+            signal: *const c_char,
+            handler: *const c_void,
+            data: *mut c_void,
+            destroy_data: *const c_void,
+            flags: c_int,
+        ) -> c_ulong;
+        fn g_object_unref(object: *mut c_void);
+        fn g_free(mem: *mut c_void);
+    }
+
+    const PREVIEW_SIZE: c_int = 192;
+
+    unsafe extern "C" fn update_preview(chooser: *mut c_void, image: *mut c_void) {
+        let filename = gtk_file_chooser_get_preview_filename(chooser);
+        let pixbuf = if filename.is_null() {
+            null_mut()
+        } else {
+            /* Fails for anything GdkPixbuf can't decode, which hides the preview. */
+            gdk_pixbuf_new_from_file_at_size(filename, PREVIEW_SIZE, PREVIEW_SIZE, null_mut())
+        };
+        g_free(filename as *mut c_void);
+        gtk_image_set_from_pixbuf(image, pixbuf);
+        gtk_file_chooser_set_preview_widget_active(chooser, c_int::from(!pixbuf.is_null()));
+        if !pixbuf.is_null() {
+            g_object_unref(pixbuf);
+        }
+    }
+
+    let image = gtk_image_new();
+    /* The chooser takes the floating reference, and keeps the image alive for the handler. */
+    gtk_file_chooser_set_preview_widget(chooser, image);
+    let handler: unsafe extern "C" fn(*mut c_void, *mut c_void) = update_preview;
+    g_signal_connect_data(
+        chooser,
+        c"update-preview".as_ptr(),
+        handler as *const c_void,
+        image,
+        std::ptr::null(),
+        0,
+    );
+}
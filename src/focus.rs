@@ -0,0 +1,97 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Queries the desktop's do-not-disturb state.
+
+/// Returns `true` if the desktop currently hides notifications, because the user enabled
+/// do-not-disturb, focus assist or a similar mode.
+///
+/// Notifications sent in that state are usually swallowed without an error, so applications
+/// can check this first and show an in-app banner instead. The answer is a best effort:
+/// - On Linux, GNOME's "Do Not Disturb" setting (`org.gnome.desktop.notifications
+///   show-banners`) is read. Other desktops are reported as not suppressing notifications.
+/// - On Windows, this is `true` when the user is busy, presenting, running a fullscreen
+///   application or in quiet hours, as reported by `SHQueryUserNotificationState`.
+/// - On macOS, Focus modes can't be queried without special entitlements, so this is always
+///   `false`.
+pub fn notifications_suppressed() -> bool {
+    crate::init::NATIVE_CODE && native_suppressed()
+}
+
+#[cfg(target_os = "linux")]
+fn native_suppressed() -> bool {
+    use std::ffi::{c_char, c_int, c_void};
+
+    const SCHEMA: &std::ffi::CStr = c"org.gnome.desktop.notifications";
+
+    crate::gtk_link::gtk_extern! {
+        fn g_settings_schema_source_get_default() -> *mut c_void;
+        fn g_settings_schema_source_lookup(
+            source: *mut c_void,
+            schema_id: *const c_char,
+            recursive: c_int,
+        ) -> *mut c_void;
+        fn g_settings_schema_unref(schema: *mut c_void);
+        fn g_settings_new(schema_id: *const c_char) -> *mut c_void;
+        fn g_settings_get_boolean(settings: *mut c_void, key: *const c_char) -> c_int;
+        fn g_object_unref(object: *mut c_void);
+    }
+
+    unsafe {
+        /* `g_settings_new` aborts on unknown schemas, which is the case outside GNOME. */
+        let source = g_settings_schema_source_get_default();
+        if source.is_null() {
+            return false;
+        }
+        let schema = g_settings_schema_source_lookup(source, SCHEMA.as_ptr(), 1);
+        if schema.is_null() {
+            return false;
+        }
+        g_settings_schema_unref(schema);
+
+        let settings = g_settings_new(SCHEMA.as_ptr());
+        let show_banners = g_settings_get_boolean(settings, c"show-banners".as_ptr());
+        g_object_unref(settings);
+        show_banners == 0
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn native_suppressed() -> bool {
+    const QUNS_ACCEPTS_NOTIFICATIONS: i32 = 5;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+    }
+
+    let mut state = QUNS_ACCEPTS_NOTIFICATIONS;
+    let result = unsafe { SHQueryUserNotificationState(&mut state) };
+    result >= 0 && state != QUNS_ACCEPTS_NOTIFICATIONS
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn native_suppressed() -> bool {
+    false
+}
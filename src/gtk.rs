@@ -0,0 +1,104 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Helpers for applications that already run a GTK main loop.
+
+use std::ffi::{c_int, c_uint, c_void};
+
+const G_PRIORITY_DEFAULT_IDLE: c_int = 200;
+const G_SOURCE_REMOVE: c_int = 0;
+
+#[repr(C)]
+struct GMainContext {
+    _private: [u8; 0],
+}
+
+crate::gtk_link::gtk_extern! {
+    fn g_idle_add_full(
+        priority: c_int,
+        function: extern "C" fn(*mut c_void) -> c_int,
+        data: *mut c_void,
+        notify: extern "C" fn(*mut c_void),
+    ) -> c_uint;
+    fn g_main_context_invoke_full(
+        context: *mut GMainContext,
+        priority: c_int,
+        function: extern "C" fn(*mut c_void) -> c_int,
+        data: *mut c_void,
+        notify: extern "C" fn(*mut c_void),
+    );
+}
+
+/* `None` once the closure has run; the box itself is freed by `destroy`. */
+type Job = Option<Box<dyn FnOnce() + Send>>;
+
+extern "C" fn run(data: *mut c_void) -> c_int {
+    let job = unsafe { &mut *(data as *mut Job) };
+    if let Some(job) = job.take() {
+        job();
+    }
+    G_SOURCE_REMOVE
+}
+
+extern "C" fn destroy(data: *mut c_void) {
+    drop(unsafe { Box::from_raw(data as *mut Job) });
+}
+
+fn into_data<F: FnOnce() + Send + 'static>(f: F) -> *mut c_void {
+    let job: Job = Some(Box::new(f));
+    Box::into_raw(Box::new(job)) as *mut c_void
+}
+
+/// Schedules `f` to run once from the default `GMainContext` the next time it is idle.
+///
+/// Use this to show dialogs from an application that already runs a GTK main loop: `f` runs from
+/// the host's own loop, after pending events have been handled, instead of from inside another
+/// signal handler. NvDialog still runs each dialog modally while it is shown, like
+/// `gtk_dialog_run` does, so `f` returns once the dialog is closed.
+///
+/// This can be called from any thread; `f` always runs on the thread owning the default context.
+pub fn idle_add<F: FnOnce() + Send + 'static>(f: F) {
+    unsafe {
+        g_idle_add_full(G_PRIORITY_DEFAULT_IDLE, run, into_data(f), destroy);
+    }
+}
+
+/// Runs `f` on the thread owning the default `GMainContext`.
+///
+/// If the calling thread owns the context, `f` runs immediately. Otherwise it is dispatched
+/// through the context, like [`idle_add`], and this function returns without waiting for it.
+///
+/// # FFI
+/// Corresponds to `g_main_context_invoke_full`.
+pub fn invoke<F: FnOnce() + Send + 'static>(f: F) {
+    unsafe {
+        g_main_context_invoke_full(
+            std::ptr::null_mut(),
+            G_PRIORITY_DEFAULT_IDLE,
+            run,
+            into_data(f),
+            destroy,
+        );
+    }
+}
@@ -0,0 +1,83 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! How the GTK, GDK and GLib functions the crate calls directly are linked.
+//!
+//! They are normally linked like the rest of GTK. With the `no-gtk-link` feature, nothing links
+//! GTK at build time, so they are looked up in the process when called instead. A function that
+//! isn't loaded then does nothing and returns zero or `NULL`.
+
+use std::ffi::{c_char, c_void, CStr};
+
+/// Declares GTK functions like an `extern "C"` block, for the current link mode.
+macro_rules! gtk_extern {
+    ($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        #[cfg(not(feature = "no-gtk-link"))]
+        extern "C" {
+            $(fn $name($($arg: $ty),*) $(-> $ret)?;)*
+        }
+        $(
+            #[cfg(feature = "no-gtk-link")]
+            unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                type Function = unsafe extern "C" fn($($ty),*) $(-> $ret)?;
+                let name = concat!(stringify!($name), "\0");
+                match crate::gtk_link::symbol(name) {
+                    Some(symbol) => {
+                        let function: Function = std::mem::transmute(symbol);
+                        function($($arg),*)
+                    }
+                    None => std::mem::zeroed(),
+                }
+            }
+        )*
+    };
+}
+pub(crate) use gtk_extern;
+
+/// Returns the address of the function `name`, a null terminated string, if it is loaded.
+pub(crate) fn symbol(name: &str) -> Option<*mut c_void> {
+    /* `RTLD_DEFAULT`, which searches every object loaded globally. */
+    const RTLD_DEFAULT: *mut c_void = std::ptr::null_mut();
+
+    extern "C" {
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    let name = CStr::from_bytes_with_nul(name.as_bytes()).ok()?;
+    let symbol = unsafe { dlsym(RTLD_DEFAULT, name.as_ptr()) };
+    (!symbol.is_null()).then_some(symbol)
+}
+
+/* Miri can't look up foreign functions. */
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loaded_functions_are_found() {
+        assert!(symbol("malloc\0").is_some());
+        assert!(symbol("nvdialog_rs_no_such_function\0").is_none());
+        assert!(symbol("malloc").is_none());
+    }
+}
@@ -0,0 +1,101 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use std::{cell::RefCell, collections::HashMap};
+
+/// Resolves message keys into text in the user's language.
+///
+/// Implement this trait on top of your translation system of choice (`fluent`, gettext catalogs,
+/// ...) and register it with [`set_localizer`]. The `*_localized` constructors of the dialogs
+/// then take message keys instead of text.
+///
+/// A simple implementation is provided for `HashMap<String, String>`, mapping keys to text in
+/// which `{name}` placeholders are replaced with the argument called `name`.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use nvdialog_rs::{DialogBox, DialogType};
+///
+/// let mut catalog = HashMap::new();
+/// catalog.insert("save-failed.title".to_owned(), "Speichern fehlgeschlagen".to_owned());
+/// catalog.insert("save-failed.body".to_owned(), "{file} konnte nicht gespeichert werden.".to_owned());
+/// nvdialog_rs::set_localizer(catalog);
+///
+/// let mut dialog = DialogBox::new_localized(
+///     "save-failed.title",
+///     "save-failed.body",
+///     &[("file", "notes.txt")],
+///     DialogType::Error,
+/// ).unwrap();
+/// dialog.show();
+/// ```
+pub trait Localizer {
+    /// Returns the text for `key` with `args` substituted in, or `None` if the key is unknown.
+    fn localize(&self, key: &str, args: &[(&str, &str)]) -> Option<String>;
+}
+
+impl Localizer for HashMap<String, String> {
+    fn localize(&self, key: &str, args: &[(&str, &str)]) -> Option<String> {
+        let mut text = self.get(key)?.clone();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        Some(text)
+    }
+}
+
+thread_local! {
+    static LOCALIZER: RefCell<Option<Box<dyn Localizer>>> = const { RefCell::new(None) };
+}
+
+/// Registers the [`Localizer`] used by the `*_localized` constructors in the current thread,
+/// replacing the previous one.
+pub fn set_localizer<L: Localizer + 'static>(localizer: L) {
+    LOCALIZER.with(|current| *current.borrow_mut() = Some(Box::new(localizer)));
+}
+
+/// Removes the registered [`Localizer`] of the current thread.
+pub fn clear_localizer() {
+    LOCALIZER.with(|current| *current.borrow_mut() = None);
+}
+
+/// Resolves `key` through the registered [`Localizer`].
+///
+/// If no localizer is registered or it doesn't know `key`, the key itself is returned, so a
+/// missing translation shows up as its key instead of an empty dialog.
+pub fn localize(key: &str, args: &[(&str, &str)]) -> String {
+    try_localize(key, args).unwrap_or_else(|| key.to_owned())
+}
+
+/// Resolves `key` through the registered [`Localizer`], or returns `None` if there is no
+/// translation for it, so that the crate's own text can fall back to English.
+pub(crate) fn try_localize(key: &str, args: &[(&str, &str)]) -> Option<String> {
+    LOCALIZER.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .and_then(|localizer| localizer.localize(key, args))
+    })
+}
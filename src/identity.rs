@@ -0,0 +1,120 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Application identity on Windows: the AppUserModelID notifications and dialogs are attributed
+//! to, and the manifest executables should embed.
+
+use crate::Error;
+use std::cell::Cell;
+
+/// An application manifest for executables showing NvDialog dialogs on Windows 10 and 11.
+///
+/// It enables version 6 of the common controls, which NvDialog's Windows backend is styled
+/// with, and per-monitor DPI awareness so dialogs aren't blurry on HiDPI displays. Windows only
+/// reads manifests embedded in the executable, so embed it from your application's build script,
+/// for example with the `embed-manifest` crate after writing it to `OUT_DIR`, or reference
+/// `assets/windows.manifest` from this crate's sources in a resource script.
+pub const WINDOWS_MANIFEST: &str = include_str!("../assets/windows.manifest");
+
+/* AppUserModelIDs are limited to 128 characters, including the terminator. */
+const MAX_ID_LENGTH: usize = 127;
+
+thread_local! {
+    static EXPLICIT_ID: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets the AppUserModelID of the process, which Windows uses to attribute notifications and
+/// dialog windows to the application: the name shown in toasts, and the taskbar button dialogs
+/// are grouped under.
+///
+/// `id` should look like `Company.Product.SubProduct`, and match the ID set on the Start menu
+/// shortcut of the application, if it has one; Windows only shows the application's icon in
+/// toasts when such a shortcut exists. Without a call to this function, [`crate::set_app_name`]
+/// derives an ID from the application name.
+///
+/// This should be called before [`crate::init`] and before any window is shown. It does nothing
+/// on platforms other than Windows.
+///
+/// # Errors
+/// Returns [`Error::EmptyString`] if `id` is empty, and [`Error::ParametersError`] if it is longer
+/// than 127 characters, contains spaces or contains a null byte.
+pub fn set_app_user_model_id<S: AsRef<str>>(id: S) -> Result<(), Error> {
+    let id = id.as_ref();
+    if id.is_empty() {
+        return Err(Error::EmptyString);
+    }
+    if id.chars().count() > MAX_ID_LENGTH || id.contains([' ', '\0']) {
+        return Err(Error::ParametersError);
+    }
+    apply(id)?;
+    EXPLICIT_ID.with(|explicit| explicit.set(true));
+    Ok(())
+}
+
+/// Called by [`crate::set_app_name`]: sets an AppUserModelID derived from `name`, unless one was
+/// set explicitly.
+pub(crate) fn sync_with_app_name(name: &str) -> Result<(), Error> {
+    if EXPLICIT_ID.with(|explicit| explicit.get()) {
+        return Ok(());
+    }
+    let id: String = name
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('.'),
+            c if c.is_alphanumeric() || c == '.' || c == '-' => Some(c),
+            _ => None,
+        })
+        .take(MAX_ID_LENGTH)
+        .collect();
+    if id.is_empty() {
+        return Ok(());
+    }
+    apply(&id)
+}
+
+#[cfg(target_os = "windows")]
+fn apply(id: &str) -> Result<(), Error> {
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SetCurrentProcessExplicitAppUserModelID(id: *const u16) -> i32;
+    }
+
+    if !crate::init::NATIVE_CODE {
+        return Ok(());
+    }
+    let wide: Vec<u16> = id.encode_utf16().chain(std::iter::once(0)).collect();
+    match unsafe { SetCurrentProcessExplicitAppUserModelID(wide.as_ptr()) } {
+        result if result >= 0 => Ok(()),
+        result => Err(Error::backend(
+            result,
+            Some("SetCurrentProcessExplicitAppUserModelID failed"),
+        )
+        .context("Failed to set the AppUserModelID")),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn apply(_id: &str) -> Result<(), Error> {
+    Ok(())
+}
@@ -0,0 +1,501 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{util::with_c_strings, Error};
+use nvdialog_sys::ffi::*;
+use std::{
+    ffi::{c_int, c_void},
+    path::Path,
+};
+use thiserror::Error;
+
+extern "C" {
+    /* `nvd_image_from_filename` hands out a buffer from the C allocator. */
+    fn free(ptr: *mut c_void);
+}
+
+/// Errors that can occur while creating an [`Image`].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    /// The pixel data doesn't match the dimensions and pixel format it was given with, or the
+    /// dimensions are too large for NvDialog to handle.
+    #[error("Image data doesn't match its dimensions and pixel format")]
+    InvalidFormat,
+    /// NvDialog failed to create the image.
+    #[error(transparent)]
+    Native(#[from] Error),
+    /// Loading images this way isn't supported on the current platform.
+    #[error("Not supported on this platform")]
+    Unsupported,
+    /// No icon with the requested name exists in the current icon theme.
+    #[error("Icon not found in the current theme")]
+    IconNotFound,
+}
+
+/// The layout of the pixels passed to [`Image::from_data`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 4 bytes per pixel: red, green, blue and alpha. This is what NvDialog uses internally.
+    Rgba,
+    /// 4 bytes per pixel: blue, green, red and alpha, as commonly used on Windows.
+    Bgra,
+    /// 3 bytes per pixel: red, green and blue, without transparency.
+    Rgb,
+}
+
+impl PixelFormat {
+    /// Returns the number of bytes a single pixel takes in this format.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Rgba | Self::Bgra => 4,
+            Self::Rgb => 3,
+        }
+    }
+}
+
+/// A rectangle inside an image, in pixels, used by [`Image::cropped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    /// The horizontal offset of the rectangle from the left edge.
+    pub x: u32,
+    /// The vertical offset of the rectangle from the top edge.
+    pub y: u32,
+    /// The width of the rectangle.
+    pub width: u32,
+    /// The height of the rectangle.
+    pub height: u32,
+}
+
+/// An image that can be used as an icon by dialogs that support one.
+///
+/// The pixels are kept by the `Image` in RGBA format for as long as it lives, since NvDialog
+/// doesn't copy them.
+///
+/// # Examples
+/// A 2x2 opaque red square:
+/// ```
+/// use nvdialog_rs::{Image, PixelFormat};
+///
+/// let pixels = [255, 0, 0].repeat(4);
+/// let image = Image::from_data(&pixels, 2, 2, PixelFormat::Rgb).unwrap();
+/// assert_eq!(image.size(), (2, 2));
+/// ```
+/// # FFI
+/// Corresponds to `NvdImage`.
+pub struct Image {
+    raw: *mut NvdImage,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl Image {
+    /// Creates an image from raw pixel data.
+    ///
+    /// `data` must contain exactly `width * height` pixels laid out row by row in the given
+    /// `format`. The data is converted to RGBA and copied, so it doesn't have to outlive the image.
+    ///
+    /// # Errors
+    /// Returns [`ImageError::InvalidFormat`] if the length of `data` doesn't match the dimensions
+    /// and format, if either dimension is zero, or if the image is too large. Returns
+    /// [`ImageError::Native`] if NvDialog fails to create the image.
+    pub fn from_data(
+        data: &[u8],
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+    ) -> Result<Self, ImageError> {
+        if width == 0 || height == 0 || width > c_int::MAX as u32 || height > c_int::MAX as u32 {
+            return Err(ImageError::InvalidFormat);
+        }
+        let expected = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(format.bytes_per_pixel()))
+            .ok_or(ImageError::InvalidFormat)?;
+        if data.len() != expected {
+            return Err(ImageError::InvalidFormat);
+        }
+
+        let data = match format {
+            PixelFormat::Rgba => data.to_vec(),
+            PixelFormat::Bgra => data
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+                .collect(),
+            PixelFormat::Rgb => data
+                .chunks_exact(3)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xff])
+                .collect(),
+        };
+        Self::from_rgba(data, width, height)
+    }
+
+    /// Loads an image from a file.
+    ///
+    /// The file is decoded by NvDialog, which supports the common formats (PNG, JPEG, BMP, ...).
+    /// The decoded pixels are copied into the returned `Image` and NvDialog's buffer is freed
+    /// right away, so loading icons repeatedly doesn't leak memory.
+    ///
+    /// # Errors
+    /// - [`ImageError::Native`] with [`Error::InaccessibleFile`] if the file doesn't exist or
+    ///   can't be accessed, or with [`Error::ParametersError`] if the path isn't valid UTF-8.
+    /// - [`ImageError::InvalidFormat`] if the file couldn't be decoded.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_image_from_filename`, followed by `nvd_create_image`.
+    pub fn from_filename<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(Error::InaccessibleFile
+                .context(format!("Can't read image {}", path.display()))
+                .into());
+        }
+        let filename = path.to_str().ok_or_else(|| {
+            Error::ParametersError.context("Image path is not valid UTF-8")
+        })?;
+
+        let (mut width, mut height): (c_int, c_int) = (0, 0);
+        let buffer = with_c_strings([filename], |[filename]| unsafe {
+            nvd_image_from_filename(filename, &mut width, &mut height)
+        })?;
+        if buffer.is_null() {
+            return Err(ImageError::InvalidFormat);
+        }
+
+        /* The buffer is owned by us, copy it and give it back before anything can fail. */
+        let data = if width > 0 && height > 0 {
+            let len = width as usize * height as usize * 4;
+            Some(unsafe { std::slice::from_raw_parts(buffer, len) }.to_vec())
+        } else {
+            None
+        };
+        unsafe { free(buffer as *mut c_void) };
+
+        match data {
+            Some(data) => Self::from_rgba(data, width as u32, height as u32),
+            None => Err(ImageError::InvalidFormat),
+        }
+    }
+
+    /// Loads an SVG file and rasterizes it to fit in a `size` by `size` square, keeping its
+    /// aspect ratio. `size` is a logical size, see [`Image::from_svg_data`].
+    ///
+    /// Requires the `svg` feature. See [`Image::from_svg_data`] for details.
+    ///
+    /// # Errors
+    /// Returns [`ImageError::Native`] with [`Error::InaccessibleFile`] if the file can't be read,
+    /// and the errors of [`Image::from_svg_data`] otherwise.
+    #[cfg(feature = "svg")]
+    pub fn from_svg<P: AsRef<Path>>(path: P, size: u32) -> Result<Self, ImageError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|e| {
+            Error::InaccessibleFile.context(format!("Can't read image {}: {e}", path.display()))
+        })?;
+        Self::from_svg_data(&data, size)
+    }
+
+    /// Rasterizes an SVG document to fit in a `size` by `size` square, keeping its aspect ratio.
+    ///
+    /// `size` is a logical size: it is multiplied by [`crate::scale_factor`], so the resulting
+    /// image has `2 * size` pixels per side on a display scaled to 200%.
+    ///
+    /// Requires the `svg` feature, which renders the image in pure Rust with
+    /// [`resvg`](https://crates.io/crates/resvg). Text inside the SVG is not rendered, as no
+    /// fonts are loaded.
+    ///
+    /// # Errors
+    /// Returns [`ImageError::InvalidFormat`] if `data` isn't a valid SVG document or `size` is 0.
+    #[cfg(feature = "svg")]
+    pub fn from_svg_data(data: &[u8], size: u32) -> Result<Self, ImageError> {
+        use resvg::{tiny_skia, usvg};
+
+        if size == 0 {
+            return Err(ImageError::InvalidFormat);
+        }
+        let tree = usvg::Tree::from_data(data, &usvg::Options::default())
+            .map_err(|_| ImageError::InvalidFormat)?;
+        let natural = tree.size();
+        let scale = crate::scale::to_physical(size) as f32 / natural.width().max(natural.height());
+        let width = ((natural.width() * scale).round() as u32).max(1);
+        let height = ((natural.height() * scale).round() as u32).max(1);
+
+        let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or(ImageError::InvalidFormat)?;
+        resvg::render(
+            &tree,
+            tiny_skia::Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+
+        /* tiny-skia works with premultiplied alpha, NvDialog doesn't. */
+        let data = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        Self::from_rgba(data, width, height)
+    }
+
+    /// Loads the icon called `name` from the current icon theme, as close as possible to `size`.
+    /// Like the other image sizes, `size` is logical and multiplied by [`crate::scale_factor`].
+    ///
+    /// Icons are looked up following the freedesktop icon theme specification through GTK, so
+    /// standard names like `folder-download` or `dialog-information` work without bundling any
+    /// assets. NvDialog must be initialized first, as GTK is loaded by [`crate::init`]. Without the
+    /// `svg` feature, only bitmap versions of the icon are considered.
+    ///
+    /// # Errors
+    /// - [`ImageError::Unsupported`] on platforms other than Linux, which have no icon themes.
+    /// - [`ImageError::Native`] with [`Error::NotYetInitialized`] if NvDialog isn't initialized.
+    /// - [`ImageError::IconNotFound`] if the theme has no icon called `name`.
+    /// - The errors of [`Image::from_filename`] if the icon file can't be loaded.
+    #[cfg(target_os = "linux")]
+    pub fn from_icon_name<S: AsRef<str>>(name: S, size: u32) -> Result<Self, ImageError> {
+        use std::ffi::{c_char, CStr};
+
+        #[repr(C)]
+        struct GtkIconTheme {
+            _private: [u8; 0],
+        }
+        #[repr(C)]
+        struct GtkIconInfo {
+            _private: [u8; 0],
+        }
+        const GTK_ICON_LOOKUP_NO_SVG: c_int = 1 << 0;
+
+        crate::gtk_link::gtk_extern! {
+            fn gtk_icon_theme_get_default() -> *mut GtkIconTheme;
+            fn gtk_icon_theme_lookup_icon(
+                theme: *mut GtkIconTheme,
+                name: *const c_char,
+                size: c_int,
+                flags: c_int,
+            ) -> *mut GtkIconInfo;
+            fn gtk_icon_info_get_filename(info: *mut GtkIconInfo) -> *const c_char;
+            fn g_object_unref(object: *mut c_void);
+        }
+
+        crate::init::ensure_initialized()?;
+        let flags = if cfg!(feature = "svg") { 0 } else { GTK_ICON_LOOKUP_NO_SVG };
+        let physical = crate::scale::to_physical(size).min(c_int::MAX as u32) as c_int;
+
+        let filename = with_c_strings([name.as_ref()], |[name]| unsafe {
+            let theme = gtk_icon_theme_get_default();
+            if theme.is_null() {
+                return None;
+            }
+            let info = gtk_icon_theme_lookup_icon(theme, name, physical, flags);
+            if info.is_null() {
+                return None;
+            }
+            let filename = gtk_icon_info_get_filename(info);
+            let filename = (!filename.is_null())
+                .then(|| CStr::from_ptr(filename).to_string_lossy().into_owned());
+            g_object_unref(info as *mut c_void);
+            filename
+        })?
+        .ok_or(ImageError::IconNotFound)?;
+
+        #[cfg(feature = "svg")]
+        if filename.ends_with(".svg") {
+            return Self::from_svg(filename, size);
+        }
+        Self::from_filename(filename)
+    }
+
+    /// Icon themes only exist on Linux, so this always returns [`ImageError::Unsupported`].
+    #[cfg(not(target_os = "linux"))]
+    pub fn from_icon_name<S: AsRef<str>>(_name: S, _size: u32) -> Result<Self, ImageError> {
+        Err(ImageError::Unsupported)
+    }
+
+    /// Creates the native image from RGBA data whose length was already checked.
+    pub(crate) fn from_rgba(data: Vec<u8>, width: u32, height: u32) -> Result<Self, ImageError> {
+        if !crate::init::NATIVE_CODE {
+            /* Only the pixels are kept, like dialogs that are never created. */
+            return Ok(Self {
+                raw: std::ptr::null_mut(),
+                data,
+                width,
+                height,
+            });
+        }
+        let raw = crate::ffi_check::track(unsafe {
+            nvd_create_image(data.as_ptr(), width as c_int, height as c_int)
+        });
+        if raw.is_null() {
+            return Err(Error::last_native().context("Failed to create image").into());
+        }
+        Ok(Self {
+            raw,
+            data,
+            width,
+            height,
+        })
+    }
+
+    /// Returns a copy of the image resized to `width` by `height`.
+    ///
+    /// The size is logical, like the other image sizes, and multiplied by [`crate::scale_factor`].
+    /// Resampling uses a Lanczos filter, which keeps downscaled icons sharp. Requires the `image`
+    /// feature.
+    ///
+    /// # Errors
+    /// Returns [`ImageError::InvalidFormat`] if either dimension is zero, and
+    /// [`ImageError::Native`] if NvDialog fails to create the new image.
+    #[cfg(feature = "image")]
+    pub fn resized(&self, width: u32, height: u32) -> Result<Self, ImageError> {
+        let (width, height) = (
+            crate::scale::to_physical(width),
+            crate::scale::to_physical(height),
+        );
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidFormat);
+        }
+        let resized = ::image::imageops::resize(
+            &self.as_rgba_image()?,
+            width,
+            height,
+            ::image::imageops::FilterType::Lanczos3,
+        );
+        Self::from_rgba(resized.into_raw(), width, height)
+    }
+
+    /// Returns a copy of the part of the image inside `rect`. Requires the `image` feature.
+    ///
+    /// # Errors
+    /// Returns [`ImageError::InvalidFormat`] if `rect` is empty or doesn't fit inside the image,
+    /// and [`ImageError::Native`] if NvDialog fails to create the new image.
+    #[cfg(feature = "image")]
+    pub fn cropped(&self, rect: Rect) -> Result<Self, ImageError> {
+        let fits = |offset: u32, len: u32, max: u32| {
+            len > 0 && offset.checked_add(len).is_some_and(|end| end <= max)
+        };
+        if !fits(rect.x, rect.width, self.width) || !fits(rect.y, rect.height, self.height) {
+            return Err(ImageError::InvalidFormat);
+        }
+        let cropped = ::image::imageops::crop_imm(
+            &self.as_rgba_image()?,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+        )
+        .to_image();
+        Self::from_rgba(cropped.into_raw(), rect.width, rect.height)
+    }
+
+    #[cfg(feature = "image")]
+    fn as_rgba_image(&self) -> Result<::image::RgbaImage, ImageError> {
+        ::image::RgbaImage::from_raw(self.width, self.height, self.data.clone())
+            .ok_or(ImageError::InvalidFormat)
+    }
+
+    /// Creates a copy of the image with its own native image, re-created from the stored pixels.
+    ///
+    /// This is the fallible version of [`Clone::clone`]: a single loaded icon can be attached to
+    /// several dialogs without loading it from disk again.
+    ///
+    /// # Errors
+    /// Returns [`ImageError::Native`] if NvDialog fails to create the copy.
+    pub fn try_clone(&self) -> Result<Self, ImageError> {
+        Self::from_rgba(self.data.clone(), self.width, self.height)
+    }
+
+    /// Returns the width and height of the image, in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the pixels of the image, in RGBA format.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the raw pointer to the image created from NvDialog directly.
+    ///
+    /// The pointer is only valid for as long as this `Image` is alive.
+    pub(crate) fn get_raw(&self) -> *mut NvdImage {
+        crate::ffi_check::check(self.raw);
+        self.raw
+    }
+}
+
+impl Clone for Image {
+    /// # Panics
+    /// Panics if NvDialog fails to create the copy. Use [`Image::try_clone`] to handle that case.
+    fn clone(&self) -> Self {
+        self.try_clone().expect("Failed to clone image")
+    }
+}
+
+impl Drop for Image {
+    fn drop(&mut self) {
+        if !self.raw.is_null() {
+            crate::ffi_check::release(self.raw);
+            unsafe { nvd_destroy_image(self.raw) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_gets_an_opaque_alpha_channel() {
+        let image = Image::from_data(&[1, 2, 3, 4, 5, 6], 2, 1, PixelFormat::Rgb).unwrap();
+        assert_eq!(image.data(), [1, 2, 3, 0xff, 4, 5, 6, 0xff]);
+        assert_eq!(image.size(), (2, 1));
+    }
+
+    #[test]
+    fn bgra_swaps_red_and_blue() {
+        let image = Image::from_data(&[1, 2, 3, 4, 5, 6, 7, 8], 1, 2, PixelFormat::Bgra).unwrap();
+        assert_eq!(image.data(), [3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+
+    #[test]
+    fn rgba_is_copied_unchanged() {
+        let pixels = [9, 8, 7, 6];
+        let image = Image::from_data(&pixels, 1, 1, PixelFormat::Rgba).unwrap();
+        assert_eq!(image.data(), pixels);
+        assert_eq!(image.try_clone().unwrap().data(), pixels);
+    }
+
+    #[test]
+    fn mismatched_data_is_rejected() {
+        let invalid = |data: &[u8], width, height, format| {
+            Image::from_data(data, width, height, format).err() == Some(ImageError::InvalidFormat)
+        };
+        assert!(invalid(&[0; 3], 1, 1, PixelFormat::Rgba));
+        assert!(invalid(&[0; 4], 1, 1, PixelFormat::Rgb));
+        assert!(invalid(&[], 0, 1, PixelFormat::Rgba));
+        let max = c_int::MAX as u32;
+        assert!(invalid(&[0; 4], 1, max + 1, PixelFormat::Rgba));
+        assert!(invalid(&[0; 4], max, max, PixelFormat::Rgba));
+    }
+}
@@ -0,0 +1,465 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{
+    backend::{clear_current_backend, set_current_backend},
+    Backend, Error,
+};
+use nvdialog_sys::ffi::nvd_init;
+use std::cell::Cell;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Uninitialized,
+    Initialized,
+}
+
+thread_local! {
+    static STATE: Cell<State> = const { Cell::new(State::Uninitialized) };
+    static COM: Cell<Option<ComApartment>> = const { Cell::new(None) };
+    /* NvDialog can't be torn down, so `nvd_init` is only called once, even after `shutdown`. */
+    static NATIVE_INITIALIZED: Cell<bool> = const { Cell::new(false) };
+    /* Whether `init_com` initialized COM and `shutdown` must balance it with `CoUninitialize`. */
+    static COM_OWED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The thread that initialized NvDialog, used by the `strict-threading` feature.
+#[cfg(feature = "strict-threading")]
+static OWNER_THREAD: std::sync::Mutex<Option<std::thread::ThreadId>> = std::sync::Mutex::new(None);
+
+fn set_state(state: State) {
+    STATE.with(|current| current.set(state));
+
+    #[cfg(feature = "strict-threading")]
+    {
+        let mut owner = OWNER_THREAD.lock().unwrap_or_else(|e| e.into_inner());
+        let current = std::thread::current().id();
+        match state {
+            State::Initialized => {
+                debug_assert!(
+                    owner.is_none() || *owner == Some(current),
+                    "nvdialog-rs: `init` was called on thread {:?}, but NvDialog is already \
+                     initialized on thread {:?}. NvDialog must only be used from a single thread.",
+                    current,
+                    owner.unwrap(),
+                );
+                *owner = Some(current);
+            }
+            _ => {
+                if *owner == Some(current) {
+                    *owner = None;
+                }
+            }
+        }
+    }
+}
+
+/// Panics (in debug builds) if the current thread isn't the one that initialized NvDialog.
+///
+/// Only does something with the `strict-threading` feature; every dialog call goes through this.
+#[inline]
+pub(crate) fn check_thread() {
+    #[cfg(feature = "strict-threading")]
+    {
+        let owner = *OWNER_THREAD.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(owner) = owner {
+            let current = std::thread::current().id();
+            debug_assert!(
+                owner == current,
+                "nvdialog-rs: NvDialog was initialized on thread {:?} but is being used from \
+                 thread {:?}. Dialogs must be created and shown on the thread that called `init`.",
+                owner,
+                current,
+            );
+        }
+    }
+}
+
+/// Options controlling how [`init_with`] initializes NvDialog.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::InitOptions;
+///
+/// // Calling this more than once is fine, only the first call initializes NvDialog.
+/// nvdialog_rs::init_with(InitOptions::new().idempotent(true)).expect("Failed to initialize NvDialog");
+/// ```
+#[derive(Debug, Clone)]
+pub struct InitOptions {
+    idempotent: bool,
+    com: ComApartment,
+    backends: Vec<Backend>,
+    #[cfg(feature = "auto-reply")]
+    auto_reply: bool,
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        Self {
+            idempotent: false,
+            com: ComApartment::default(),
+            backends: vec![Backend::Native, Backend::ExternalTools],
+            #[cfg(feature = "auto-reply")]
+            auto_reply: false,
+        }
+    }
+}
+
+/// How [`init_with`] sets up COM on the calling thread. Only has an effect on Windows.
+///
+/// The Win32 file dialogs need COM to be initialized as a single-threaded apartment (STA). If the
+/// application already initialized COM as a multithreaded apartment, they fail with
+/// [`Error::BackendFailed`] when shown, which is hard to trace back to its cause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComApartment {
+    /// Initialize COM as a single-threaded apartment before NvDialog. This is the default.
+    ///
+    /// If COM was already initialized on the thread as a single-threaded apartment, that is
+    /// fine. If it was initialized as a multithreaded apartment, [`init_with`] returns
+    /// [`Error::ComApartmentConflict`] instead of leaving the file dialogs broken.
+    #[default]
+    SingleThreaded,
+    /// Leave COM alone. The application is responsible for initializing it as a single-threaded
+    /// apartment before showing file dialogs.
+    Manual,
+}
+
+impl InitOptions {
+    /// Creates the default options, which behave exactly like [`init`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// If set to `true`, initializing an already initialized thread returns `Ok(())` instead of
+    /// [`Error::AlreadyInitialized`].
+    pub fn idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// Sets how COM is initialized on Windows. Defaults to [`ComApartment::SingleThreaded`].
+    pub fn com_apartment(mut self, com: ComApartment) -> Self {
+        self.com = com;
+        self
+    }
+
+    /// Sets the backends to try, in order, until one of them initializes. Defaults to
+    /// `[Backend::Native, Backend::ExternalTools]`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::{Backend, InitOptions};
+    ///
+    /// nvdialog_rs::init_with(InitOptions::new().backends([Backend::Native, Backend::Tty]))
+    ///     .expect("No backend is available");
+    /// println!("Using {:?}", nvdialog_rs::current_backend());
+    /// ```
+    pub fn backends<I: IntoIterator<Item = Backend>>(mut self, backends: I) -> Self {
+        self.backends = backends.into_iter().collect();
+        self
+    }
+
+    /// If set to `true`, dialogs are answered from the [`AUTO_REPLY_ENV`](crate::AUTO_REPLY_ENV)
+    /// environment variable when it is set, and no backend is initialized. Defaults to `false`,
+    /// so that the variable has no effect on applications that didn't opt in.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::InitOptions;
+    ///
+    /// let testing = std::env::args().any(|arg| arg == "--test-mode");
+    /// nvdialog_rs::init_with(InitOptions::new().auto_reply(testing))
+    ///     .expect("Failed to initialize NvDialog");
+    /// ```
+    #[cfg(feature = "auto-reply")]
+    pub fn auto_reply(mut self, enabled: bool) -> Self {
+        self.auto_reply = enabled;
+        self
+    }
+}
+
+/// Initialize NvDialog in the current thread.
+///
+/// This function initializes NvDialog and its associated backends, and should be called at the
+/// top of your program. Note that this function is required to be called in order to show dialogs.
+/// Not calling this function before using most of NvDialog's available API is **undefined behavior**.
+///
+/// # Returns
+/// If the initialization is successful (i.e., `nvd_init` returns 0), then this function returns
+/// `Ok(())`. Otherwise, an [`Error`] is returned built from the error that NvDialog returned.
+///
+/// # Examples
+/// Basic usage:
+///
+/// ```
+/// fn main() {
+///     nvdialog_rs::init().expect("Failed to initialize NvDialog");
+///     // the rest of your application...
+/// }
+/// ```
+///
+/// Initializing from a second thread:
+///
+/// ```
+/// use std::thread;
+/// fn main() {
+///     println!("Main thread!");
+///     thread::spawn(move ||{
+///         nvdialog_rs::init().expect("Init error");
+///         // Use `nvdialog_rs` only within this thread now!
+///     })
+/// }
+/// ```
+/// The `init` function is intended to be called once at the beginning of your program. Calling it
+/// again after it has already been called succesfully is going to return [`Error::AlreadyInitialized`].
+/// Whether NvDialog was initialized is tracked per thread on the Rust side, so this error is
+/// returned without calling into NvDialog again. Use [`init_with`] and
+/// [`InitOptions::idempotent`] if repeated calls should succeed instead.
+///
+/// Before initializing NvDialog, the version of the library is checked against the headers the
+/// crate was built with, and [`Error::VersionMismatch`] is returned if they aren't compatible.
+///
+/// # Multithreading
+/// For projects that wish to use multiple threads with NvDialog, you must make **ALL** calls in the second
+/// thread. That is, do not call this function on your main thread and other functions in the secondary thread,
+/// as that produces undefined behavior on some platforms. The CI on the [**NvDialog Repo**](https://github.com/tseli0s/nvdialog)
+/// runs a multithreading test on most desktop platforms with that exact undefined behavior to monitor the runtime
+/// behavior.
+///
+/// # FFI
+/// Corresponds to `nvd_init`.
+pub fn init() -> Result<(), Error> {
+    init_with(InitOptions::new())
+}
+
+/// Initialize NvDialog in the current thread, using the given [`InitOptions`].
+///
+/// See [`init`] for details, which is equivalent to calling this function with the default options.
+///
+/// # Errors
+/// If none of the [`InitOptions::backends`] could be initialized, the error of the first one that
+/// failed is returned, or [`Error::InvalidBackend`] if the list is empty.
+pub fn init_with(options: InitOptions) -> Result<(), Error> {
+    if is_initialized() {
+        return if options.idempotent {
+            Ok(())
+        } else {
+            Err(Error::AlreadyInitialized)
+        };
+    }
+
+    #[cfg(feature = "auto-reply")]
+    if options.auto_reply && crate::auto_reply::enable() {
+        set_state(State::Initialized);
+        return Ok(());
+    }
+
+    let mut first_error = None;
+    for backend in &options.backends {
+        let result = match backend {
+            Backend::Native if !NATIVE_CODE => Ok(()),
+            Backend::Native => init_native(options.com),
+            fallback if fallback.init_fallback() => Ok(()),
+            _ => Err(Error::InvalidBackend),
+        };
+        match result {
+            Ok(()) => {
+                set_current_backend(*backend);
+                set_state(State::Initialized);
+                return Ok(());
+            }
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    Err(first_error.unwrap_or(Error::InvalidBackend))
+}
+
+fn init_native(com: ComApartment) -> Result<(), Error> {
+    /* Checked once up front, so that notifications know how to be delivered. */
+    #[cfg(target_os = "macos")]
+    crate::bundle::is_app_bundle();
+
+    let first_init = !NATIVE_INITIALIZED.with(|initialized| initialized.get());
+    if first_init {
+        crate::version::check()?;
+    }
+    let com_initialized = init_com(com)?;
+    let result = if first_init { unsafe { nvd_init() } } else { 0 };
+
+    if result == 0 {
+        NATIVE_INITIALIZED.with(|initialized| initialized.set(true));
+        COM.with(|current| current.set(Some(com)));
+        COM_OWED.with(|owed| owed.set(com_initialized));
+        Ok(())
+    } else {
+        if com_initialized {
+            uninit_com();
+        }
+        Err(Error::from(result).context("Failed to initialize NvDialog"))
+    }
+}
+
+/// Whether NvDialog and the toolkits may be called at all. They never are under Miri, which
+/// can't run foreign code, and with the `noop` feature. Every native object then stays `NULL`,
+/// so the crate's own logic runs without crossing the FFI boundary.
+pub(crate) const NATIVE_CODE: bool = !cfg!(any(miri, feature = "noop"));
+
+/// Returns `true` if dialogs are created through NvDialog in the current thread, that is if it
+/// is initialized and not delegating dialogs to a fallback [`Backend`].
+pub(crate) fn native_available() -> bool {
+    NATIVE_CODE && is_initialized() && crate::backend::fallback().is_none()
+}
+
+/// Returns the [`ComApartment`] policy the current thread was initialized with, or `None` if
+/// NvDialog hasn't been initialized natively in this thread.
+pub fn com_apartment() -> Option<ComApartment> {
+    COM.with(|com| com.get())
+}
+
+/// Initializes COM according to `apartment`, returning whether a `CoUninitialize` is owed.
+#[cfg(target_os = "windows")]
+fn init_com(apartment: ComApartment) -> Result<bool, Error> {
+    use std::ffi::c_void;
+
+    const COINIT_APARTMENTTHREADED: u32 = 0x2;
+    const COINIT_DISABLE_OLE1DDE: u32 = 0x4;
+    const RPC_E_CHANGED_MODE: i32 = 0x8001_0106_u32 as i32;
+
+    #[link(name = "ole32")]
+    extern "system" {
+        fn CoInitializeEx(reserved: *mut c_void, coinit: u32) -> i32;
+    }
+
+    if apartment == ComApartment::Manual {
+        return Ok(false);
+    }
+    match unsafe {
+        CoInitializeEx(
+            std::ptr::null_mut(),
+            COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE,
+        )
+    } {
+        RPC_E_CHANGED_MODE => Err(Error::ComApartmentConflict),
+        /* Both S_OK and S_FALSE (already initialized as an STA) must be balanced. */
+        result if result >= 0 => Ok(true),
+        result => Err(Error::backend(result, Some("CoInitializeEx failed"))
+            .context("Failed to initialize COM")),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn init_com(_apartment: ComApartment) -> Result<bool, Error> {
+    Ok(false)
+}
+
+fn uninit_com() {
+    #[cfg(target_os = "windows")]
+    {
+        #[link(name = "ole32")]
+        extern "system" {
+            fn CoUninitialize();
+        }
+        unsafe { CoUninitialize() };
+    }
+}
+
+/// Returns `true` if NvDialog has been successfully initialized in the current thread.
+pub fn is_initialized() -> bool {
+    STATE.with(|state| state.get()) == State::Initialized
+}
+
+/// Shuts down NvDialog in the current thread.
+///
+/// After this call, the thread behaves as if [`init`] was never called: new dialogs return
+/// [`Error::NotYetInitialized`] (or do nothing, for constructors that can't fail),
+/// [`current_backend`](crate::current_backend) returns `None`, and [`init`] or [`init_with`] can
+/// be called again to resume using the library, picking a backend among the ones requested then.
+/// Buffers the crate keeps around for the thread are freed as well. Calling this on a thread that
+/// isn't initialized does nothing.
+///
+/// Dialogs created before the call must be dropped before it, as they are still owned by the
+/// backend that created them.
+///
+/// On Windows, the COM initialization done by [`init`] (see [`ComApartment`]) is balanced with
+/// `CoUninitialize` here, and done again by the next [`init`].
+///
+/// # Notes
+/// NvDialog itself doesn't offer a teardown function, so the backend stays loaded: the GTK
+/// display connection is only released when the process exits. What this function guarantees
+/// is that the crate is back in a clean, re-initializable state, which is what long running
+/// daemons and test suites need.
+pub fn shutdown() {
+    if is_initialized() {
+        set_state(State::Uninitialized);
+        clear_current_backend();
+        COM.with(|current| current.set(None));
+        #[cfg(feature = "auto-reply")]
+        crate::auto_reply::disable();
+        if COM_OWED.with(|owed| owed.replace(false)) {
+            uninit_com();
+        }
+        crate::util::release_scratch_buffer();
+    }
+}
+
+/// Makes `backend` the backend of the current thread, whether it was initialized before or not.
+#[cfg(feature = "mock")]
+pub(crate) fn force_backend(backend: Backend) {
+    set_current_backend(backend);
+    set_state(State::Initialized);
+}
+
+/// Returns [`Error::NotYetInitialized`] if NvDialog hasn't been initialized in the current thread.
+pub(crate) fn ensure_initialized() -> Result<(), Error> {
+    if is_initialized() {
+        Ok(())
+    } else {
+        Err(Error::NotYetInitialized)
+    }
+}
+
+#[cfg(all(test, feature = "mock", not(feature = "strict-threading")))]
+mod tests {
+    use super::*;
+    use crate::current_backend;
+
+    #[test]
+    fn reinitializing_picks_the_backend_again() {
+        init_with(InitOptions::new().backends([Backend::Mock])).unwrap();
+        assert_eq!(current_backend(), Some(Backend::Mock));
+        shutdown();
+        assert!(!is_initialized());
+        assert_eq!(current_backend(), None);
+
+        let none = init_with(InitOptions::new().backends([]));
+        assert_eq!(none, Err(Error::InvalidBackend));
+        assert!(!is_initialized());
+
+        init_with(InitOptions::new().backends([Backend::Mock])).unwrap();
+        assert_eq!(current_backend(), Some(Backend::Mock));
+        shutdown();
+    }
+}
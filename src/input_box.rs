@@ -0,0 +1,330 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{
+    util::{free_raw, with_c_strings},
+    DialogBox, DialogType, DynamicString, Error, Object,
+};
+use nvdialog_sys::ffi::*;
+
+/// How an [`InputBox`] was closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputResult {
+    /// The user accepted the input box with this text, which may be empty.
+    Submitted(String),
+    /// The user cancelled or closed the input box.
+    Cancelled,
+}
+
+impl InputResult {
+    /// Returns the submitted text, or `None` if the input box was cancelled.
+    pub fn submitted(self) -> Option<String> {
+        match self {
+            Self::Submitted(text) => Some(text),
+            Self::Cancelled => None,
+        }
+    }
+}
+
+/// The characters an [`InputBox`] accepts, set with [`InputBox::allowed_chars`].
+#[derive(Debug, Clone, Copy)]
+pub enum Charset {
+    /// The ASCII digits `0` to `9`.
+    Digits,
+    /// Letters and digits, in any script.
+    Alphanumeric,
+    /// Printable ASCII characters, including spaces.
+    Ascii,
+    /// The characters for which the function returns `true`.
+    Custom(fn(char) -> bool),
+}
+
+impl Charset {
+    /// Returns `true` if `c` belongs to this character set.
+    pub fn contains(self, c: char) -> bool {
+        match self {
+            Self::Digits => c.is_ascii_digit(),
+            Self::Alphanumeric => c.is_alphanumeric(),
+            Self::Ascii => c.is_ascii_graphic() || c == ' ',
+            Self::Custom(f) => f(c),
+        }
+    }
+}
+
+/// A dialog asking the user to type in some text.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::InputBox;
+///
+/// let mut input_box = InputBox::new("Rename", "Enter the new name of the file:")
+///     .expect("Failed to create input box");
+/// input_box.display();
+/// if let Some(name) = input_box.get_input_ref() {
+///     println!("New name: {name}");
+/// }
+/// ```
+///
+/// # Constraints
+/// [`InputBox::max_length`] and [`InputBox::allowed_chars`] restrict what can be submitted.
+/// NvDialog has no way to enforce them in the input field itself, so they are checked when the
+/// input box is accepted instead: if the text breaks one of them, a warning explaining why is
+/// shown and the input box is displayed again, until the user enters valid text or cancels.
+///
+/// ## FFI
+/// Matches with `NvdInputBox`.
+pub struct InputBox {
+    raw: *mut NvdInputBox,
+    title: String,
+    prompt: String,
+    input: Option<DynamicString>,
+    max_length: Option<usize>,
+    allowed_chars: Option<Charset>,
+}
+
+impl InputBox {
+    /// Creates a new `InputBox` with the given `title` and `prompt`, the text shown above the
+    /// input field.
+    ///
+    /// # Errors
+    /// Returns [`Error::NotYetInitialized`] if [`crate::init`] hasn't been called in this thread,
+    /// [`Error::Unsupported`] if a fallback backend is active or if the NvDialog the crate was
+    /// built against predates input boxes, [`Error::ParametersError`] if
+    /// `title` or `prompt` contain a null byte, or the error NvDialog reports if creating the
+    /// input box failed.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_input_box_new`.
+    pub fn new<S: AsRef<str>>(title: S, prompt: S) -> Result<Self, Error> {
+        crate::init::check_thread();
+        crate::init::ensure_initialized()?;
+        if crate::backend::fallback().is_some() {
+            return Err(Error::Unsupported
+                .context("Input boxes are only supported by the native backend"));
+        }
+        if !nvdialog_sys::available::INPUT_BOX {
+            return Err(Error::Unsupported
+                .context("The version of NvDialog in use has no input boxes"));
+        }
+        if !crate::init::native_available() {
+            return Ok(Self::detached(title.as_ref(), prompt.as_ref()));
+        }
+        let raw = with_c_strings([title.as_ref(), prompt.as_ref()], |[t, p]| unsafe {
+            nvd_input_box_new(t, p)
+        })?;
+        if raw.is_null() {
+            return Err(Error::last_native().context("Failed to create input box"));
+        }
+        let mut input_box = Self::detached(title.as_ref(), prompt.as_ref());
+        input_box.raw = crate::ffi_check::track(raw);
+        Ok(input_box)
+    }
+
+    /// An input box without a native counterpart, which is never shown.
+    fn detached(title: &str, prompt: &str) -> Self {
+        Self {
+            raw: std::ptr::null_mut(),
+            title: String::from(title),
+            prompt: String::from(prompt),
+            input: None,
+            max_length: None,
+            allowed_chars: None,
+        }
+    }
+
+    /// Only accepts text of at most `max_length` characters.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Only accepts text made of characters from `charset`.
+    pub fn allowed_chars(mut self, charset: Charset) -> Self {
+        self.allowed_chars = Some(charset);
+        self
+    }
+
+    /// Returns why `text` can't be submitted, if it breaks one of the constraints.
+    fn violation(&self, text: &str) -> Option<String> {
+        if let Some(max_length) = self.max_length {
+            if text.chars().count() > max_length {
+                return Some(format!(
+                    "The text can't be longer than {max_length} characters."
+                ));
+            }
+        }
+        let charset = self.allowed_chars?;
+        text.chars()
+            .find(|c| !charset.contains(*c))
+            .map(|c| format!("The character '{c}' isn't allowed here."))
+    }
+
+    /// Asks the user for some text in a single call: creates an input box, displays it and
+    /// returns what was entered.
+    ///
+    /// Accepting the input box without typing anything gives an empty
+    /// [`InputResult::Submitted`], not [`InputResult::Cancelled`].
+    ///
+    /// # Errors
+    /// Same as [`InputBox::new`].
+    ///
+    /// # Examples
+    /// ```
+    /// use nvdialog_rs::{InputBox, InputResult};
+    ///
+    /// match InputBox::prompt("Rename", "Enter the new name of the file:") {
+    ///     Ok(InputResult::Submitted(name)) => println!("New name: {name}"),
+    ///     Ok(InputResult::Cancelled) => println!("Cancelled"),
+    ///     Err(e) => eprintln!("Failed to ask for a name: {e}"),
+    /// }
+    /// ```
+    pub fn prompt<S: AsRef<str>>(title: S, prompt: S) -> Result<InputResult, Error> {
+        let mut input_box = Self::new(title, prompt)?;
+        input_box.display();
+        Ok(input_box.result())
+    }
+
+    /// Returns the title of the input box.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the prompt shown above the input field.
+    pub fn message(&self) -> &str {
+        &self.prompt
+    }
+
+    /// Shows the input box and waits for the user to close it. The text entered is then
+    /// available through [`InputBox::get_input`]. If constraints were set, this only returns
+    /// once the text satisfies them or the input box is cancelled (see [Constraints](#constraints)).
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_show_input_box`.
+    pub fn display(&mut self) {
+        crate::init::check_thread();
+        if self.raw.is_null() {
+            return;
+        }
+        loop {
+            unsafe { nvd_show_input_box(self.raw) };
+            self.refresh_input();
+            let Some(problem) = self
+                .input
+                .as_ref()
+                .and_then(|input| self.violation(input.as_str()))
+            else {
+                return;
+            };
+            if let Ok(mut warning) =
+                DialogBox::new(self.title.as_str(), &problem, DialogType::Warning)
+            {
+                warning.show();
+            }
+        }
+    }
+
+    /// Returns a copy of the text entered by the user, as read when the input box was last
+    /// displayed or refreshed. Returns `None` if the input box hasn't been displayed yet, or if
+    /// the user cancelled it; accepting it without typing anything gives an empty string.
+    pub fn get_input(&self) -> Option<DynamicString> {
+        self.input.clone()
+    }
+
+    /// Returns whether the user submitted or cancelled the input box, as of the last time it was
+    /// displayed or refreshed. An input box that hasn't been displayed yet counts as cancelled.
+    ///
+    /// NvDialog only sets the input string when the input box is accepted, so this doesn't need
+    /// any status from the backend beyond that.
+    pub fn result(&self) -> InputResult {
+        match &self.input {
+            Some(input) => InputResult::Submitted(String::from(input.as_str())),
+            None => InputResult::Cancelled,
+        }
+    }
+
+    /// Same as [`InputBox::get_input`], without copying the text.
+    pub fn get_input_ref(&self) -> Option<&DynamicString> {
+        self.input.as_ref()
+    }
+
+    /// Reads the entered text from NvDialog again, replacing the copy cached by
+    /// [`InputBox::display`]. Use this when the native string may have been changed since, for
+    /// example through [`InputBox::native_input`].
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_input_box_get_string`.
+    pub fn refresh_input(&mut self) -> Option<&DynamicString> {
+        crate::init::check_thread();
+        if self.raw.is_null() {
+            return None;
+        }
+        let native = unsafe { nvd_input_box_get_string(self.raw) };
+        self.input = if native.is_null() {
+            None
+        } else {
+            /* The string belongs to the input box, so keep a copy that outlives it. */
+            Some(unsafe { DynamicString::from_raw(nvd_duplicate_string(native)) })
+        };
+        self.input.as_ref()
+    }
+
+    /// Returns the string NvDialog stores the entered text in, or a null pointer if there is none.
+    ///
+    /// # Safety
+    /// The string is owned by the input box and invalid once it is dropped. Call
+    /// [`InputBox::refresh_input`] after changing it, so that [`InputBox::get_input`] reflects
+    /// the change.
+    pub unsafe fn native_input(&self) -> *mut NvdDynamicString {
+        if self.raw.is_null() {
+            return std::ptr::null_mut();
+        }
+        crate::ffi_check::check(self.raw);
+        nvd_input_box_get_string(self.raw)
+    }
+}
+
+impl Object for InputBox {
+    type NativeType = NvdInputBox;
+    type ReturnValue = InputResult;
+
+    fn get_raw(&self) -> *mut NvdInputBox {
+        crate::ffi_check::check(self.raw);
+        self.raw
+    }
+
+    fn show(&mut self) -> Result<InputResult, Error> {
+        self.display();
+        Ok(self.result())
+    }
+
+    fn free(&mut self) {
+        unsafe { free_raw(std::mem::replace(&mut self.raw, std::ptr::null_mut())) };
+    }
+}
+
+impl Drop for InputBox {
+    fn drop(&mut self) {
+        unsafe { free_raw(self.raw) };
+    }
+}
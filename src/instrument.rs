@@ -0,0 +1,107 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{Decision, DialogInfo};
+use std::{
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+/// Receives measurements about every dialog the crate shows, to feed metrics systems such as
+/// Prometheus or statsd.
+///
+/// Register an implementation with [`set_instrumentation`]. Every method has an empty default
+/// implementation, so only the measurements you need have to be implemented. Methods are called
+/// from the thread showing the dialog, and should return quickly.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DialogInfo, Instrumentation};
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// #[derive(Default)]
+/// struct DialogCounter(AtomicU64);
+///
+/// impl Instrumentation for DialogCounter {
+///     fn dialog_shown(&self, _info: &DialogInfo) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// nvdialog_rs::set_instrumentation(DialogCounter::default());
+/// ```
+pub trait Instrumentation: Send + Sync {
+    /// Called right before a dialog is shown, or a notification is sent.
+    fn dialog_shown(&self, info: &DialogInfo) {
+        let _ = info;
+    }
+
+    /// Called once a dialog was closed, with the time it was visible for and the user's
+    /// decision, for dialogs that return one. Not called for notifications, as the crate can't
+    /// know when they are dismissed.
+    fn dialog_closed(&self, info: &DialogInfo, visible_for: Duration, decision: Option<&Decision>) {
+        let _ = (info, visible_for, decision);
+    }
+}
+
+static INSTRUMENTATION: RwLock<Option<Arc<dyn Instrumentation>>> = RwLock::new(None);
+
+/// Registers `instrumentation` for all threads, replacing the previous one.
+pub fn set_instrumentation<I: Instrumentation + 'static>(instrumentation: I) {
+    *INSTRUMENTATION.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(instrumentation));
+}
+
+/// Removes the instrumentation registered with [`set_instrumentation`], if any.
+pub fn clear_instrumentation() {
+    *INSTRUMENTATION.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn current() -> Option<Arc<dyn Instrumentation>> {
+    INSTRUMENTATION.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Runs `show`, reporting it to the registered instrumentation along with the decision
+/// `decision` extracts from its result. `info` is only called if instrumentation is registered.
+pub(crate) fn instrument<R>(
+    info: impl FnOnce() -> DialogInfo,
+    show: impl FnOnce() -> R,
+    decision: impl FnOnce(&R) -> Option<Decision>,
+) -> R {
+    let Some(instrumentation) = current() else {
+        return show();
+    };
+    let info = info();
+    instrumentation.dialog_shown(&info);
+    let start = Instant::now();
+    let result = show();
+    instrumentation.dialog_closed(&info, start.elapsed(), decision(&result).as_ref());
+    result
+}
+
+/// Reports a notification being sent to the registered instrumentation.
+pub(crate) fn notification_sent(info: impl FnOnce() -> DialogInfo) {
+    if let Some(instrumentation) = current() {
+        instrumentation.dialog_shown(&info());
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::DialogSpec;
+use std::sync::{Arc, RwLock};
+
+/// What should happen to a dialog after the interceptor registered with
+/// [`set_dialog_interceptor`] has seen it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterceptAction {
+    /// Show the dialog, with any changes the interceptor made to its description.
+    Show,
+    /// Don't show the dialog at all. Question dialogs then return their remembered answer or
+    /// their [`DefaultAnswer`](crate::DefaultAnswer), and file dialogs `None`.
+    Suppress,
+}
+
+type Interceptor = Arc<dyn Fn(&mut DialogSpec) -> InterceptAction + Send + Sync>;
+
+static INTERCEPTOR: RwLock<Option<Interceptor>> = RwLock::new(None);
+
+/// Registers `interceptor` to be called with the description of every dialog box, question
+/// dialog and file dialog when it is created, in any thread, replacing the previous one.
+///
+/// The interceptor can change the title, message and other options of the dialog before the
+/// native dialog is created (for example to append a support code to every error), or suppress
+/// it altogether, after logging it if dialogs must not be shown (for example in kiosk mode).
+/// Changing the variant of the [`DialogSpec`] has no effect.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DialogSpec, InterceptAction};
+///
+/// nvdialog_rs::set_dialog_interceptor(|spec| {
+///     if let DialogSpec::Message(message) = spec {
+///         message.message.push_str("\n\nSupport code: 4F2A");
+///     }
+///     InterceptAction::Show
+/// });
+/// ```
+pub fn set_dialog_interceptor<F>(interceptor: F)
+where
+    F: Fn(&mut DialogSpec) -> InterceptAction + Send + Sync + 'static,
+{
+    *INTERCEPTOR.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(interceptor));
+}
+
+/// Removes the interceptor registered with [`set_dialog_interceptor`], if any.
+pub fn clear_dialog_interceptor() {
+    *INTERCEPTOR.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Runs the registered interceptor and the duplicate detection on `spec`, returning the
+/// description to use and whether the dialog was suppressed.
+pub(crate) fn intercept<S>(spec: S) -> (S, bool)
+where
+    S: Clone + Into<DialogSpec> + TryFrom<DialogSpec>,
+{
+    /* Cloned out of the lock, so that the interceptor itself may register another one. */
+    let interceptor = INTERCEPTOR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    if interceptor.is_none() && !crate::dedupe::enabled() {
+        return (spec, false);
+    }
+    let mut intercepted = spec.clone().into();
+    let suppressed = interceptor
+        .is_some_and(|interceptor| interceptor(&mut intercepted) == InterceptAction::Suppress)
+        || crate::dedupe::is_duplicate(&mut intercepted);
+    (S::try_from(intercepted).unwrap_or(spec), suppressed)
+}
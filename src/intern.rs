@@ -0,0 +1,73 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::Error;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::{c_char, CStr},
+};
+
+thread_local! {
+    static INTERNED: RefCell<HashMap<&'static str, &'static CStr>> = RefCell::new(HashMap::new());
+}
+
+/// Converts `s` into a C string once, and returns the same C string for every later call with
+/// the same text in this thread.
+///
+/// Once a string has been interned, dialogs created in the same thread with that exact title or
+/// message use the interned copy directly instead of converting it again. This is meant for
+/// strings that are shown over and over, such as `"Error"` or the name of your application.
+/// Interned strings are never freed, so only intern a bounded set of strings.
+///
+/// # Errors
+/// Returns [`Error::ParametersError`] if `s` contains a null byte.
+///
+/// # Examples
+/// ```
+/// let title = nvdialog_rs::intern("Error").unwrap();
+/// assert_eq!(title.to_bytes(), b"Error");
+/// ```
+pub fn intern(s: &'static str) -> Result<&'static CStr, Error> {
+    INTERNED.with(|interned| {
+        let mut interned = interned.borrow_mut();
+        if let Some(c_str) = interned.get(s) {
+            return Ok(*c_str);
+        }
+        let c_str: &'static CStr = Box::leak(crate::try_c_string!(s)?.into_boxed_c_str());
+        interned.insert(s, c_str);
+        Ok(c_str)
+    })
+}
+
+/// Returns the interned copy of `s`, if [`intern`] was called with the same text in this thread.
+pub(crate) fn lookup(s: &str) -> Option<*const c_char> {
+    INTERNED.with(|interned| {
+        let interned = interned.borrow();
+        if interned.is_empty() {
+            return None;
+        }
+        interned.get(s).map(|c_str| c_str.as_ptr())
+    })
+}
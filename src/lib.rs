@@ -38,6 +38,20 @@
 //! - macOS does not allow any UI operations outside the main thread.
 //! - Gtk on Linux does not support it directly, but GLib offers ways to safely send data between threads.
 //!
+//! The `macos-dispatch` feature adds [`run_on_main`], which runs a closure on the main thread
+//! through Grand Central Dispatch and hands its result back to the calling thread.
+//!
+//! Applications that already run a GTK main loop can enable the `gtk-integration` feature, whose
+//! [`gtk`] module schedules dialog code on the existing `GMainContext` instead of running it from
+//! inside other event handlers.
+//!
+//! Applications that run their own render loop can queue dialogs with [`DeferredDialog`], to have
+//! them shown between two frames by [`pump_events`] instead of in the middle of one.
+//!
+//! Enabling the `strict-threading` feature turns this rule into a check: the thread calling
+//! [`init`] is recorded, and in debug builds every dialog call made from another thread panics
+//! with a message explaining the problem, instead of causing platform-specific undefined behavior.
+//!
 //!
 //! # Example dialog:
 //! ```rust
@@ -61,89 +75,236 @@
 //! /* Showing the dialog box. */
 //! dialog_box.show();
 //! ```
+//!
+//! # Headless automation
+//! With the `auto-reply` feature enabled, applications that initialize with
+//! [`InitOptions::auto_reply`] let the [`AUTO_REPLY_ENV`] environment variable
+//! (`NVDIALOG_AUTO_REPLY`) make every dialog return a scripted answer immediately, without
+//! NvDialog being initialized or anything being rendered. This is meant for end-to-end tests and
+//! CI runs of applications built on this crate. When the variable is not set, or the application
+//! didn't opt in, the feature has no effect.
+//!
+//! For unit tests, the `mock` feature adds [`MockBackend`], which renders the dialogs shown in
+//! the current thread as text instead of displaying them.
+//!
+//! Server builds of applications that share code with a GUI frontend can enable the `noop`
+//! feature instead: [`init`] then succeeds without initializing any toolkit, and every dialog
+//! returns immediately without being shown. Questions reply with their [`DefaultAnswer`] and file
+//! dialogs return the path set through `set_noop_file`.
+//!
+//! # Recording dialogs
+//! The `recorder` feature adds [`record_to`], which logs every dialog shown (its kind, title,
+//! message and the reply given) to a JSON file, and [`replay_from`], which feeds the replies of
+//! such a file back into a later run. Use it to reproduce a user-reported flow, or to write
+//! regression tests against a real application.
+//!
+//! # Fallback backends
+//! When NvDialog's native backend can't be initialized (for example because GTK is missing at
+//! runtime in a minimal container), [`init`] tries the next [`Backend`] in line. With the
+//! `external-tools` feature, it falls back to showing dialogs through `zenity` or `kdialog` on
+//! Linux and `osascript` on macOS, if one of them is installed. [`InitOptions::backends`] changes
+//! the order, or adds [`Backend::Tty`] to ask on the terminal as a last resort, and
+//! [`current_backend`] tells which one was picked. Message, question and file dialogs work with
+//! every backend; notifications and input boxes return [`Error::Unsupported`] and about dialogs
+//! are not shown with fallback backends ([`AboutDialog::try_show`] reports it).
 
-#![allow(dead_code, improper_ctypes)]
+#![allow(dead_code)]
 
+#[cfg(test)]
+mod alloc_count;
+mod dedupe;
+mod default_answer;
+mod deferred;
 mod dialog_box;
+mod dialog_info;
+mod direction;
+mod drop_target;
+mod dynamic_string;
+#[cfg(feature = "macos-dispatch")]
+mod dispatch;
 mod error;
+#[cfg(feature = "async")]
+mod events;
+#[cfg(feature = "async")]
+mod executor;
+#[cfg(feature = "external-tools")]
+mod external;
+mod ffi_check;
 mod file_dialog;
+mod focus;
+#[cfg(all(feature = "gtk-integration", target_os = "linux"))]
+pub mod gtk;
+#[cfg(feature = "i18n")]
+mod i18n;
+#[cfg(feature = "windows-identity")]
+mod identity;
+mod image;
+mod init;
+mod input_box;
+mod instrument;
+mod intercept;
+mod intern;
+#[cfg(feature = "log")]
+mod logger;
+mod macros;
+#[cfg(feature = "noop")]
+mod noop;
+#[cfg(feature = "mock")]
+pub mod mock;
 mod notification;
+mod notification_log;
+mod object;
 mod question_dialog;
+mod queue;
+mod rate_limit;
+mod recent;
+#[cfg(feature = "recorder")]
+mod recorder;
+mod scale;
+mod schedule;
+mod sound;
+mod tty;
+mod spec;
 mod util;
+mod version;
 mod about_dialog;
+mod audit;
+mod backend;
+mod bundle;
+mod button_layout;
+mod cancel;
+#[cfg(feature = "macros")]
+mod catalog;
+mod choice;
+mod choice_dialog;
+mod confirm;
+#[cfg(feature = "auto-reply")]
+mod auto_reply;
+#[cfg(target_os = "windows")]
+mod win_hook;
+#[cfg(target_os = "linux")]
+mod gtk_link;
 
+pub use dedupe::set_dedupe_window;
+pub use default_answer::{default_answer, set_default_answer, DefaultAnswer};
+pub use deferred::{pump_events, DeferredDialog};
 pub use dialog_box::*;
+pub use dialog_info::*;
+pub use direction::{set_text_direction, TextDirection};
+pub use drop_target::DropTargetDialog;
+pub use dynamic_string::DynamicString;
+#[cfg(feature = "macos-dispatch")]
+pub use dispatch::run_on_main;
 pub use error::*;
+#[cfg(feature = "async")]
+pub use events::{event_stream, EventStream};
+#[cfg(feature = "async")]
+pub use executor::{
+    show_async, show_file_dialog_async, BlockingExecutor, BlockingJob, DialogThread,
+    FileDialogFuture,
+};
 pub use about_dialog::*;
+pub use audit::{clear_decision_logger, set_decision_logger, Decision, DialogEvent};
+pub use backend::{current_backend, Backend};
+pub use bundle::is_app_bundle;
+pub use button_layout::{button_layout, set_button_layout, ButtonLayout};
+pub use cancel::CancelToken;
+#[cfg(feature = "macros")]
+pub use catalog::{CatalogEntry, DialogCatalog};
+pub use choice::{clear_choice_store, forget_choice, set_choice_store, ChoiceStore};
+pub use choice_dialog::ChoiceDialog;
+pub use confirm::{confirm_discard_changes, SaveDiscardCancel};
+#[cfg(feature = "choice-store")]
+pub use choice::JsonChoiceStore;
+#[cfg(feature = "auto-reply")]
+pub use auto_reply::{auto_reply_enabled, AUTO_REPLY_ENV};
 pub use file_dialog::*;
+pub use focus::notifications_suppressed;
+#[cfg(feature = "i18n")]
+pub use i18n::*;
+#[cfg(feature = "windows-identity")]
+pub use identity::{set_app_user_model_id, WINDOWS_MANIFEST};
+pub use image::*;
+pub use init::*;
+pub use input_box::{Charset, InputBox, InputResult};
+pub use intercept::{clear_dialog_interceptor, set_dialog_interceptor, InterceptAction};
+pub use intern::intern;
+#[doc(hidden)]
+pub use macros::__gui_assert_failed;
+pub use instrument::{clear_instrumentation, set_instrumentation, Instrumentation};
+#[cfg(feature = "log")]
+pub use logger::{show_logged, DialogLogger, Surface};
+#[cfg(feature = "mock")]
+pub use mock::MockBackend;
+#[cfg(feature = "noop")]
+#[allow(deprecated)]
+pub use noop::{set_noop_file, set_noop_reply};
 pub use notification::*;
-use nvdialog_sys::ffi::nvd_init;
+pub use notification_log::{DeliveryStatus, NotificationLog, NotificationRecord};
+pub use object::{free_all, show_all, Dialogs, Object};
 pub use question_dialog::*;
+pub use queue::{DialogQueue, ReplySender};
+pub use rate_limit::set_notification_rate_limit;
+#[cfg(feature = "recorder")]
+pub use recorder::{record_to, replay_from, stop_recorder};
+pub use scale::{scale_factor, set_scale_factor};
+pub use schedule::ScheduledNotification;
+pub use sound::*;
+pub use spec::*;
+pub use version::{runtime_version, Version, NATIVE_VERSION};
 
-/// Initialize NvDialog in the current thread.
-///
-/// This function initializes NvDialog and its associated backends, and should be called at the
-/// top of your program. Note that this function is required to be called in order to show dialogs.
-/// Not calling this function before using most of NvDialog's available API is **undefined behavior**.
-///
-/// # Returns
-/// If the initialization is successful (i.e., `nvd_init` returns 0), then this function returns
-/// `Ok(())`. Otherwise, an [`Error`] is returned built from the error that NvDialog returned.
+/// Sets the application name for NvDialog.
 ///
-/// # Examples
-/// Basic usage:
+/// This function sets the application name for NvDialog, often used in notifications
+/// and system configuration (eg. DBus). By default, the name is set to `NvDialog Application`
+/// since empty strings may cause issues.
+/// **NOTICE:** Do not confuse this function with your program's executable name! That used to be
+/// handled by [`crate::init`] but has been deprecated entirely!
 ///
-/// ```
-/// fn main() {
-///     nvdialog_rs::init().expect("Failed to initialize NvDialog");
-///     // the rest of your application...
-/// }
-/// ```
+/// With the `windows-identity` feature, this also derives the process' AppUserModelID from
+/// `name` on Windows, unless `set_app_user_model_id` was called first.
 ///
-/// Initializing from a second thread:
+/// # Errors
+/// Returns [`Error::ParametersError`] if `name` contains a null byte.
+pub fn set_app_name<S: AsRef<str>>(name: S) -> Result<(), Error> {
+    #[cfg(feature = "windows-identity")]
+    identity::sync_with_app_name(name.as_ref())?;
+    if !init::NATIVE_CODE {
+        return util::with_c_strings([name.as_ref()], |_| ());
+    }
+    util::with_c_strings([name.as_ref()], |[name]| unsafe {
+        nvdialog_sys::ffi::nvd_set_application_name(name);
+    })
+}
+
+/// Sets the application ID dialogs are associated with.
 ///
-/// ```
-/// use std::thread;
-/// fn main() {
-///     println!("Main thread!");
-///     thread::spawn(move ||{
-///         nvdialog_rs::init().expect("Init error");
-///         // Use `nvdialog_rs` only within this thread now!
-///     })
-/// }
-/// ```
-/// The `init` function is intended to be called once at the beginning of your program. Calling it
-/// again after it has already been called succesfully is going to return [`Error::AlreadyInitialized`].
+/// `app_id` should match the name of your application's `.desktop` file, without the extension
+/// (eg. `org.example.App` for `org.example.App.desktop`). On Wayland it becomes the `app_id` of the
+/// dialog windows, and on X11 their `WM_CLASS`, so that the desktop shows your application's icon
+/// for them and groups them with its other windows instead of under a generic entry.
 ///
-/// # Multithreading
-/// For projects that wish to use multiple threads with NvDialog, you must make **ALL** calls in the second
-/// thread. That is, do not call this function on your main thread and other functions in the secondary thread,
-/// as that produces undefined behavior on some platforms. The CI on the [**NvDialog Repo**](https://github.com/tseli0s/nvdialog)
-/// runs a multithreading test on most desktop platforms with that exact undefined behavior to monitor the runtime
-/// behavior.
+/// This must be called before [`init`], as GTK reads the ID when it is initialized. It does
+/// nothing on platforms other than Linux.
 ///
-/// # FFI
-/// Corresponds to `nvd_init`.
-pub fn init() -> Result<(), Error> {
-    let result = unsafe { nvd_init() };
-
-    if result == 0 {
-        Ok(())
-    } else {
-        Err(Error::from(result))
+/// # Errors
+/// Returns [`Error::EmptyString`] if `app_id` is empty, and [`Error::ParametersError`] if it
+/// contains a null byte.
+pub fn set_app_id<S: AsRef<str>>(app_id: S) -> Result<(), Error> {
+    if app_id.as_ref().is_empty() {
+        return Err(Error::EmptyString);
     }
-}
 
-/// Sets the application name for NvDialog.
-///
-/// This function sets the application name for NvDialog, often used in notifications
-/// and system configuration (eg. DBus). By default, the name is set to `NvDialog Application`
-/// since empty strings may cause issues.
-/// **NOTICE:** Do not confuse this function with your program's executable name! That used to be
-/// handled by [`crate::init`] but has been deprecated entirely!
-pub fn set_app_name<S: AsRef<str>>(name: S) {
-    let name = c_string!(name.as_ref());
-    unsafe {
-        nvdialog_sys::ffi::nvd_set_application_name(name.as_ptr());
+    #[cfg(target_os = "linux")]
+    if init::NATIVE_CODE {
+        crate::gtk_link::gtk_extern! {
+            fn g_set_prgname(prgname: *const std::ffi::c_char);
+            fn gdk_set_program_class(program_class: *const std::ffi::c_char);
+        }
+        util::with_c_strings([app_id.as_ref()], |[app_id]| unsafe {
+            g_set_prgname(app_id);
+            gdk_set_program_class(app_id);
+        })?;
     }
+    Ok(())
 }
@@ -0,0 +1,253 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! The `log` feature, which surfaces log records as dialogs or notifications.
+
+use crate::{DialogBox, DialogType, Notification, NotificationKind};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// How a [`DialogLogger`] shows the records it surfaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    /// A [`DialogBox`], shown one at a time.
+    Dialog,
+    /// A [`Notification`].
+    Notification,
+}
+
+struct Pending {
+    level: Level,
+    message: String,
+}
+
+struct State {
+    pending: VecDeque<Pending>,
+    shown: VecDeque<Instant>,
+    dropped: u64,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    pending: VecDeque::new(),
+    shown: VecDeque::new(),
+    dropped: 0,
+});
+
+static SURFACE: Mutex<Surface> = Mutex::new(Surface::Dialog);
+
+/// A [`log`] logger that shows records as error dialogs or notifications, for small GUI tools
+/// that have no console to print errors to. Requires the `log` feature.
+///
+/// Records can be logged from any thread, but dialogs must be shown from the thread that
+/// initialized NvDialog, so surfaced records are queued and shown by [`crate::pump_events`]
+/// (one dialog per call) or [`show_logged`]. Records above the configured level, and records
+/// over the per-minute limit, are only passed to the logger set with
+/// [`DialogLogger::forward_to`], if any; the next record shown mentions how many were dropped.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{DialogLogger, Surface};
+///
+/// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+/// DialogLogger::new()
+///     .surface(Surface::Notification)
+///     .install()
+///     .expect("A logger was already installed");
+///
+/// log::error!("Failed to save the settings");
+/// nvdialog_rs::show_logged();
+/// ```
+pub struct DialogLogger {
+    level: LevelFilter,
+    surface: Surface,
+    max_per_minute: u32,
+    forward: Option<Box<dyn Log>>,
+}
+
+impl DialogLogger {
+    /// Creates a logger surfacing `error!` records as dialogs, at most 5 per minute.
+    pub fn new() -> Self {
+        Self {
+            level: LevelFilter::Error,
+            surface: Surface::Dialog,
+            max_per_minute: 5,
+            forward: None,
+        }
+    }
+
+    /// Surfaces records of `level` and above, instead of only errors.
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Shows records as `surface` instead of dialogs.
+    pub fn surface(mut self, surface: Surface) -> Self {
+        self.surface = surface;
+        self
+    }
+
+    /// Shows at most `max_per_minute` records in any 60 seconds.
+    pub fn max_per_minute(mut self, max_per_minute: u32) -> Self {
+        self.max_per_minute = max_per_minute;
+        self
+    }
+
+    /// Passes every record to `logger` too, so that records still reach the console or a log
+    /// file. The maximum level of the `log` crate is raised to what `logger` accepts.
+    pub fn forward_to<L: Log + 'static>(mut self, logger: L) -> Self {
+        self.forward = Some(Box::new(logger));
+        self
+    }
+
+    /// Installs this logger as the logger of the `log` crate.
+    ///
+    /// # Errors
+    /// Returns the error of [`log::set_boxed_logger`] if a logger was already installed.
+    pub fn install(self) -> Result<(), SetLoggerError> {
+        let max_level = if self.forward.is_some() {
+            LevelFilter::Trace
+        } else {
+            self.level
+        };
+        *SURFACE.lock().unwrap_or_else(|e| e.into_inner()) = self.surface;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+
+    /// Queues `record` to be shown, unless the limit was reached.
+    fn queue(&self, record: &Record) {
+        let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        while state
+            .shown
+            .front()
+            .is_some_and(|shown| now.duration_since(*shown) >= WINDOW)
+        {
+            state.shown.pop_front();
+        }
+        if state.shown.len() >= self.max_per_minute as usize {
+            state.dropped += 1;
+            return;
+        }
+        state.shown.push_back(now);
+
+        let mut message = record.args().to_string();
+        match std::mem::take(&mut state.dropped) {
+            0 => {}
+            1 => message += "\n\n1 more record was not shown.",
+            dropped => message += &format!("\n\n{dropped} more records were not shown."),
+        }
+        state.pending.push_back(Pending {
+            level: record.level(),
+            message,
+        });
+    }
+}
+
+impl Default for DialogLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Log for DialogLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+            || self
+                .forward
+                .as_ref()
+                .is_some_and(|forward| forward.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        if let Some(forward) = &self.forward {
+            forward.log(record);
+        }
+        if record.level() <= self.level {
+            self.queue(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(forward) = &self.forward {
+            forward.flush();
+        }
+    }
+}
+
+/// Shows every record queued by the [`DialogLogger`]. Must be called from the thread that
+/// initialized NvDialog.
+pub fn show_logged() {
+    while show_next() {}
+}
+
+/// Called by [`crate::pump_events`]: shows the next queued record. Returns `false` if there was
+/// none.
+pub(crate) fn show_next() -> bool {
+    let Some(pending) = STATE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .pending
+        .pop_front()
+    else {
+        return false;
+    };
+    let title = match pending.level {
+        Level::Error => "Error",
+        Level::Warn => "Warning",
+        _ => "Information",
+    };
+    match *SURFACE.lock().unwrap_or_else(|e| e.into_inner()) {
+        Surface::Dialog => {
+            let kind = match pending.level {
+                Level::Error => DialogType::Error,
+                Level::Warn => DialogType::Warning,
+                _ => DialogType::Simple,
+            };
+            if let Ok(mut dialog) = DialogBox::new(title, pending.message.as_str(), kind) {
+                dialog.show();
+            }
+        }
+        Surface::Notification => {
+            let kind = match pending.level {
+                Level::Error => NotificationKind::Error,
+                Level::Warn => NotificationKind::Warning,
+                _ => NotificationKind::Simple,
+            };
+            /* Logging failures about the logger would only queue more of them. */
+            if let Ok(mut notification) = Notification::new(title, pending.message.as_str(), kind) {
+                let _ = notification.send();
+            }
+        }
+    }
+    true
+}
@@ -0,0 +1,183 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+/// Creates and shows a [`DialogBox`](crate::DialogBox) in a single expression.
+///
+/// The dialog type is given as `simple`, `warning` or `error` before the title, and defaults to
+/// `simple` when omitted. The title and message can be anything implementing `AsRef<str>`, and
+/// an optional `accept:` label sets the text of the accept button. The macro evaluates to a
+/// `Result<(), Error>`, which is an error if the dialog couldn't be created.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::dialog;
+///
+/// nvdialog_rs::init().expect("Can't initialize NvDialog");
+///
+/// dialog!("Hello" => "A simple dialog box.").unwrap();
+/// dialog!(warning "Disk almost full" => "Only 2% of space is left.", accept: "Got it").unwrap();
+/// dialog!(error "Save failed" => format!("Can't write to {}", "/tmp/file.txt")).unwrap();
+/// ```
+#[macro_export]
+macro_rules! dialog {
+    (simple $title:expr => $msg:expr $(, accept: $accept:expr)? $(,)?) => {
+        $crate::dialog!(@show $crate::DialogType::Simple, $title, $msg $(, $accept)?)
+    };
+    (warning $title:expr => $msg:expr $(, accept: $accept:expr)? $(,)?) => {
+        $crate::dialog!(@show $crate::DialogType::Warning, $title, $msg $(, $accept)?)
+    };
+    (error $title:expr => $msg:expr $(, accept: $accept:expr)? $(,)?) => {
+        $crate::dialog!(@show $crate::DialogType::Error, $title, $msg $(, $accept)?)
+    };
+    (@show $kind:expr, $title:expr, $msg:expr $(, $accept:expr)?) => {
+        $crate::DialogBox::new(
+            ::std::convert::AsRef::<str>::as_ref(&$title),
+            ::std::convert::AsRef::<str>::as_ref(&$msg),
+            $kind,
+        )
+        .and_then(|mut dialog| {
+            $(dialog.set_accept_label(::std::convert::AsRef::<str>::as_ref(&$accept))?;)?
+            dialog.show();
+            ::std::result::Result::Ok(())
+        })
+    };
+    ($title:expr => $msg:expr $(, accept: $accept:expr)? $(,)?) => {
+        $crate::dialog!(simple $title => $msg $(, accept: $accept)?)
+    };
+}
+
+/// Asks a question with a [`QuestionDialog`](crate::QuestionDialog) and evaluates to the
+/// [`Reply`](crate::Reply).
+///
+/// The buttons are given as `yes`, `yes_no` or `yes_no_cancel` before the title, and default to
+/// `yes_no` when omitted.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{ask, Reply};
+///
+/// nvdialog_rs::init().expect("Can't initialize NvDialog");
+///
+/// if ask!("Delete file?" => "This action cannot be undone.") == Reply::Accepted {
+///     // delete the file...
+/// }
+/// let reply = ask!(yes_no_cancel "Unsaved changes" => "Save the document before closing?");
+/// ```
+#[macro_export]
+macro_rules! ask {
+    (yes $title:expr => $msg:expr $(,)?) => {
+        $crate::ask!(@ask $crate::QuestionDialogButtons::Yes, $title, $msg)
+    };
+    (yes_no $title:expr => $msg:expr $(,)?) => {
+        $crate::ask!(@ask $crate::QuestionDialogButtons::YesNo, $title, $msg)
+    };
+    (yes_no_cancel $title:expr => $msg:expr $(,)?) => {
+        $crate::ask!(@ask $crate::QuestionDialogButtons::YesNoCancel, $title, $msg)
+    };
+    (@ask $buttons:expr, $title:expr, $msg:expr) => {
+        $crate::QuestionDialog::new(
+            ::std::convert::AsRef::<str>::as_ref(&$title),
+            ::std::convert::AsRef::<str>::as_ref(&$msg),
+            $buttons,
+        )
+        .get_reply()
+    };
+    ($title:expr => $msg:expr $(,)?) => {
+        $crate::ask!(yes_no $title => $msg)
+    };
+}
+
+/// Checks that a condition holds in debug builds, like [`debug_assert!`], but asks the user
+/// what to do instead of panicking when it doesn't.
+///
+/// A warning shows the failed condition, the optional message and where the assertion is, and
+/// asks whether to continue. Answering no aborts the process. This is meant for GUI
+/// applications started without a console, where a panic message would go unseen. When
+/// NvDialog isn't initialized in the current thread, a failed assertion panics like
+/// [`assert!`] instead. In release builds, the condition isn't evaluated.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::gui_assert;
+///
+/// nvdialog_rs::init().expect("Can't initialize NvDialog");
+///
+/// let layers = vec!["background"];
+/// gui_assert!(!layers.is_empty());
+/// gui_assert!(layers.len() < 64, "Too many layers: {}", layers.len());
+/// ```
+#[macro_export]
+macro_rules! gui_assert {
+    ($cond:expr $(,)?) => {
+        if ::std::cfg!(debug_assertions) && !$cond {
+            $crate::__gui_assert_failed(
+                ::std::stringify!($cond),
+                ::std::option::Option::None,
+                ::std::file!(),
+                ::std::line!(),
+            );
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if ::std::cfg!(debug_assertions) && !$cond {
+            $crate::__gui_assert_failed(
+                ::std::stringify!($cond),
+                ::std::option::Option::Some(::std::format_args!($($arg)+)),
+                ::std::file!(),
+                ::std::line!(),
+            );
+        }
+    };
+}
+
+/// Called by [`gui_assert!`] when the condition doesn't hold.
+#[doc(hidden)]
+#[cold]
+pub fn __gui_assert_failed(
+    condition: &str,
+    message: Option<std::fmt::Arguments<'_>>,
+    file: &str,
+    line: u32,
+) {
+    let message = match message {
+        Some(message) => format!("{message}\n\n"),
+        None => String::new(),
+    };
+    if !crate::is_initialized() {
+        panic!("assertion failed: {condition}\n{message}");
+    }
+    let text = format!(
+        "Assertion failed: {condition}\n{message}at {file}:{line}\n\nContinue anyway? \
+         Answering no aborts the application."
+    );
+    let reply = crate::QuestionDialog::new(
+        "Assertion failed",
+        text.as_str(),
+        crate::QuestionDialogButtons::YesNo,
+    )
+    .get_reply();
+    if reply != crate::Reply::Accepted {
+        std::process::abort();
+    }
+}
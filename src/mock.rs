@@ -0,0 +1,713 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! The [`Backend::Mock`] backend, which records dialogs instead of showing them, and answers
+//! them from scripted rules. Enabled with the `mock` feature.
+
+use crate::{
+    Backend, ButtonLayout, DialogInfo, DialogKind, DialogType, FileDialogType,
+    QuestionDialogButtons, Reply,
+};
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{Arc, Condvar, Mutex},
+};
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+#[derive(Default)]
+struct State {
+    snapshots: Vec<String>,
+    shown: Vec<DialogInfo>,
+    expected_dialogs: Option<usize>,
+    questions: Vec<Rule<Reply>>,
+    files: Vec<Rule<FileAnswer>>,
+    /// Closes the next file dialog, see [`hold_next_file_dialog`].
+    held: Option<Arc<HeldDialog>>,
+}
+
+/// How a [`FileDialogRule`] answers.
+#[derive(Clone)]
+enum FileAnswer {
+    Path(Option<PathBuf>),
+    /// See [`FileDialogRule::until_closed`].
+    UntilClosed,
+}
+
+/// A scripted answer, along with how many times it was used.
+struct Rule<T> {
+    matcher: Matcher,
+    answer: T,
+    expected: Option<usize>,
+    hits: usize,
+}
+
+/// Selects the dialogs a [`MockBackend`] rule applies to.
+///
+/// Build one with [`title_contains`], [`message_contains`], [`any`] or [`Matcher::new`].
+pub struct Matcher {
+    predicate: Box<dyn Fn(&DialogInfo) -> bool>,
+    description: String,
+}
+
+impl Matcher {
+    /// Matches the dialogs for which `f` returns `true`.
+    pub fn new<F: Fn(&DialogInfo) -> bool + 'static>(f: F) -> Self {
+        Self::described("custom matcher", f)
+    }
+
+    fn described<F: Fn(&DialogInfo) -> bool + 'static>(description: &str, f: F) -> Self {
+        Self {
+            predicate: Box::new(f),
+            description: String::from(description),
+        }
+    }
+
+    fn matches(&self, info: &DialogInfo) -> bool {
+        (self.predicate)(info)
+    }
+}
+
+/// Matches dialogs whose title contains `text`.
+pub fn title_contains<S: Into<String>>(text: S) -> Matcher {
+    let text = text.into();
+    Matcher::described(&format!("title contains {text:?}"), move |info| {
+        info.title.contains(&text)
+    })
+}
+
+/// Matches dialogs whose message contains `text`.
+pub fn message_contains<S: Into<String>>(text: S) -> Matcher {
+    let text = text.into();
+    Matcher::described(&format!("message contains {text:?}"), move |info| {
+        info.message.contains(&text)
+    })
+}
+
+/// Matches every dialog.
+pub fn any() -> Matcher {
+    Matcher::described("any dialog", |_| true)
+}
+
+/// A handle to the mock backend of the current thread, for testing applications built on this
+/// crate.
+///
+/// Once [`MockBackend::install`] is called, dialogs created in the thread are not shown: they
+/// are rendered as text instead, which [`MockBackend::snapshot`] returns, so tests can compare
+/// them against golden files. Notifications and input boxes are not supported.
+///
+/// Questions and file dialogs are answered by the rules registered with
+/// [`MockBackend::on_question`] and [`MockBackend::on_file_dialog`], the first matching rule
+/// winning. A question or file dialog that no rule matches panics, failing the test, so an
+/// application can't show a prompt the test didn't expect.
+///
+/// To check that exactly the intended dialogs were shown, set expectations with
+/// [`MockBackend::expect_dialogs`] and the `times` method of the rules, and call
+/// [`MockBackend::verify`] at the end of the test.
+///
+/// The handle can't be sent to other threads, as every thread has its own mock backend.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DialogBox, DialogType, MockBackend};
+///
+/// let mock = MockBackend::install();
+/// DialogBox::new("Disk full", "Free some space and try again.", DialogType::Warning)
+///     .unwrap()
+///     .show();
+/// assert_eq!(
+///     mock.snapshot().unwrap(),
+///     "+- Warning ----------------------+\n\
+///      | Disk full                      |\n\
+///      |                                |\n\
+///      | Free some space and try again. |\n\
+///      |                                |\n\
+///      |                         [ OK ] |\n\
+///      +--------------------------------+"
+/// );
+/// ```
+///
+/// Scripting replies:
+/// ```
+/// use nvdialog_rs::{
+///     mock::{title_contains, MockBackend},
+///     QuestionDialog, QuestionDialogButtons, Reply,
+/// };
+///
+/// let mock = MockBackend::install();
+/// mock.on_question(title_contains("Delete")).times(1).reply(Reply::Accepted);
+/// mock.on_file_dialog().return_path("/tmp/picture.png");
+/// mock.expect_dialogs(1);
+///
+/// let mut question = QuestionDialog::new("Delete file?", "", QuestionDialogButtons::YesNo);
+/// assert_eq!(question.get_reply(), Reply::Accepted);
+/// mock.verify();
+/// ```
+pub struct MockBackend {
+    _thread: PhantomData<*const ()>,
+}
+
+impl MockBackend {
+    /// Makes the mock backend the active backend of the current thread, initializing the thread
+    /// if needed, and clears everything recorded so far.
+    pub fn install() -> Self {
+        STATE.with(|state| *state.borrow_mut() = State::default());
+        crate::init::force_backend(Backend::Mock);
+        Self {
+            _thread: PhantomData,
+        }
+    }
+
+    /// Adds a rule answering the questions `matcher` selects.
+    pub fn on_question(&self, matcher: Matcher) -> QuestionRule<'_> {
+        QuestionRule {
+            _mock: self,
+            matcher,
+            expected: None,
+        }
+    }
+
+    /// Adds a rule answering every file dialog. Use [`MockBackend::on_file_dialog_matching`] to
+    /// only answer some of them.
+    pub fn on_file_dialog(&self) -> FileDialogRule<'_> {
+        self.on_file_dialog_matching(any())
+    }
+
+    /// Adds a rule answering the file dialogs `matcher` selects.
+    pub fn on_file_dialog_matching(&self, matcher: Matcher) -> FileDialogRule<'_> {
+        FileDialogRule {
+            _mock: self,
+            matcher,
+            expected: None,
+        }
+    }
+
+    /// Expects exactly `count` dialogs of any kind to be shown, checked by [`MockBackend::verify`].
+    pub fn expect_dialogs(&self, count: usize) {
+        STATE.with(|state| state.borrow_mut().expected_dialogs = Some(count));
+    }
+
+    /// Returns every dialog shown since [`MockBackend::install`], in order.
+    pub fn dialogs(&self) -> Vec<DialogInfo> {
+        STATE.with(|state| state.borrow().shown.clone())
+    }
+
+    /// Returns how many of the dialogs shown so far `matcher` selects.
+    pub fn count(&self, matcher: &Matcher) -> usize {
+        STATE.with(|state| {
+            let state = state.borrow();
+            state
+                .shown
+                .iter()
+                .filter(|info| matcher.matches(info))
+                .count()
+        })
+    }
+
+    /// Checks the expectations set with [`MockBackend::expect_dialogs`] and the `times` method of
+    /// the rules.
+    ///
+    /// # Panics
+    /// Panics with a description of every expectation that wasn't met, along with the dialogs
+    /// that were shown.
+    pub fn verify(&self) {
+        STATE.with(|state| {
+            let state = state.borrow();
+            let mut failures = Vec::new();
+            if let Some(expected) = state.expected_dialogs {
+                if state.shown.len() != expected {
+                    failures.push(format!(
+                        "expected {expected} dialogs, but {} were shown",
+                        state.shown.len()
+                    ));
+                }
+            }
+            let rules = state
+                .questions
+                .iter()
+                .map(|rule| ("question", &rule.matcher, rule.expected, rule.hits))
+                .chain(
+                    state
+                        .files
+                        .iter()
+                        .map(|rule| ("file dialog", &rule.matcher, rule.expected, rule.hits)),
+                );
+            for (kind, matcher, expected, hits) in rules {
+                match expected {
+                    Some(expected) if expected != hits => failures.push(format!(
+                        "expected {expected} {kind}s where {}, but {hits} were shown",
+                        matcher.description
+                    )),
+                    _ => {}
+                }
+            }
+            if !failures.is_empty() {
+                panic!(
+                    "nvdialog-rs: MockBackend expectations were not met:\n- {}\n\nDialogs shown:\n{}",
+                    failures.join("\n- "),
+                    state.snapshots.join("\n")
+                );
+            }
+        });
+    }
+
+    /// Returns the rendering of the last dialog shown, or `None` if no dialog was shown yet.
+    ///
+    /// Dialogs are drawn as a box, with the kind of dialog in the top border, the title and
+    /// message inside, and the buttons aligned to the right at the bottom. Lines of the message
+    /// are kept as-is, and the box is exactly as wide as its content, so the output only changes
+    /// when the dialog does.
+    ///
+    /// The buttons of questions follow their [`ButtonLayout`]. [`ButtonLayout::Platform`] depends
+    /// on the platform the tests run on, so set another layout with
+    /// [`set_button_layout`](crate::set_button_layout) for snapshots shared across platforms.
+    pub fn snapshot(&self) -> Option<String> {
+        STATE.with(|state| state.borrow().snapshots.last().cloned())
+    }
+
+    /// Returns the renderings of every dialog shown since [`MockBackend::install`], in order.
+    pub fn snapshots(&self) -> Vec<String> {
+        STATE.with(|state| state.borrow().snapshots.clone())
+    }
+}
+
+/// A rule for questions, started with [`MockBackend::on_question`].
+pub struct QuestionRule<'a> {
+    _mock: &'a MockBackend,
+    matcher: Matcher,
+    expected: Option<usize>,
+}
+
+impl QuestionRule<'_> {
+    /// Expects this rule to answer exactly `count` questions, checked by [`MockBackend::verify`].
+    ///
+    /// Once it answered `count` questions, the next rule matching them answers instead, so rules
+    /// for the same questions can script a sequence of replies.
+    pub fn times(mut self, count: usize) -> Self {
+        self.expected = Some(count);
+        self
+    }
+
+    /// Answers the matching questions with `reply`.
+    pub fn reply(self, reply: Reply) {
+        let rule = Rule {
+            matcher: self.matcher,
+            answer: reply,
+            expected: self.expected,
+            hits: 0,
+        };
+        STATE.with(|state| state.borrow_mut().questions.push(rule));
+    }
+}
+
+/// A rule for file dialogs, started with [`MockBackend::on_file_dialog`].
+pub struct FileDialogRule<'a> {
+    _mock: &'a MockBackend,
+    matcher: Matcher,
+    expected: Option<usize>,
+}
+
+impl FileDialogRule<'_> {
+    /// Expects this rule to answer exactly `count` file dialogs, checked by
+    /// [`MockBackend::verify`].
+    ///
+    /// Once it answered `count` file dialogs, the next rule matching them answers instead.
+    pub fn times(mut self, count: usize) -> Self {
+        self.expected = Some(count);
+        self
+    }
+
+    /// Makes the matching file dialogs return `path`.
+    pub fn return_path<P: Into<PathBuf>>(self, path: P) {
+        self.answer(FileAnswer::Path(Some(path.into())));
+    }
+
+    /// Makes the matching file dialogs return `None`, as if the user cancelled them.
+    pub fn cancel(self) {
+        self.answer(FileAnswer::Path(None));
+    }
+
+    /// Keeps the matching file dialogs open until their [`CancelToken`](crate::CancelToken) is
+    /// cancelled, or the future returned by `show_file_dialog_async` is dropped, and then makes
+    /// them return `None`, so that tests can cover cancelling them.
+    ///
+    /// # Panics
+    /// The matching file dialogs panic if they weren't given a
+    /// [`CancelToken`](crate::CancelToken), as nothing could close them.
+    pub fn until_closed(self) {
+        self.answer(FileAnswer::UntilClosed);
+    }
+
+    fn answer(self, answer: FileAnswer) {
+        let rule = Rule {
+            matcher: self.matcher,
+            answer,
+            expected: self.expected,
+            hits: 0,
+        };
+        STATE.with(|state| state.borrow_mut().files.push(rule));
+    }
+}
+
+/// Draws a dialog as text, see [`MockBackend::snapshot`].
+fn render(kind: &str, title: &str, body: &str, buttons: &[&str]) -> String {
+    let buttons = buttons
+        .iter()
+        .map(|button| format!("[ {button} ]"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut lines = vec![title, ""];
+    if !body.is_empty() {
+        lines.extend(body.lines());
+        lines.push("");
+    }
+    let width = lines
+        .iter()
+        .chain([&buttons.as_str()])
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or_default()
+        .max(kind.chars().count() + 2);
+
+    let mut out = format!(
+        "+- {kind} {}+\n",
+        "-".repeat(width - kind.chars().count() - 1)
+    );
+    for line in lines {
+        out += &format!("| {line:<width$} |\n");
+    }
+    out += &format!("| {buttons:>width$} |\n");
+    out += &format!("+{}+", "-".repeat(width + 2));
+    out
+}
+
+/// Returns a button label as shown, without the underscores marking its mnemonic.
+fn without_mnemonic(label: &str) -> String {
+    let mut shown = String::with_capacity(label.len());
+    let mut chars = label.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '_' if chars.peek() == Some(&'_') => {
+                chars.next();
+                shown.push('_');
+            }
+            '_' => {}
+            c => shown.push(c),
+        }
+    }
+    shown
+}
+
+fn record(info: &DialogInfo, snapshot: &str) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.shown.push(info.clone());
+        state.snapshots.push(String::from(snapshot));
+    });
+}
+
+/// Returns the answer of the first rule in `rules` matching `info`, counting the hit.
+///
+/// Rules that already answered as many dialogs as their `times` expect are skipped. If every
+/// matching rule is used up, the first one answers anyway, so that [`MockBackend::verify`]
+/// reports the extra dialog.
+fn answer<T: Clone>(info: &DialogInfo, rules: fn(&mut State) -> &mut Vec<Rule<T>>) -> Option<T> {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let rules = rules(&mut state);
+        let exhausted =
+            |rule: &Rule<T>| rule.expected.is_some_and(|expected| rule.hits >= expected);
+        let index = rules
+            .iter()
+            .position(|rule| rule.matcher.matches(info) && !exhausted(rule))
+            .or_else(|| rules.iter().position(|rule| rule.matcher.matches(info)))?;
+        let rule = &mut rules[index];
+        rule.hits += 1;
+        Some(rule.answer.clone())
+    })
+}
+
+pub(crate) fn message(title: &str, msg: &str, dialog_type: DialogType) {
+    let kind = match dialog_type {
+        DialogType::Simple => "Message",
+        DialogType::Warning => "Warning",
+        DialogType::Error => "Error",
+    };
+    let info = DialogInfo {
+        kind: DialogKind::DialogBox(dialog_type),
+        title: String::from(title),
+        message: String::from(msg),
+    };
+    record(&info, &render(kind, title, msg, &["OK"]));
+}
+
+pub(crate) fn question(
+    title: &str,
+    msg: &str,
+    buttons: &QuestionDialogButtons,
+    layout: ButtonLayout,
+    labels: &[(Reply, &str)],
+) -> Reply {
+    let info = DialogInfo {
+        kind: DialogKind::Question(buttons.clone()),
+        title: String::from(title),
+        message: String::from(msg),
+    };
+    let shown: &[Reply] = match buttons {
+        QuestionDialogButtons::Yes => &[Reply::Accepted],
+        QuestionDialogButtons::YesNo => &[Reply::Accepted, Reply::Rejected],
+        QuestionDialogButtons::YesNoCancel => &[Reply::Accepted, Reply::Rejected, Reply::Cancelled],
+    };
+    let labels: Vec<String> = layout
+        .order()
+        .into_iter()
+        .filter(|reply| shown.contains(reply))
+        .map(|reply| {
+            let label = labels.iter().find(|(labelled, _)| *labelled == reply);
+            match label {
+                Some((_, label)) => without_mnemonic(label),
+                None => String::from(match reply {
+                    Reply::Accepted => "Yes",
+                    Reply::Rejected => "No",
+                    Reply::Cancelled | Reply::Dismissed => "Cancel",
+                }),
+            }
+        })
+        .collect();
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let snapshot = render("Question", title, msg, &labels);
+    record(&info, &snapshot);
+    answer(&info, |state| &mut state.questions).unwrap_or_else(|| {
+        panic!("nvdialog-rs: no MockBackend rule matches this question:\n{snapshot}")
+    })
+}
+
+pub(crate) fn file(
+    title: &str,
+    dialog_type: FileDialogType,
+    extensions: &[String],
+) -> Option<PathBuf> {
+    let (kind, accept) = match dialog_type {
+        FileDialogType::OpenFile => ("Open file", "Open"),
+        FileDialogType::SaveFile => ("Save file", "Save"),
+    };
+    let filter = match extensions.is_empty() {
+        true => String::new(),
+        false => format!("Files: {}", extensions.join(", ")),
+    };
+    let info = DialogInfo {
+        kind: DialogKind::File(dialog_type),
+        title: String::from(title),
+        message: String::new(),
+    };
+    let snapshot = render(kind, title, &filter, &[accept, "Cancel"]);
+    record(&info, &snapshot);
+    let held = STATE.with(|state| state.borrow_mut().held.take());
+    let answer = answer(&info, |state| &mut state.files).unwrap_or_else(|| {
+        panic!("nvdialog-rs: no MockBackend rule matches this file dialog:\n{snapshot}")
+    });
+    match answer {
+        FileAnswer::Path(path) => path,
+        FileAnswer::UntilClosed => {
+            let held = held.unwrap_or_else(|| {
+                panic!(
+                    "nvdialog-rs: this file dialog is kept open until it is closed, but it has no \
+                     `CancelToken`:\n{snapshot}"
+                )
+            });
+            held.wait();
+            None
+        }
+    }
+}
+
+/// A file dialog kept open by [`FileDialogRule::until_closed`], which any thread can close.
+#[derive(Default)]
+pub(crate) struct HeldDialog {
+    closed: Mutex<bool>,
+    changed: Condvar,
+}
+
+impl HeldDialog {
+    pub(crate) fn close(&self) {
+        *self.closed.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        self.changed.notify_all();
+    }
+
+    fn wait(&self) {
+        let mut closed = self.closed.lock().unwrap_or_else(|e| e.into_inner());
+        while !*closed {
+            closed = self.changed.wait(closed).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+/// Returns what closes the next file dialog of the current thread, if a rule keeps it open.
+pub(crate) fn hold_next_file_dialog() -> Arc<HeldDialog> {
+    let held = Arc::<HeldDialog>::default();
+    STATE.with(|state| state.borrow_mut().held = Some(held.clone()));
+    held
+}
+
+#[cfg(all(test, not(feature = "strict-threading")))]
+mod tests {
+    use super::*;
+    use crate::{DialogBox, FileDialog, QuestionDialog};
+
+    #[test]
+    fn message_boxes_are_drawn_with_an_ok_button() {
+        let mock = MockBackend::install();
+        DialogBox::new("Saved", "The file was saved.", DialogType::Simple)
+            .unwrap()
+            .show();
+        let expected = [
+            "+- Message -----------+",
+            "| Saved               |",
+            "|                     |",
+            "| The file was saved. |",
+            "|                     |",
+            "|              [ OK ] |",
+            "+---------------------+",
+        ];
+        assert_eq!(mock.snapshot().unwrap(), expected.join("\n"));
+    }
+
+    #[test]
+    fn questions_keep_the_lines_of_their_message() {
+        let mock = MockBackend::install();
+        mock.on_question(any()).reply(Reply::Rejected);
+        let buttons = QuestionDialogButtons::YesNo;
+        QuestionDialog::new("Quit", "Unsaved changes\nwill be lost.", buttons)
+            .button_layout(ButtonLayout::AffirmativeLeft)
+            .get_reply();
+        let expected = [
+            "+- Question ------+",
+            "| Quit            |",
+            "|                 |",
+            "| Unsaved changes |",
+            "| will be lost.   |",
+            "|                 |",
+            "|  [ Yes ] [ No ] |",
+            "+-----------------+",
+        ];
+        assert_eq!(mock.snapshot().unwrap(), expected.join("\n"));
+    }
+
+    #[test]
+    fn file_dialogs_list_their_extensions() {
+        let mock = MockBackend::install();
+        mock.on_file_dialog().cancel();
+        FileDialog::new("Pick", FileDialogType::OpenFile, Some(["png", "jpg"])).retrieve_filename();
+        let expected = [
+            "+- Open file ---------+",
+            "| Pick                |",
+            "|                     |",
+            "| Files: png, jpg     |",
+            "|                     |",
+            "| [ Open ] [ Cancel ] |",
+            "+---------------------+",
+        ];
+        assert_eq!(mock.snapshot().unwrap(), expected.join("\n"));
+    }
+
+    #[test]
+    fn used_up_rules_let_the_next_one_answer() {
+        let mock = MockBackend::install();
+        mock.on_question(title_contains("Retry"))
+            .times(2)
+            .reply(Reply::Accepted);
+        mock.on_question(title_contains("Retry"))
+            .times(1)
+            .reply(Reply::Rejected);
+        let ask = || QuestionDialog::new("Retry?", "", QuestionDialogButtons::YesNo).get_reply();
+        assert_eq!(ask(), Reply::Accepted);
+        assert_eq!(ask(), Reply::Accepted);
+        assert_eq!(ask(), Reply::Rejected);
+        mock.verify();
+    }
+
+    #[test]
+    fn verify_passes_when_the_expected_dialogs_were_shown() {
+        let mock = MockBackend::install();
+        mock.on_question(any()).times(1).reply(Reply::Accepted);
+        mock.on_file_dialog().times(1).cancel();
+        mock.expect_dialogs(2);
+        QuestionDialog::new("Delete?", "", QuestionDialogButtons::YesNo).get_reply();
+        FileDialog::new("Pick", FileDialogType::OpenFile, None::<[&str; 0]>).retrieve_filename();
+        mock.verify();
+        assert_eq!(mock.count(&title_contains("Delete")), 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected 1 questions where title contains \"Delete\", but 0 were shown"
+    )]
+    fn verify_reports_missing_dialogs() {
+        let mock = MockBackend::install();
+        mock.on_question(title_contains("Delete"))
+            .times(1)
+            .reply(Reply::Accepted);
+        mock.verify();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 1 dialogs, but 2 were shown")]
+    fn verify_reports_unexpected_dialogs() {
+        let mock = MockBackend::install();
+        mock.expect_dialogs(1);
+        for _ in 0..2 {
+            DialogBox::new("Saved", "", DialogType::Simple)
+                .unwrap()
+                .show();
+        }
+        mock.verify();
+    }
+
+    #[test]
+    fn the_box_is_widened_to_fit_its_kind() {
+        let snapshot = render("Question", "Hi", "", &["Yes"]);
+        let expected = [
+            "+- Question -+",
+            "| Hi         |",
+            "|            |",
+            "|    [ Yes ] |",
+            "+------------+",
+        ];
+        assert_eq!(snapshot, expected.join("\n"));
+    }
+
+    #[test]
+    fn mnemonics_are_hidden_from_labels() {
+        assert_eq!(without_mnemonic("_Save"), "Save");
+        assert_eq!(
+            without_mnemonic("Close _without Saving"),
+            "Close without Saving"
+        );
+        assert_eq!(without_mnemonic("snake__case"), "snake_case");
+    }
+}
@@ -0,0 +1,58 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! The `noop` feature, which turns every dialog into a no-op.
+
+use crate::{DefaultAnswer, Reply};
+use std::{path::PathBuf, sync::Mutex};
+
+struct Defaults {
+    file: Option<PathBuf>,
+}
+
+static DEFAULTS: Mutex<Defaults> = Mutex::new(Defaults { file: None });
+
+/// Sets the reply every question returns with the `noop` feature. Defaults to
+/// [`Reply::Cancelled`].
+///
+/// Questions return their [`DefaultAnswer`] under the `noop` feature, so this is the same as
+/// [`crate::set_default_answer`], which also covers the other unattended modes.
+#[deprecated(note = "use `set_default_answer`")]
+pub fn set_noop_reply(reply: Reply) {
+    crate::set_default_answer(DefaultAnswer(reply));
+}
+
+/// Sets the path every file dialog returns with the `noop` feature. Defaults to `None`, as if
+/// every file dialog was cancelled.
+pub fn set_noop_file<P: Into<PathBuf>>(file: Option<P>) {
+    DEFAULTS.lock().unwrap_or_else(|e| e.into_inner()).file = file.map(Into::into);
+}
+
+pub(crate) fn file() -> Option<PathBuf> {
+    DEFAULTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .file
+        .clone()
+}
@@ -22,8 +22,11 @@
  * IN THE SOFTWARE.
  */
 
-use crate::c_string;
+use crate::{
+    rate_limit::Admission, util::with_c_strings, DeliveryStatus, DialogInfo, DialogKind, Object,
+};
 use nvdialog_sys::ffi::*;
+use std::time::{Duration, Instant};
 
 /// A notification dialog, which can be used to send a notification to the user.
 ///
@@ -32,10 +35,15 @@ use nvdialog_sys::ffi::*;
 /// use nvdialog_rs::Notification;
 ///
 /// let mut notification = Notification::new("Hello world!", "This is a notification.");
-/// notification.send();
+/// notification.send().expect("Failed to send notification");
 /// ```
 pub struct Notification {
     raw: *mut NvdNotification,
+    title: String,
+    msg: String,
+    kind: NotificationKind,
+    /// Whether the notification is sent through `osascript` instead of NvDialog.
+    osascript: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -69,7 +77,14 @@ impl Notification {
     /// * `kind`: The kind of the notification, see [`NotificationKind`].
     ///
     /// # Errors
-    /// Returns an `Error` of type `OutOfMemory` if NvDialog's allocation failed.
+    /// Returns an `Error` of type `OutOfMemory` if NvDialog's allocation failed, or
+    /// `NotYetInitialized` if [`crate::init`] hasn't been called in this thread, or
+    /// `ParametersError` if `title` or `msg` contain a null byte, or `Unsupported` if a fallback
+    /// [`Backend`](crate::Backend) is active.
+    ///
+    /// On macOS, processes that don't run from an app bundle can't send notifications (see
+    /// [`crate::is_app_bundle`]). Their notifications are sent through `osascript` with the
+    /// `external-tools` feature, and `RequiresAppBundle` is returned without it.
     ///
     /// # Examples
     /// ```
@@ -83,23 +98,121 @@ impl Notification {
         msg: S,
         kind: NotificationKind,
     ) -> Result<Self, crate::Error> {
-        let t = c_string!(title.as_ref());
-        let m = c_string!(msg.as_ref());
-        let raw = unsafe { nvd_notification_new(t.as_ptr(), m.as_ptr(), kind.into()) };
+        crate::init::check_thread();
+        #[cfg(feature = "auto-reply")]
+        if crate::auto_reply_enabled() {
+            return Ok(Self::detached(title.as_ref(), msg.as_ref(), kind));
+        }
+
+        crate::init::ensure_initialized()?;
+        if crate::backend::fallback().is_some() {
+            return Err(crate::Error::Unsupported
+                .context("Notifications are only supported by the native backend"));
+        }
+        if !crate::init::native_available() {
+            return Ok(Self::detached(title.as_ref(), msg.as_ref(), kind));
+        }
+        if cfg!(target_os = "macos") && !crate::is_app_bundle() {
+            #[cfg(feature = "external-tools")]
+            {
+                let mut notification = Self::detached(title.as_ref(), msg.as_ref(), kind);
+                notification.osascript = true;
+                return Ok(notification);
+            }
+            #[cfg(not(feature = "external-tools"))]
+            return Err(crate::Error::RequiresAppBundle
+                .context("macOS only delivers notifications from app bundles"));
+        }
+        let raw = with_c_strings([title.as_ref(), msg.as_ref()], |[t, m]| unsafe {
+            nvd_notification_new(t, m, kind.clone().into())
+        })?;
 
         if raw.is_null() {
-            return Err(crate::Error::OutOfMemory);
+            return Err(crate::Error::OutOfMemory.context("Failed to create notification"));
         }
-        Ok(Self { raw })
+        let mut notification = Self::detached(title.as_ref(), msg.as_ref(), kind);
+        notification.raw = crate::ffi_check::track(raw);
+        Ok(notification)
+    }
+
+    /// A notification without a native counterpart, which is never sent.
+    fn detached(title: &str, msg: &str, kind: NotificationKind) -> Self {
+        Self {
+            raw: std::ptr::null_mut(),
+            title: String::from(title),
+            msg: String::from(msg),
+            kind,
+            osascript: false,
+        }
+    }
+
+    /// Creates a new `Notification` whose title and body are resolved from message keys through
+    /// the registered [`Localizer`](crate::Localizer), with `args` substituted in.
+    ///
+    /// Keys without a translation are shown as-is. See [`crate::localize`] for details.
+    #[cfg(feature = "i18n")]
+    pub fn new_localized(
+        title_key: &str,
+        msg_key: &str,
+        args: &[(&str, &str)],
+        kind: NotificationKind,
+    ) -> Result<Self, crate::Error> {
+        Self::new(
+            crate::localize(title_key, args),
+            crate::localize(msg_key, args),
+            kind,
+        )
+    }
+
+    /// Returns the title of the notification.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the body of the notification.
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    /// Returns the kind of the notification, which is always [`DialogKind::Notification`].
+    pub fn kind(&self) -> DialogKind {
+        DialogKind::Notification(self.kind.clone())
     }
 
-    pub fn add_action<S: AsRef<str>>(&mut self, name: S, val: i32, ptr: &mut i32) {
-        let a = c_string!(name.as_ref());
-        unsafe {
-            nvd_add_notification_action(self.raw, a.as_ptr(), val, ptr);
+    /// Returns a [`DialogInfo`] describing what the notification is going to display.
+    pub fn info(&self) -> DialogInfo {
+        DialogInfo {
+            kind: self.kind(),
+            title: self.title.clone(),
+            message: self.msg.clone(),
         }
     }
 
+    /// Adds an action button named `name` to the notification. When it is clicked, `val` is
+    /// written to `ptr`.
+    ///
+    /// # Errors
+    /// Returns `ParametersError` if `name` contains a null byte, or `Unsupported` on platforms
+    /// other than Linux, where NvDialog's notifications have no actions.
+    pub fn add_action<S: AsRef<str>>(
+        &mut self,
+        name: S,
+        val: i32,
+        ptr: &mut i32,
+    ) -> Result<(), crate::Error> {
+        crate::init::check_thread();
+        if self.raw.is_null() {
+            return Ok(());
+        }
+        if !cfg!(target_os = "linux") {
+            return Err(crate::Error::Unsupported
+                .context("Notification actions are only supported on Linux"));
+        }
+        with_c_strings([name.as_ref()], |[a]| unsafe {
+            nvd_add_notification_action(self.raw, a, val, ptr);
+        })
+    }
+
     /// Sends the notification to the desktop notification system. If the notification has
     /// already been shown or sent, calling this method again will have no effect.
     ///
@@ -112,7 +225,7 @@ impl Notification {
     ///     .expect("Failed to create notification");
     ///
     /// // Send the notification
-    /// notification.send();
+    /// notification.send().expect("Failed to send notification");
     /// ```
     ///
     /// Note that calling `send` multiple times will result in multiple notifications being shown:
@@ -122,7 +235,7 @@ impl Notification {
     /// let mut notification = Notification::new("Title", "Body", NotificationKind::Simple)
     ///     .expect("Failed to create notification");
     /// // Send the notification
-    /// notification.send();
+    /// notification.send().expect("Failed to send notification");
     /// ```
     ///
     /// It is safe to call this method on a notification that has not yet been shown, as well as
@@ -132,26 +245,208 @@ impl Notification {
     /// # let mut notification = Notification::new("Title", "Body", NotificationKind::Simple)
     /// #     .expect("Failed to create notification");
     /// // Send the notification
-    /// notification.send();
+    /// notification.send().expect("Failed to send notification");
     ///
     /// // It is safe to call `send` multiple times
-    /// notification.send();
+    /// notification.send().expect("Failed to send notification");
     ///
     /// // It is also safe to call `send` on a notification that hasn't been shown yet
     /// let mut other_notification = Notification::new("Other title", "Other body", NotificationKind::Simple)
     ///     .expect("Failed to create notification");
-    /// other_notification.send();
+    /// other_notification.send().expect("Failed to send notification");
     /// ```
     ///
+    /// If a limit was set with [`crate::set_notification_rate_limit`], notifications over it are
+    /// dropped, and the first one sent afterwards mentions how many were.
+    ///
+    /// While a [`crate::NotificationLog`] is installed, the notification and whether it was
+    /// delivered are recorded in it.
+    ///
+    /// # Errors
+    /// Returns the error NvDialog reports if sending failed, for example when the notification
+    /// daemon rejected the notification or DBus is unavailable. Errors that NvDialog had already
+    /// reported before this call are not returned again.
+    ///
     /// # FFI
-    /// Corresponds to `nvd_send_notification`.
-    pub fn send(&mut self) {
-        unsafe { nvd_send_notification(self.raw) }
+    /// Corresponds to `nvd_send_notification`, followed by `nvd_get_error`.
+    pub fn send(&mut self) -> Result<(), crate::Error> {
+        crate::init::check_thread();
+        #[cfg(feature = "recorder")]
+        crate::recorder::record("notification", &self.title, &self.msg, None);
+        let result = match crate::rate_limit::admit(&self.kind) {
+            Admission::Send => self.deliver(),
+            Admission::Drop => {
+                self.log(DeliveryStatus::RateLimited);
+                return Ok(());
+            }
+            Admission::SendWithSummary(summary) => {
+                /* The native notification holds the original text, so send a new one instead. */
+                let msg = format!("{}\n\n{summary}", self.msg);
+                Notification::new(self.title.as_str(), msg.as_str(), self.kind.clone())
+                    .and_then(|mut notification| notification.deliver())
+            }
+        };
+        self.log(match &result {
+            Ok(()) => DeliveryStatus::Delivered,
+            Err(e) => DeliveryStatus::Failed(e.clone()),
+        });
+        result
+    }
+
+    fn log(&self, status: DeliveryStatus) {
+        crate::notification_log::record(&self.title, &self.msg, &self.kind, status);
+    }
+
+    /// Sends the notification once `delay` has passed, for reminders. Returns a handle to cancel
+    /// it with [`ScheduledNotification::cancel_scheduled`](crate::ScheduledNotification::cancel_scheduled).
+    ///
+    /// The notification is sent from the current thread by the first call to
+    /// [`crate::pump_events`] made after `delay`, so the application must call it regularly,
+    /// for example once per frame or from a timer of its event loop. Errors from sending it are
+    /// not reported, but are recorded by an installed [`crate::NotificationLog`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::{Notification, NotificationKind};
+    /// use std::time::Duration;
+    ///
+    /// let reminder = Notification::new("Break", "Time to stretch!", NotificationKind::Simple)
+    ///     .expect("Failed to create notification")
+    ///     .send_after(Duration::from_secs(30 * 60));
+    /// // Later, if the user took a break already:
+    /// reminder.cancel_scheduled();
+    /// ```
+    pub fn send_after(self, delay: Duration) -> crate::ScheduledNotification {
+        crate::init::check_thread();
+        crate::schedule::schedule(self, Instant::now() + delay)
+    }
+
+    fn deliver(&mut self) -> Result<(), crate::Error> {
+        #[cfg(feature = "external-tools")]
+        if self.osascript {
+            crate::instrument::notification_sent(|| self.info());
+            return crate::external::notify(&self.title, &self.msg);
+        }
+        if self.raw.is_null() {
+            return Ok(());
+        }
+        crate::instrument::notification_sent(|| self.info());
+        /* NvDialog's error state is sticky, so only a change means this call failed. */
+        let before = unsafe { nvd_get_error() };
+        unsafe { nvd_send_notification(self.raw) };
+        let after = unsafe { nvd_get_error() };
+        if after == before || crate::Error::from(after as i32) == crate::Error::NoError {
+            return Ok(());
+        }
+        Err(crate::Error::last_native().context("Failed to send notification"))
+    }
+}
+
+impl Object for Notification {
+    type NativeType = NvdNotification;
+    type ReturnValue = ();
+
+    fn get_raw(&self) -> *mut NvdNotification {
+        crate::ffi_check::check(self.raw);
+        self.raw
+    }
+
+    fn show(&mut self) -> Result<(), crate::Error> {
+        self.send()
+    }
+
+    fn free(&mut self) {
+        /* Notifications have their own destructor, so they can't go through `free_raw`. */
+        let raw = std::mem::replace(&mut self.raw, std::ptr::null_mut());
+        if !raw.is_null() {
+            crate::ffi_check::release(raw);
+            unsafe { nvd_delete_notification(raw) };
+        }
     }
 }
 
 impl Drop for Notification {
     fn drop(&mut self) {
-        unsafe { nvd_delete_notification(self.raw) };
+        self.free();
+    }
+}
+
+/// A queue of notifications that are sent together as a single, coalesced notification.
+///
+/// Bulk operations (copying files, syncing messages...) can end up sending dozens of
+/// notifications at once. Pushing them into a `NotificationGroup` instead and calling
+/// [`NotificationGroup::send`] shows one notification summarizing all of them, so the desktop
+/// isn't flooded with popups.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{NotificationGroup, NotificationKind};
+///
+/// let mut group = NotificationGroup::new("Downloads");
+/// group.push("song.ogg", "Download finished", NotificationKind::Simple);
+/// group.push("video.mkv", "Download failed", NotificationKind::Error);
+/// group.send().expect("Failed to send notifications");
+/// ```
+pub struct NotificationGroup {
+    title: String,
+    pending: Vec<(String, String, NotificationKind)>,
+}
+
+impl NotificationGroup {
+    /// Creates an empty group. `title` is used as the title of the coalesced notification.
+    pub fn new<S: AsRef<str>>(title: S) -> Self {
+        Self {
+            title: String::from(title.as_ref()),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Queues a notification to be sent with the rest of the group.
+    pub fn push<S: AsRef<str>>(&mut self, title: S, msg: S, kind: NotificationKind) {
+        self.pending
+            .push((String::from(title.as_ref()), String::from(msg.as_ref()), kind));
+    }
+
+    /// Returns the number of queued notifications.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns `true` if no notifications are queued.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Sends the queued notifications and empties the group.
+    ///
+    /// A single queued notification is sent unchanged. Several are coalesced into one
+    /// notification titled after the group, listing each of them on its own line and using the
+    /// most severe [`NotificationKind`] among them. Nothing is sent if the group is empty.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`Notification::new`] and [`Notification::send`].
+    pub fn send(&mut self) -> Result<(), crate::Error> {
+        let mut pending = std::mem::take(&mut self.pending);
+        let mut notification = match pending.len() {
+            0 => return Ok(()),
+            1 => {
+                let (title, msg, kind) = pending.remove(0);
+                Notification::new(title, msg, kind)?
+            }
+            _ => {
+                let kind = pending
+                    .iter()
+                    .map(|(_, _, kind)| kind.clone())
+                    .max()
+                    .unwrap_or(NotificationKind::Simple);
+                let body = pending
+                    .iter()
+                    .map(|(title, msg, _)| format!("{title}: {msg}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Notification::new(self.title.clone(), body, kind)?
+            }
+        };
+        notification.send()
     }
 }
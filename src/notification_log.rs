@@ -0,0 +1,213 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{Error, NotificationKind};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+/// What became of a notification recorded in a [`NotificationLog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// The notification was handed to the desktop.
+    Delivered,
+    /// Sending the notification failed with this error.
+    Failed(Error),
+    /// The notification was dropped by the limit set with
+    /// [`set_notification_rate_limit`](crate::set_notification_rate_limit).
+    RateLimited,
+}
+
+/// A notification sent while a [`NotificationLog`] was installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationRecord {
+    /// Identifies the record within its log, in the order notifications were sent.
+    pub id: u64,
+    pub sent_at: SystemTime,
+    pub title: String,
+    pub message: String,
+    pub kind: NotificationKind,
+    pub status: DeliveryStatus,
+    /// The action the user took, as reported with [`NotificationLog::record_action`].
+    pub action: Option<String>,
+}
+
+struct Inner {
+    capacity: usize,
+    next_id: u64,
+    records: VecDeque<NotificationRecord>,
+}
+
+static INSTALLED: Mutex<Option<NotificationLog>> = Mutex::new(None);
+
+/// An in-process history of the notifications sent, for applications that show their own
+/// notification center to users who missed a popup.
+///
+/// Nothing is recorded until a log is installed with [`NotificationLog::install`]. The log is a
+/// cheap handle: keep a clone around to query it while it records. Once `capacity` records are
+/// stored, the oldest ones are forgotten.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DeliveryStatus, NotificationLog};
+///
+/// let log = NotificationLog::new(100);
+/// log.install();
+/// // Send notifications...
+/// for record in log.records() {
+///     let failed = matches!(record.status, DeliveryStatus::Failed(_));
+///     println!("{}: {} (failed: {failed})", record.title, record.message);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct NotificationLog {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl NotificationLog {
+    /// Creates an empty log keeping the last `capacity` notifications.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                capacity,
+                next_id: 0,
+                records: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Records every notification sent from now on, from any thread, into this log, replacing
+    /// the log installed before, if any.
+    pub fn install(&self) {
+        *INSTALLED.lock().unwrap_or_else(|e| e.into_inner()) = Some(self.clone());
+    }
+
+    /// Stops recording notifications. The records of the log that was installed are kept.
+    pub fn uninstall() {
+        *INSTALLED.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn inner(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns a copy of the records, oldest first.
+    pub fn records(&self) -> Vec<NotificationRecord> {
+        self.inner().records.iter().cloned().collect()
+    }
+
+    /// Returns a copy of the records for which `filter` returns `true`, oldest first.
+    ///
+    /// # Examples
+    /// ```
+    /// use nvdialog_rs::{NotificationKind, NotificationLog};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let log = NotificationLog::new(100);
+    /// let hour_ago = SystemTime::now() - Duration::from_secs(3600);
+    /// let recent_errors =
+    ///     log.query(|record| record.kind == NotificationKind::Error && record.sent_at > hour_ago);
+    /// ```
+    pub fn query<F: FnMut(&NotificationRecord) -> bool>(
+        &self,
+        mut filter: F,
+    ) -> Vec<NotificationRecord> {
+        self.inner()
+            .records
+            .iter()
+            .filter(|record| filter(record))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the record with the given `id`, if the log still has it.
+    pub fn get(&self, id: u64) -> Option<NotificationRecord> {
+        self.inner()
+            .records
+            .iter()
+            .find(|record| record.id == id)
+            .cloned()
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.inner().records.len()
+    }
+
+    /// Returns `true` if the log has no records.
+    pub fn is_empty(&self) -> bool {
+        self.inner().records.is_empty()
+    }
+
+    /// Notes that the user took `action` on the notification recorded as `id`, for example after
+    /// the value set with [`Notification::add_action`](crate::Notification::add_action) was
+    /// written. Returns `false` if the log no longer has the record.
+    pub fn record_action<S: AsRef<str>>(&self, id: u64, action: S) -> bool {
+        let mut inner = self.inner();
+        match inner.records.iter_mut().find(|record| record.id == id) {
+            Some(record) => {
+                record.action = Some(String::from(action.as_ref()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forgets every record.
+    pub fn clear(&self) {
+        self.inner().records.clear();
+    }
+
+    fn push(&self, title: &str, message: &str, kind: &NotificationKind, status: DeliveryStatus) {
+        let mut inner = self.inner();
+        if inner.capacity == 0 {
+            return;
+        }
+        while inner.records.len() >= inner.capacity {
+            inner.records.pop_front();
+        }
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.records.push_back(NotificationRecord {
+            id,
+            sent_at: SystemTime::now(),
+            title: String::from(title),
+            message: String::from(message),
+            kind: kind.clone(),
+            status,
+            action: None,
+        });
+    }
+}
+
+/// Called by [`Notification::send`](crate::Notification::send): records a notification in the
+/// installed log, if any.
+pub(crate) fn record(title: &str, message: &str, kind: &NotificationKind, status: DeliveryStatus) {
+    let installed = INSTALLED.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(log) = installed {
+        log.push(title, message, kind, status);
+    }
+}
@@ -0,0 +1,174 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::Error;
+
+/// The operations every dialog of the crate supports, for code that works with any of them.
+///
+/// Each dialog type also has its own methods for these, which are usually more convenient when
+/// the type is known: `show` corresponds to [`DialogBox::show`](crate::DialogBox::show),
+/// [`QuestionDialog::get_reply`](crate::QuestionDialog::get_reply),
+/// [`FileDialog::retrieve_filename`](crate::FileDialog::retrieve_filename) and so on.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DialogBox, DialogType, Error, Object};
+///
+/// fn show_twice<O: Object>(dialog: &mut O) -> Result<(O::ReturnValue, O::ReturnValue), Error> {
+///     Ok((dialog.show()?, dialog.show()?))
+/// }
+///
+/// let mut dialog = DialogBox::new("Title", "Message", DialogType::Simple)?;
+/// show_twice(&mut dialog)?;
+/// # Ok::<(), Error>(())
+/// ```
+pub trait Object {
+    /// The NvDialog type behind the dialog, eg. `NvdDialogBox` for
+    /// [`DialogBox`](crate::DialogBox).
+    type NativeType;
+    /// What showing the dialog returns: the reply to a question, the chosen file...
+    type ReturnValue;
+
+    /// Returns the NvDialog object behind the dialog, or a null pointer if there is none (for
+    /// example when a fallback backend is active, or after [`Object::free`]).
+    fn get_raw(&self) -> *mut Self::NativeType;
+
+    /// Shows the dialog, blocking until it is closed, and returns its result.
+    ///
+    /// # Errors
+    /// Returns an error if the dialog couldn't be shown. Dialogs whose own methods can't fail
+    /// never return one.
+    fn show(&mut self) -> Result<Self::ReturnValue, Error>;
+
+    /// Frees the NvDialog object behind the dialog before it is dropped, releasing its native
+    /// resources early. A freed dialog isn't displayed natively anymore, and calling this twice
+    /// is harmless.
+    fn free(&mut self);
+}
+
+/// Shows each of `dialogs` in order and returns their results, stopping at the first dialog
+/// that fails to show.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{QuestionDialog, QuestionDialogButtons};
+///
+/// let mut questions = [
+///     QuestionDialog::new("Setup", "Enable telemetry?", QuestionDialogButtons::YesNo),
+///     QuestionDialog::new("Setup", "Check for updates?", QuestionDialogButtons::YesNo),
+/// ];
+/// let replies = nvdialog_rs::show_all(&mut questions)?;
+/// # Ok::<(), nvdialog_rs::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns the error of the first dialog whose [`Object::show`] failed. The dialogs after it
+/// aren't shown.
+pub fn show_all<O: Object>(dialogs: &mut [O]) -> Result<Vec<O::ReturnValue>, Error> {
+    dialogs.iter_mut().map(Object::show).collect()
+}
+
+/// Frees the NvDialog objects behind each of `dialogs`. See [`Object::free`].
+pub fn free_all<O: Object>(dialogs: &mut [O]) {
+    dialogs.iter_mut().for_each(Object::free);
+}
+
+/// An ordered collection of dialogs of the same type, shown one after the other.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::{DialogBox, DialogType, Dialogs};
+///
+/// let mut tips = Dialogs::new();
+/// tips.push(DialogBox::new("Tip 1/2", "Press F1 for help.", DialogType::Simple)?);
+/// tips.push(DialogBox::new("Tip 2/2", "Press Ctrl+Q to quit.", DialogType::Simple)?);
+/// tips.show_all()?;
+/// # Ok::<(), nvdialog_rs::Error>(())
+/// ```
+pub struct Dialogs<O: Object> {
+    dialogs: Vec<O>,
+}
+
+impl<O: Object> Dialogs<O> {
+    /// Creates an empty collection.
+    pub fn new() -> Self {
+        Self {
+            dialogs: Vec::new(),
+        }
+    }
+
+    /// Appends `dialog` to the collection.
+    pub fn push(&mut self, dialog: O) {
+        self.dialogs.push(dialog);
+    }
+
+    /// Returns the number of dialogs in the collection.
+    pub fn len(&self) -> usize {
+        self.dialogs.len()
+    }
+
+    /// Returns `true` if the collection holds no dialogs.
+    pub fn is_empty(&self) -> bool {
+        self.dialogs.is_empty()
+    }
+
+    /// Shows the dialogs in the order they were pushed and returns their results, in the same
+    /// order. The dialogs are kept, so the collection can be shown again.
+    ///
+    /// # Errors
+    /// Same as [`show_all`].
+    pub fn show_all(&mut self) -> Result<Vec<O::ReturnValue>, Error> {
+        show_all(&mut self.dialogs)
+    }
+
+    /// Frees the NvDialog objects behind every dialog of the collection. See [`Object::free`].
+    pub fn free_all(&mut self) {
+        free_all(&mut self.dialogs);
+    }
+
+    /// Returns the dialogs of the collection.
+    pub fn into_inner(self) -> Vec<O> {
+        self.dialogs
+    }
+}
+
+impl<O: Object> Default for Dialogs<O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<O: Object> FromIterator<O> for Dialogs<O> {
+    fn from_iter<I: IntoIterator<Item = O>>(iter: I) -> Self {
+        Self {
+            dialogs: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<O: Object> Extend<O> for Dialogs<O> {
+    fn extend<I: IntoIterator<Item = O>>(&mut self, iter: I) {
+        self.dialogs.extend(iter);
+    }
+}
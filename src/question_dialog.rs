@@ -22,9 +22,18 @@
  * IN THE SOFTWARE.
  */
 
-use crate::c_string;
+use crate::{
+    util::{free_raw, with_owner, with_truncated_c_strings},
+    Backend, ButtonLayout, CancelToken, DefaultAnswer, DeferredDialog, DialogInfo, DialogKind,
+    Error, Object, QuestionSpec,
+};
 use nvdialog_sys::ffi::*;
-use std::ffi::{c_uint, c_void};
+#[cfg(target_os = "linux")]
+use std::ffi::c_int;
+use std::{
+    ffi::{c_uint, c_void},
+    time::Duration,
+};
 
 /// # Possible button combination for question dialogs.
 /// This enum contains the button combination for a question
@@ -52,6 +61,8 @@ use std::ffi::{c_uint, c_void};
 /// - `YesNo`: Corresponds to `NVD_YES_NO`.
 /// - `YesNoCancel`: Corresponds to `NVD_YES_NO_CANCEL`.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum QuestionDialogButtons {
     Yes = 0x04,
     YesNo,
@@ -87,6 +98,13 @@ pub enum QuestionDialogButtons {
 ///     }
 /// }
 /// ```
+/// ## Lifecycle
+/// [`QuestionDialog::new`] creates the native dialog without showing it, so dialogs can be
+/// prepared ahead of time. [`QuestionDialog::get_reply`] then shows it and blocks until it is
+/// answered. Applications with their own event or render loop can call
+/// [`QuestionDialog::show_nonblocking`] instead, which hands the dialog to [`crate::pump_events`]
+/// and returns a [`PendingReply`] to poll with [`PendingReply::try_reply`].
+///
 /// ## Safety
 /// This function converts the C enum for the reply (See
 /// [`NvdReply`](https://github.com/tseli0s/nvdialog/blob/master/include))
@@ -102,10 +120,21 @@ pub struct QuestionDialog {
     title: String,
     msg: String,
     buttons: QuestionDialogButtons,
+    owner: *mut c_void,
+    suppressed: bool,
+    default_answer: Option<DefaultAnswer>,
+    remember_as: Option<String>,
+    button_layout: Option<ButtonLayout>,
+    cancel: Option<CancelToken>,
+    /// Labels replacing those of the buttons, see [`QuestionDialog::relabel`].
+    labels: Vec<(Reply, String)>,
+    default_button: Option<Reply>,
 }
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 /// An enum that holds all possible replies from a dialog.
 /// Can be converted from a `u32` if needed.
 /// # Example
@@ -129,6 +158,13 @@ pub struct QuestionDialog {
 /// - `Accepted` -> Corresponds to `NVD_REPLY_OK`, returned if user pressed the Okay button.
 /// - `Cancelled` -> Corresponds to `NVD_REPLY_CANCEL`, returned if user pressed the Cancel button.
 /// - `Rejected` -> Corresponds to `NVD_REPLY_NO`, returned if user pressed the No button.
+/// - `Dismissed` -> Has no NvDialog equivalent. Returned when the dialog was closed without
+///   pressing any button (e.g. with the window's close button or Escape), as opposed to the user
+///   explicitly pressing Cancel. None of NvDialog's current backends report this case separately,
+///   so closing a native dialog still yields `Cancelled`; `Dismissed` is produced by the crate's own
+///   reply sources (such as the `auto-reply` and `recorder` features), by a [`CancelToken`]
+///   closing the dialog, and by backends that will support it. Applications should treat it like
+///   `Cancelled` unless they need the distinction.
 /// # Errors
 /// In order to work with raw C integers, a conversion is done (See the `From` trait for details). If the
 /// integer given is not valid though, then the return value will always be `NVD_REPLY_CANCEL` to comply
@@ -137,6 +173,7 @@ pub enum Reply {
     Accepted = 0x04,
     Cancelled,
     Rejected,
+    Dismissed,
 }
 
 impl QuestionDialog {
@@ -148,6 +185,13 @@ impl QuestionDialog {
     /// * `msg` - A string slice or reference that contains the message to display in the dialog box.
     /// * `buttons` - A `QuestionDialogButtons` enum that specifies the buttons to display in the dialog box.
     ///
+    /// If [`crate::init`] hasn't been called in this thread, no native dialog is created and
+    /// [`QuestionDialog::get_reply`] returns the [`DefaultAnswer`] without showing anything.
+    /// If `title` or `msg` contain a null byte, only the text before it is shown.
+    ///
+    /// The dialog goes through the interceptor registered with
+    /// [`set_dialog_interceptor`](crate::set_dialog_interceptor) first, if any.
+    ///
     /// # Examples
     ///
     /// ```
@@ -159,24 +203,192 @@ impl QuestionDialog {
     /// ```
 
     pub fn new<S: AsRef<str>>(title: S, msg: S, buttons: QuestionDialogButtons) -> Self {
-        let t = c_string!(title.as_ref());
-        let q = c_string!(msg.as_ref());
-        Self {
-            raw: unsafe {
-                nvd_dialog_question_new(
-                    t.as_ptr(),
-                    q.as_ptr(),
-                    buttons.clone() as c_uint
-                )
-            },
+        crate::init::check_thread();
+        let (spec, suppressed) = crate::intercept::intercept(QuestionSpec {
             title: String::from(title.as_ref()),
-            msg: String::from(msg.as_ref()),
+            message: String::from(msg.as_ref()),
+            buttons,
+        });
+        let mut dialog = Self {
+            raw: std::ptr::null_mut(),
+            title: spec.title,
+            msg: spec.message,
+            buttons: spec.buttons,
+            owner: std::ptr::null_mut(),
+            suppressed,
+            default_answer: None,
+            remember_as: None,
+            button_layout: None,
+            cancel: None,
+            labels: Vec::new(),
+            default_button: None,
+        };
+
+        #[cfg(feature = "auto-reply")]
+        if crate::auto_reply_enabled() {
+            return dialog;
+        }
+
+        if !suppressed && crate::init::native_available() {
+            dialog.raw = with_truncated_c_strings(
+                [dialog.title.as_str(), dialog.msg.as_str()],
+                |[t, q]| unsafe { nvd_dialog_question_new(t, q, dialog.buttons.clone() as c_uint) },
+            );
+            crate::ffi_check::track(dialog.raw);
+        }
+        dialog
+    }
+
+    /// Sets the reply this question resolves to when it is answered without the user, in place
+    /// of the one set with [`crate::set_default_answer`]. See [`DefaultAnswer`].
+    pub fn default_answer(mut self, answer: DefaultAnswer) -> Self {
+        self.default_answer = Some(answer);
+        self
+    }
+
+    /// Lays the buttons of this question out following `layout`, in place of the one set with
+    /// [`crate::set_button_layout`]. See [`ButtonLayout`].
+    pub fn button_layout(mut self, layout: ButtonLayout) -> Self {
+        self.button_layout = Some(layout);
+        self
+    }
+
+    /// Lets `token` close this question while it is shown, in which case it returns
+    /// [`Reply::Dismissed`]. See [`CancelToken`].
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Makes this a "don't ask again" question: once the user answered it with
+    /// [`Reply::Accepted`] or [`Reply::Rejected`], the answer is kept under `key` in the store
+    /// registered with [`crate::set_choice_store`], and later questions with the same `key`
+    /// return it without being shown.
+    ///
+    /// NvDialog's dialogs can't show a "Don't ask again" checkbox, so say in the message that
+    /// the answer will be remembered, and offer a way to ask again with [`crate::forget_choice`].
+    /// Questions answered without the user (see [`DefaultAnswer`]) are not remembered.
+    pub fn remember_choice<S: Into<String>>(mut self, key: S) -> Self {
+        self.remember_as = Some(key.into());
+        self
+    }
+
+    /// Creates a new `QuestionDialog` whose title and question are resolved from message keys
+    /// through the registered [`Localizer`](crate::Localizer), with `args` substituted in.
+    ///
+    /// Keys without a translation are shown as-is. See [`crate::localize`] for details.
+    #[cfg(feature = "i18n")]
+    pub fn new_localized(
+        title_key: &str,
+        msg_key: &str,
+        args: &[(&str, &str)],
+        buttons: QuestionDialogButtons,
+    ) -> Self {
+        Self::new(
+            crate::localize(title_key, args),
+            crate::localize(msg_key, args),
             buttons,
+        )
+    }
+
+    /// Gives the buttons replying `labels` their label, in this crate's mnemonic syntax (see
+    /// [`DialogBox::set_accept_label`](crate::DialogBox::set_accept_label)), and makes the button
+    /// replying `default` the one Enter picks.
+    ///
+    /// # Errors
+    /// Returns [`Error::Unsupported`] if the buttons of this dialog can't be relabelled, which is
+    /// only possible with GTK, on Windows and with the mock backend, and
+    /// [`Error::ParametersError`] if a label contains a null byte.
+    pub(crate) fn relabel(
+        mut self,
+        labels: Vec<(Reply, String)>,
+        default: Reply,
+    ) -> Result<Self, Error> {
+        if labels.iter().any(|(_, label)| label.contains('\0')) {
+            return Err(Error::ParametersError.context("Button label contains a null byte"));
+        }
+        let supported = match crate::current_backend() {
+            /* With GTK, the buttons are changed through the dialog's widget. */
+            Some(Backend::Native) if cfg!(target_os = "linux") => {
+                self.raw.is_null() || !unsafe { self.native_widget() }.is_null()
+            }
+            Some(Backend::Native) => cfg!(target_os = "windows"),
+            Some(Backend::Mock) | None => true,
+            Some(_) => false,
+        };
+        if !supported {
+            return Err(Error::Unsupported);
+        }
+        self.labels = labels;
+        self.default_button = Some(default);
+        Ok(self)
+    }
+
+    /// Makes the window with the handle `hwnd` the owner of this dialog, so that it stays on top
+    /// of the application's window and doesn't get its own taskbar entry. Pass a null pointer to
+    /// go back to NvDialog's global parent window.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_set_parent`, which is called right before the dialog is shown and
+    /// undone afterwards.
+    #[cfg(target_os = "windows")]
+    pub fn set_owner_hwnd(&mut self, hwnd: *mut c_void) {
+        self.owner = hwnd;
+    }
+
+    /// Returns the toolkit's own object behind this dialog: a `GtkWidget*` with GTK, an `HWND`
+    /// on Windows or an `NSWindow*` on macOS. Returns a null pointer if no native dialog was
+    /// created, or if the backend doesn't create the window before it is shown.
+    ///
+    /// This is unsupported territory, meant for tweaking properties this crate has no API for.
+    ///
+    /// # Safety
+    /// The pointer is owned by NvDialog and invalid once the dialog is dropped. Changing the
+    /// object in ways NvDialog doesn't expect (destroying it, replacing its contents...) is
+    /// undefined behavior.
+    ///
+    /// # FFI
+    /// Corresponds to `nvd_dialog_question_get_raw`.
+    pub unsafe fn native_widget(&self) -> *mut c_void {
+        if self.raw.is_null() {
+            return std::ptr::null_mut();
+        }
+        crate::ffi_check::check(self.raw);
+        nvd_dialog_question_get_raw(self.raw)
+    }
+
+    /// Returns the title of the dialog.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the question displayed in the dialog.
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    /// Returns the kind of the dialog, which is always [`DialogKind::Question`].
+    pub fn kind(&self) -> DialogKind {
+        DialogKind::Question(self.buttons.clone())
+    }
+
+    /// Returns a [`DialogInfo`] describing what the dialog is going to display.
+    pub fn info(&self) -> DialogInfo {
+        DialogInfo {
+            kind: self.kind(),
+            title: self.title.clone(),
+            message: self.msg.clone(),
         }
     }
 
     /// Returns the user's reply to the question displayed in the dialog box.
     ///
+    /// If the dialog was suppressed by the interceptor registered with
+    /// [`set_dialog_interceptor`](crate::set_dialog_interceptor) or as a duplicate (see
+    /// [`set_dedupe_window`](crate::set_dedupe_window)), nothing is shown and the answer
+    /// remembered for it (see [`QuestionDialog::remember_choice`]) is returned, or else its
+    /// [`DefaultAnswer`]. [`Reply::Dismissed`] is only returned when the dialog was closed.
+    ///
     /// # Examples
     /// ```
     /// let question_dialog = QuestionDialog::new(
@@ -192,7 +404,160 @@ impl QuestionDialog {
     /// }
     /// ```
     pub fn get_reply(&mut self) -> Reply {
-        Reply::from(unsafe { nvd_get_reply(self.raw) })
+        crate::init::check_thread();
+        if let Some(reply) = self.remember_as.as_deref().and_then(crate::choice::recall) {
+            return reply;
+        }
+        if self.suppressed {
+            return self.default_reply();
+        }
+        let reply = crate::instrument::instrument(
+            || self.info(),
+            || match &self.cancel {
+                Some(token) => {
+                    let showing = crate::cancel::showing(unsafe { self.native_widget() });
+                    token
+                        .run(showing, || self.fetch_reply())
+                        .unwrap_or(Reply::Dismissed)
+                }
+                None => self.fetch_reply(),
+            },
+            |reply| Some(crate::Decision::Reply(*reply)),
+        );
+        crate::audit::log_decision(|| crate::DialogEvent {
+            dialog: self.info(),
+            decision: crate::Decision::Reply(reply),
+        });
+        #[cfg(feature = "recorder")]
+        crate::recorder::record(
+            "question",
+            &self.title,
+            &self.msg,
+            Some(crate::recorder::reply_name(reply)),
+        );
+        reply
+    }
+
+    /// Queues the dialog to be shown by the next call to [`crate::pump_events`] in this thread,
+    /// instead of showing it right away. The reply is collected through the returned
+    /// [`PendingReply`].
+    ///
+    /// Native dialogs are modal, so the call to [`crate::pump_events`] that shows the dialog
+    /// still waits for it to be answered.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nvdialog_rs::{QuestionDialog, QuestionDialogButtons};
+    /// use std::time::Duration;
+    ///
+    /// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+    /// let pending = QuestionDialog::new("Quit", "Save before quitting?", QuestionDialogButtons::YesNo)
+    ///     .show_nonblocking();
+    ///
+    /// loop {
+    ///     // Update and render the frame...
+    ///     nvdialog_rs::pump_events(Duration::from_millis(2));
+    ///     if let Some(reply) = pending.try_reply() {
+    ///         println!("Reply: {reply:?}");
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    pub fn show_nonblocking(mut self) -> PendingReply {
+        PendingReply {
+            deferred: DeferredDialog::new(move || self.get_reply()),
+        }
+    }
+
+    /// Returns the reply given when nobody can answer, see [`DefaultAnswer`].
+    fn default_reply(&self) -> Reply {
+        let DefaultAnswer(default) = self
+            .default_answer
+            .unwrap_or_else(crate::default_answer::default_answer);
+        default
+    }
+
+    fn fetch_reply(&self) -> Reply {
+        #[cfg(feature = "recorder")]
+        if let Some(reply) = crate::recorder::replay_reply() {
+            return reply;
+        }
+        let default = self.default_reply();
+        #[cfg(feature = "auto-reply")]
+        if let Some(reply) = crate::auto_reply::next_reply() {
+            return reply.unwrap_or(default);
+        }
+        #[cfg(feature = "noop")]
+        if crate::backend::fallback().is_none() {
+            return default;
+        }
+
+        let layout = self
+            .button_layout
+            .unwrap_or_else(crate::button_layout::button_layout);
+        let reply = if self.raw.is_null() {
+            match crate::backend::fallback() {
+                Some(backend) => {
+                    let labels: Vec<(Reply, &str)> = self
+                        .labels
+                        .iter()
+                        .map(|(reply, label)| (*reply, label.as_str()))
+                        .collect();
+                    backend.question(&self.title, &self.msg, &self.buttons, layout, &labels)
+                }
+                None => return default,
+            }
+        } else {
+            #[cfg(target_os = "linux")]
+            unsafe {
+                crate::button_layout::apply_gtk(self.native_widget(), layout);
+                if let Some(default) = self.default_button {
+                    /* `relabel` rejected labels with a null byte. */
+                    use std::ffi::CString;
+
+                    let labels: Vec<(Reply, CString)> = self
+                        .labels
+                        .iter()
+                        .filter_map(|(reply, label)| {
+                            Some((*reply, CString::new(label.as_str()).ok()?))
+                        })
+                        .collect();
+                    let labels: Vec<_> = labels
+                        .iter()
+                        .map(|(reply, label)| (*reply, label.as_c_str()))
+                        .collect();
+                    relabel_gtk_buttons(self.native_widget(), &labels, default);
+                }
+            }
+            let show = || unsafe { nvd_get_reply(self.raw) };
+            /* The message box only exists once it is shown. */
+            #[cfg(target_os = "windows")]
+            let show = || {
+                let labels: Vec<(Reply, Vec<u16>)> = self
+                    .labels
+                    .iter()
+                    .map(|(reply, label)| {
+                        let label = crate::util::native_mnemonic(label);
+                        (*reply, label.encode_utf16().chain([0]).collect())
+                    })
+                    .collect();
+                let default = self.default_button;
+                crate::win_hook::on_dialog(
+                    move |dialog| unsafe {
+                        crate::button_layout::apply_win32(dialog, layout);
+                        if let Some(default) = default {
+                            relabel_win32_buttons(dialog, &labels, default);
+                        }
+                    },
+                    show,
+                )
+            };
+            Reply::from(with_owner(self.owner, show))
+        };
+        if let Some(key) = &self.remember_as {
+            crate::choice::remember(key, reply);
+        }
+        reply
     }
 }
 
@@ -210,10 +575,182 @@ impl From<u32> for Reply {
     }
 }
 
+impl Object for QuestionDialog {
+    type NativeType = NvdQuestionBox;
+    type ReturnValue = Reply;
+
+    fn get_raw(&self) -> *mut NvdQuestionBox {
+        crate::ffi_check::check(self.raw);
+        self.raw
+    }
+
+    fn show(&mut self) -> Result<Reply, Error> {
+        Ok(self.get_reply())
+    }
+
+    fn free(&mut self) {
+        unsafe { free_raw(std::mem::replace(&mut self.raw, std::ptr::null_mut())) };
+    }
+}
+
+/// The reply to a [`QuestionDialog`] queued with [`QuestionDialog::show_nonblocking`].
+///
+/// Dropping it before the dialog was shown cancels the dialog.
+pub struct PendingReply {
+    deferred: DeferredDialog<Reply>,
+}
+
+impl PendingReply {
+    /// Returns the reply if the dialog has been answered, or `None` if it is still waiting for
+    /// [`crate::pump_events`] to show it. The reply is only returned once.
+    pub fn try_reply(&self) -> Option<Reply> {
+        self.deferred.take()
+    }
+
+    /// Shows the dialog now, along with the dialogs queued before it, and returns its reply.
+    ///
+    /// Returns [`Reply::Cancelled`] if the dialog was queued in another thread, where this one
+    /// can't show it.
+    pub fn wait_reply(self) -> Reply {
+        loop {
+            if let Some(reply) = self.deferred.take() {
+                return reply;
+            }
+            /* Nothing left to show: the dialog isn't in this thread's queue. */
+            if crate::pump_events(Duration::ZERO) == 0 && !self.deferred.is_ready() {
+                return Reply::Cancelled;
+            }
+        }
+    }
+}
+
 impl Drop for QuestionDialog {
     fn drop(&mut self) {
-        unsafe {
-            nvd_free_object(self.raw as *mut c_void);
+        unsafe { free_raw(self.raw) };
+    }
+}
+
+/// Returns the `GtkButton` giving `reply` in the GTK question dialog `dialog`, along with its
+/// response ID, or a null pointer if it has none.
+///
+/// The response IDs NvDialog uses depend on its version, so every ID meaning `reply` is tried.
+#[cfg(target_os = "linux")]
+pub(crate) unsafe fn gtk_button_for(dialog: *mut c_void, reply: Reply) -> (*mut c_void, c_int) {
+    crate::gtk_link::gtk_extern! {
+        fn gtk_dialog_get_widget_for_response(dialog: *mut c_void, response: c_int) -> *mut c_void;
+    }
+
+    const GTK_RESPONSE_REJECT: c_int = -2;
+    const GTK_RESPONSE_ACCEPT: c_int = -3;
+    const GTK_RESPONSE_OK: c_int = -5;
+    const GTK_RESPONSE_CANCEL: c_int = -6;
+    const GTK_RESPONSE_YES: c_int = -8;
+    const GTK_RESPONSE_NO: c_int = -9;
+
+    let responses: &[c_int] = match reply {
+        Reply::Accepted => &[GTK_RESPONSE_YES, GTK_RESPONSE_OK, GTK_RESPONSE_ACCEPT],
+        Reply::Rejected => &[GTK_RESPONSE_NO, GTK_RESPONSE_REJECT],
+        Reply::Cancelled | Reply::Dismissed => &[GTK_RESPONSE_CANCEL],
+    };
+    for &response in responses {
+        let button = gtk_dialog_get_widget_for_response(dialog, response);
+        if !button.is_null() {
+            return (button, response);
+        }
+    }
+    (std::ptr::null_mut(), 0)
+}
+
+/// Gives the buttons of the GTK question dialog `dialog` the labels in `labels`, using GTK's
+/// mnemonic syntax, and makes the button giving `default` the one Enter picks.
+#[cfg(target_os = "linux")]
+pub(crate) unsafe fn relabel_gtk_buttons(
+    dialog: *mut c_void,
+    labels: &[(Reply, &std::ffi::CStr)],
+    default: Reply,
+) {
+    use std::ffi::c_char;
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_button_set_label(button: *mut c_void, label: *const c_char);
+        fn gtk_button_set_use_underline(button: *mut c_void, use_underline: c_int);
+        fn gtk_dialog_set_default_response(dialog: *mut c_void, response: c_int);
+    }
+
+    if dialog.is_null() {
+        return;
+    }
+    for &(reply, label) in labels {
+        let (button, response) = gtk_button_for(dialog, reply);
+        if button.is_null() {
+            continue;
+        }
+        gtk_button_set_label(button, label.as_ptr());
+        gtk_button_set_use_underline(button, 1);
+        if reply == default {
+            gtk_dialog_set_default_response(dialog, response);
+        }
+    }
+}
+
+/// Returns the button giving `reply` in the Windows message box `dialog`, along with its ID, or a
+/// null pointer if it has none.
+#[cfg(target_os = "windows")]
+pub(crate) unsafe fn win32_button_for(dialog: *mut c_void, reply: Reply) -> (*mut c_void, i32) {
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetDlgItem(dialog: *mut c_void, id: i32) -> *mut c_void;
+    }
+
+    const IDOK: i32 = 1;
+    const IDCANCEL: i32 = 2;
+    const IDYES: i32 = 6;
+    const IDNO: i32 = 7;
+
+    let ids: &[i32] = match reply {
+        Reply::Accepted => &[IDYES, IDOK],
+        Reply::Rejected => &[IDNO],
+        Reply::Cancelled | Reply::Dismissed => &[IDCANCEL],
+    };
+    for &id in ids {
+        let button = GetDlgItem(dialog, id);
+        if !button.is_null() {
+            return (button, id);
         }
     }
+    (std::ptr::null_mut(), 0)
+}
+
+/// Gives the buttons of the Windows message box `dialog` the null-terminated labels in `labels`,
+/// and makes the button giving `default` the one Enter picks.
+///
+/// The buttons keep their size, so longer labels are cut.
+#[cfg(target_os = "windows")]
+pub(crate) unsafe fn relabel_win32_buttons(
+    dialog: *mut c_void,
+    labels: &[(Reply, Vec<u16>)],
+    default: Reply,
+) {
+    #[link(name = "user32")]
+    extern "system" {
+        fn SetWindowTextW(hwnd: *mut c_void, text: *const u16) -> i32;
+        fn SendMessageW(hwnd: *mut c_void, msg: u32, wparam: usize, lparam: isize) -> isize;
+        fn PostMessageW(hwnd: *mut c_void, msg: u32, wparam: usize, lparam: isize) -> i32;
+    }
+
+    const DM_SETDEFID: u32 = 0x0401;
+    const WM_NEXTDLGCTL: u32 = 0x0028;
+
+    for (reply, label) in labels {
+        let (button, _) = win32_button_for(dialog, *reply);
+        if !button.is_null() {
+            SetWindowTextW(button, label.as_ptr());
+        }
+    }
+    let (button, id) = win32_button_for(dialog, default);
+    if !button.is_null() {
+        SendMessageW(dialog, DM_SETDEFID, id as usize, 0);
+        /* Focus follows once the message box is up, as it focuses its own default first. */
+        PostMessageW(dialog, WM_NEXTDLGCTL, button as usize, 1);
+    }
 }
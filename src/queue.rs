@@ -0,0 +1,245 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    sync::mpsc::{channel, Receiver, Sender, SyncSender},
+};
+
+/// The sending half of a channel [`DialogQueue::push_with_sender`] can deliver replies through.
+///
+/// Implemented for the senders of `std::sync::mpsc`, and with the `crossbeam` and `flume`
+/// features for the senders of those crates, so replies can go straight into the channels an
+/// application already uses. Implement it for other channel types as needed.
+pub trait ReplySender<T> {
+    /// Sends `reply`. Errors, such as the receiver having been dropped, are ignored: nobody is
+    /// waiting for the reply anymore.
+    fn send_reply(&self, reply: T);
+}
+
+impl<T> ReplySender<T> for Sender<T> {
+    fn send_reply(&self, reply: T) {
+        let _ = self.send(reply);
+    }
+}
+
+/// Blocks if the channel is full, like [`SyncSender::send`].
+impl<T> ReplySender<T> for SyncSender<T> {
+    fn send_reply(&self, reply: T) {
+        let _ = self.send(reply);
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+impl<T> ReplySender<T> for crossbeam_channel::Sender<T> {
+    fn send_reply(&self, reply: T) {
+        let _ = self.send(reply);
+    }
+}
+
+#[cfg(feature = "flume")]
+impl<T> ReplySender<T> for flume::Sender<T> {
+    fn send_reply(&self, reply: T) {
+        let _ = self.send(reply);
+    }
+}
+
+type Job = Box<dyn FnOnce()>;
+
+/// Marks the queue as no longer showing a dialog when dropped, even if the job panicked.
+struct ShowingGuard<'a>(&'a Cell<bool>);
+
+impl Drop for ShowingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// A queue that shows dialogs strictly one at a time, in the order they were pushed.
+///
+/// When several independent parts of an application want to ask the user something, showing
+/// their dialogs as they come interleaves badly, and can show a dialog while another one is
+/// still open. Pushing them into a shared `DialogQueue` instead makes them wait for their turn.
+/// Replies are delivered to a callback given to [`DialogQueue::push`], through the receiver
+/// returned by [`DialogQueue::push_with_receiver`], or through a channel of the application's
+/// own with [`DialogQueue::push_with_sender`].
+///
+/// A `DialogQueue` is a cheap handle: clones refer to the same queue, so each subsystem can keep
+/// its own. It is not `Send`, as dialogs must stay on the thread that initialized NvDialog.
+///
+/// # Examples
+/// ```no_run
+/// use nvdialog_rs::{DialogQueue, QuestionDialog, QuestionDialogButtons};
+///
+/// nvdialog_rs::init().expect("Failed to initialize NvDialog");
+/// let queue = DialogQueue::new();
+/// let ask = |title: &'static str, question: &'static str| {
+///     move || QuestionDialog::new(title, question, QuestionDialogButtons::YesNo).get_reply()
+/// };
+/// queue.push(ask("Sync", "Upload local changes?"), |reply| println!("Sync: {reply:?}"));
+/// let updates = queue.push_with_receiver(ask("Updates", "Install updates now?"));
+///
+/// queue.run();
+/// println!("Updates: {:?}", updates.recv());
+/// ```
+#[derive(Clone, Default)]
+pub struct DialogQueue {
+    pending: Rc<RefCell<VecDeque<Job>>>,
+    showing: Rc<Cell<bool>>,
+}
+
+impl DialogQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues `show`, which should create and show a dialog and return its result. `on_reply` is
+    /// called with that result once the dialog is closed, and may push more dialogs.
+    pub fn push<T, F, C>(&self, show: F, on_reply: C)
+    where
+        F: FnOnce() -> T + 'static,
+        C: FnOnce(T) + 'static,
+    {
+        self.pending
+            .borrow_mut()
+            .push_back(Box::new(move || on_reply(show())));
+    }
+
+    /// Queues `show` like [`DialogQueue::push`], sending its result to the returned receiver.
+    pub fn push_with_receiver<T, F>(&self, show: F) -> Receiver<T>
+    where
+        T: 'static,
+        F: FnOnce() -> T + 'static,
+    {
+        let (sender, receiver) = channel();
+        self.push_with_sender(show, sender);
+        receiver
+    }
+
+    /// Queues `show` like [`DialogQueue::push`], sending its result through `sender`, which can
+    /// be any [`ReplySender`].
+    pub fn push_with_sender<T, F, S>(&self, show: F, sender: S)
+    where
+        T: 'static,
+        F: FnOnce() -> T + 'static,
+        S: ReplySender<T> + 'static,
+    {
+        self.push(show, move |reply| sender.send_reply(reply));
+    }
+
+    /// Shows the next queued dialog and delivers its reply. Returns `false` if the queue is empty,
+    /// or if a dialog of this queue is already being shown, for example when called from one of
+    /// the callbacks.
+    pub fn run_next(&self) -> bool {
+        if self.showing.get() {
+            return false;
+        }
+        /* Popped before running, so the job can push more dialogs into the queue. */
+        let Some(job) = self.pending.borrow_mut().pop_front() else {
+            return false;
+        };
+        self.showing.set(true);
+        let _showing = ShowingGuard(&self.showing);
+        job();
+        true
+    }
+
+    /// Shows every queued dialog, including the ones pushed while running, until the queue is
+    /// empty.
+    pub fn run(&self) {
+        while self.run_next() {}
+    }
+
+    /// Returns the number of dialogs waiting to be shown.
+    pub fn len(&self) -> usize {
+        self.pending.borrow().len()
+    }
+
+    /// Returns `true` if no dialogs are waiting to be shown.
+    pub fn is_empty(&self) -> bool {
+        self.pending.borrow().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dialogs_run_in_the_order_they_were_pushed() {
+        let queue = DialogQueue::new();
+        let shown = Rc::new(RefCell::new(Vec::new()));
+        for index in 0..3 {
+            let shown = shown.clone();
+            queue.push(move || index, move |reply| shown.borrow_mut().push(reply));
+        }
+        let last = queue.push_with_receiver(|| 3);
+        assert_eq!(queue.len(), 4);
+
+        queue.run();
+        assert_eq!(*shown.borrow(), [0, 1, 2]);
+        assert_eq!(last.try_recv(), Ok(3));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn callbacks_can_push_more_dialogs() {
+        let queue = DialogQueue::new();
+        let shown = Rc::new(RefCell::new(Vec::new()));
+        let (inner_queue, inner_shown) = (queue.clone(), shown.clone());
+        queue.push(
+            || "first",
+            move |reply| {
+                inner_shown.borrow_mut().push(reply);
+                /* The dialog is still being handled, so nothing else is shown yet. */
+                assert!(!inner_queue.run_next());
+                let shown = inner_shown.clone();
+                inner_queue.push(|| "pushed", move |reply| shown.borrow_mut().push(reply));
+            },
+        );
+        let shown_second = shown.clone();
+        queue.push(
+            || "second",
+            move |reply| shown_second.borrow_mut().push(reply),
+        );
+
+        queue.run();
+        assert_eq!(*shown.borrow(), ["first", "second", "pushed"]);
+    }
+
+    #[test]
+    fn a_panicking_dialog_doesnt_block_the_queue() {
+        let queue = DialogQueue::new();
+        queue.push(|| panic!("the dialog failed"), |()| {});
+        let after = queue.push_with_receiver(|| "after");
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| queue.run()));
+        assert!(panicked.is_err());
+        assert!(queue.run_next());
+        assert_eq!(after.try_recv(), Ok("after"));
+    }
+}
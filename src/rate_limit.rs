@@ -0,0 +1,209 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::NotificationKind;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+struct State {
+    max_per_minute: Option<u32>,
+    sent: VecDeque<Instant>,
+    dropped: BTreeMap<NotificationKind, u64>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    max_per_minute: None,
+    sent: VecDeque::new(),
+    dropped: BTreeMap::new(),
+});
+
+/// Limits notifications to `max_per_minute` in any 60 seconds, or removes the limit with `None`
+/// (the default).
+///
+/// Once the limit is reached, [`Notification::send`](crate::Notification::send) drops further
+/// notifications and returns `Ok(())`, until older ones fall out of the last minute. The next
+/// notification sent afterwards mentions how many were dropped (`3 more errors were not
+/// shown.`), so an error loop can't bury the desktop in notifications while the user still
+/// learns about it.
+///
+/// The limit is shared by all threads. Removing it forgets the dropped notifications.
+///
+/// # Examples
+/// ```
+/// nvdialog_rs::set_notification_rate_limit(Some(5));
+/// ```
+pub fn set_notification_rate_limit(max_per_minute: Option<u32>) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.max_per_minute = max_per_minute;
+    if max_per_minute.is_none() {
+        state.sent.clear();
+        state.dropped.clear();
+    }
+}
+
+/// What to do with a notification about to be sent.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Admission {
+    Send,
+    /// Send it, with this summary of the notifications dropped before it appended.
+    SendWithSummary(String),
+    Drop,
+}
+
+/// Decides whether a notification of `kind` can be sent now, and records it.
+pub(crate) fn admit(kind: &NotificationKind) -> Admission {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.admit(kind, Instant::now())
+}
+
+impl State {
+    /// Decides whether a notification of `kind` can be sent at `now`, and records it.
+    fn admit(&mut self, kind: &NotificationKind, now: Instant) -> Admission {
+        let Some(max_per_minute) = self.max_per_minute else {
+            return Admission::Send;
+        };
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= WINDOW)
+        {
+            self.sent.pop_front();
+        }
+
+        if self.sent.len() >= max_per_minute as usize {
+            *self.dropped.entry(kind.clone()).or_default() += 1;
+            return Admission::Drop;
+        }
+        self.sent.push_back(now);
+        if self.dropped.is_empty() {
+            return Admission::Send;
+        }
+        Admission::SendWithSummary(summary(&std::mem::take(&mut self.dropped)))
+    }
+}
+
+/// Describes the dropped notifications, the most severe first: `2 more errors and 1 more
+/// notification were not shown.`
+fn summary(dropped: &BTreeMap<NotificationKind, u64>) -> String {
+    let parts: Vec<String> = dropped
+        .iter()
+        .rev()
+        .map(|(kind, count)| {
+            let noun = match kind {
+                NotificationKind::Simple => "notification",
+                NotificationKind::Warning => "warning",
+                NotificationKind::Error => "error",
+            };
+            let plural = if *count == 1 { "" } else { "s" };
+            format!("{count} more {noun}{plural}")
+        })
+        .collect();
+    let list = match parts.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+        None => String::new(),
+    };
+    let total: u64 = dropped.values().sum();
+    let verb = if total == 1 { "was" } else { "were" };
+    format!("{list} {verb} not shown.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use NotificationKind::{Error, Simple, Warning};
+
+    fn limited(max_per_minute: u32) -> State {
+        State {
+            max_per_minute: Some(max_per_minute),
+            sent: VecDeque::new(),
+            dropped: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn notifications_are_dropped_until_the_window_moves_on() {
+        let mut state = limited(2);
+        let start = Instant::now();
+        assert_eq!(state.admit(&Simple, start), Admission::Send);
+        assert_eq!(
+            state.admit(&Simple, start + Duration::from_secs(30)),
+            Admission::Send
+        );
+        assert_eq!(
+            state.admit(&Error, start + Duration::from_secs(59)),
+            Admission::Drop
+        );
+        assert_eq!(
+            state.admit(&Simple, start + WINDOW),
+            Admission::SendWithSummary(String::from("1 more error was not shown."))
+        );
+        assert_eq!(state.admit(&Simple, start + WINDOW), Admission::Drop);
+    }
+
+    #[test]
+    fn dropped_notifications_are_counted_per_kind_and_reported_once() {
+        let mut state = limited(1);
+        let start = Instant::now();
+        assert_eq!(state.admit(&Simple, start), Admission::Send);
+        for kind in [Error, Simple, Error] {
+            assert_eq!(state.admit(&kind, start), Admission::Drop);
+        }
+        assert_eq!(
+            state.admit(&Warning, start + WINDOW),
+            Admission::SendWithSummary(String::from(
+                "2 more errors and 1 more notification were not shown."
+            ))
+        );
+        assert_eq!(state.admit(&Warning, start + WINDOW * 2), Admission::Send);
+    }
+
+    #[test]
+    fn without_a_limit_everything_is_sent() {
+        let mut state = limited(0);
+        state.max_per_minute = None;
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(state.admit(&Error, now), Admission::Send);
+        }
+    }
+
+    #[test]
+    fn summary_lists_the_most_severe_first() {
+        let dropped = BTreeMap::from([(Simple, 3), (Warning, 1), (Error, 2)]);
+        assert_eq!(
+            summary(&dropped),
+            "2 more errors, 1 more warning and 3 more notifications were not shown."
+        );
+        assert_eq!(
+            summary(&BTreeMap::from([(Warning, 1)])),
+            "1 more warning was not shown."
+        );
+    }
+}
@@ -0,0 +1,131 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Registration of files with the platform's list of recently used documents.
+
+use std::path::Path;
+
+/// Called by [`crate::FileDialog::add_to_recent`] dialogs: adds `path` to the recent documents,
+/// ignoring failures since they don't affect the file the user chose.
+pub(crate) fn add(path: &Path) {
+    if crate::init::NATIVE_CODE {
+        add_native(path);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn add_native(path: &Path) {
+    use std::ffi::{c_char, c_int, c_void, CString};
+
+    crate::gtk_link::gtk_extern! {
+        fn gtk_recent_manager_get_default() -> *mut c_void;
+        fn gtk_recent_manager_add_item(manager: *mut c_void, uri: *const c_char) -> c_int;
+        fn g_filename_to_uri(
+            filename: *const c_char,
+            hostname: *const c_char,
+            error: *mut *mut c_void,
+        ) -> *mut c_char;
+        fn g_free(mem: *mut c_void);
+    }
+
+    let Ok(filename) = CString::new(crate::util::os_str_bytes(path.as_os_str()).into_owned())
+    else {
+        return;
+    };
+    unsafe {
+        /* Fails on relative paths, which file dialogs don't return. */
+        let uri = g_filename_to_uri(filename.as_ptr(), std::ptr::null(), std::ptr::null_mut());
+        if uri.is_null() {
+            return;
+        }
+        gtk_recent_manager_add_item(gtk_recent_manager_get_default(), uri);
+        g_free(uri as *mut c_void);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn add_native(path: &Path) {
+    use std::{ffi::c_void, os::windows::ffi::OsStrExt};
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHAddToRecentDocs(flags: u32, data: *const c_void);
+    }
+
+    const SHARD_PATHW: u32 = 0x0000_0003;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    unsafe { SHAddToRecentDocs(SHARD_PATHW, wide.as_ptr() as *const c_void) };
+}
+
+#[cfg(target_os = "macos")]
+fn add_native(path: &Path) {
+    use std::ffi::{c_char, c_void, CString};
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    let Some(path) = path.to_str().and_then(|path| CString::new(path).ok()) else {
+        return;
+    };
+    unsafe {
+        let send: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        let send_ptr: unsafe extern "C" fn(*mut c_void, *mut c_void, *const c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+        let string = send_ptr(
+            objc_getClass(c"NSString".as_ptr()),
+            sel_registerName(c"stringWithUTF8String:".as_ptr()),
+            path.as_ptr() as *const c_void,
+        );
+        let url = send_ptr(
+            objc_getClass(c"NSURL".as_ptr()),
+            sel_registerName(c"fileURLWithPath:".as_ptr()),
+            string,
+        );
+        let controller = send(
+            objc_getClass(c"NSDocumentController".as_ptr()),
+            sel_registerName(c"sharedDocumentController".as_ptr()),
+        );
+        if !url.is_null() && !controller.is_null() {
+            send_ptr(
+                controller,
+                sel_registerName(c"noteNewRecentDocumentURL:".as_ptr()),
+                url,
+            );
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn add_native(_path: &Path) {}
@@ -0,0 +1,199 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::Reply;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::{self, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+/// A single dialog as written to (and read from) a recording file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    kind: String,
+    title: String,
+    message: String,
+    #[serde(default)]
+    reply: Option<String>,
+}
+
+enum State {
+    Idle,
+    /// `empty` is `true` until the first entry is appended to `file`.
+    Recording {
+        file: File,
+        empty: bool,
+    },
+    /// `cursors` holds, for each kind of dialog, the index of the next entry to look at.
+    Replaying {
+        entries: Vec<Entry>,
+        cursors: HashMap<String, usize>,
+    },
+}
+
+thread_local! {
+    static STATE: RefCell<State> = const { RefCell::new(State::Idle) };
+}
+
+/// Starts recording every dialog shown in the current thread into the JSON file at `path`.
+///
+/// The file is created (or truncated) immediately and each dialog is appended to it as it is
+/// shown, so the file is a valid recording even if the application crashes halfway through. Each
+/// entry holds the kind of dialog, its title and message, and the reply the user gave, if the
+/// dialog returns one.
+///
+/// Recording stops when [`stop_recorder`] is called or when [`replay_from`] is used.
+///
+/// # Errors
+/// Returns the I/O error if the file can't be written.
+pub fn record_to<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(b"[\n]")?;
+    STATE.with(|state| *state.borrow_mut() = State::Recording { file, empty: true });
+    Ok(())
+}
+
+/// Loads a recording made with [`record_to`] and replays its replies in the current thread.
+///
+/// Question and file dialogs take their reply from the next recorded dialog of the same kind
+/// and return it immediately, without being shown. Other dialogs are unaffected. Once the
+/// recording runs out, dialogs behave normally again.
+///
+/// # Errors
+/// Returns the I/O error if the file can't be read, or an [`io::ErrorKind::InvalidData`] error
+/// if it isn't a valid recording.
+pub fn replay_from<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let data = std::fs::read(path)?;
+    let entries: Vec<Entry> = serde_json::from_slice(&data)?;
+    STATE.with(|state| {
+        *state.borrow_mut() = State::Replaying {
+            entries,
+            cursors: HashMap::new(),
+        }
+    });
+    Ok(())
+}
+
+/// Stops any recording or replay running in the current thread.
+pub fn stop_recorder() {
+    STATE.with(|state| *state.borrow_mut() = State::Idle);
+}
+
+pub(crate) fn record(kind: &str, title: &str, message: &str, reply: Option<String>) {
+    STATE.with(|state| {
+        if let State::Recording { file, empty } = &mut *state.borrow_mut() {
+            let entry = Entry {
+                kind: kind.to_owned(),
+                title: title.to_owned(),
+                message: message.to_owned(),
+                reply,
+            };
+            /* A failing recorder must never take the application down with it. */
+            if let Ok(entry) = serde_json::to_string(&entry) {
+                let separator = if *empty { "" } else { "," };
+                /* Overwrites the closing `\n]`, so only the new entry is written. */
+                let appended = file
+                    .seek(SeekFrom::End(-2))
+                    .and_then(|_| write!(file, "{separator}\n{entry}\n]"));
+                if appended.is_ok() {
+                    *empty = false;
+                }
+            }
+        }
+    });
+}
+
+fn next_recorded(kind: &str) -> Option<Option<String>> {
+    STATE.with(|state| {
+        if let State::Replaying { entries, cursors } = &mut *state.borrow_mut() {
+            /* Each kind has its own cursor, so entries of other kinds stay available. */
+            let cursor = cursors.entry(kind.to_owned()).or_default();
+            let index = *cursor
+                + entries[*cursor..]
+                    .iter()
+                    .position(|entry| entry.kind == kind)?;
+            *cursor = index + 1;
+            return Some(entries[index].reply.clone());
+        }
+        None
+    })
+}
+
+pub(crate) fn reply_name(reply: Reply) -> String {
+    match reply {
+        Reply::Accepted => "accepted",
+        Reply::Cancelled => "cancelled",
+        Reply::Rejected => "rejected",
+        Reply::Dismissed => "dismissed",
+    }
+    .to_owned()
+}
+
+pub(crate) fn replay_reply() -> Option<Reply> {
+    next_recorded("question").map(|reply| match reply.as_deref() {
+        Some("accepted") => Reply::Accepted,
+        Some("rejected") => Reply::Rejected,
+        Some("dismissed") => Reply::Dismissed,
+        _ => Reply::Cancelled,
+    })
+}
+
+pub(crate) fn replay_file() -> Option<Option<PathBuf>> {
+    next_recorded("file").map(|reply| reply.map(PathBuf::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replies_round_trip_through_a_recording() {
+        let path = std::env::temp_dir().join(format!("nvdialog-rs-{}.json", std::process::id()));
+        record_to(&path).unwrap();
+        record("question", "Delete?", "", Some(reply_name(Reply::Accepted)));
+        record("dialog_box", "Deleted", "", None);
+        record("file", "Open", "", Some(String::from("/tmp/picture.png")));
+        record("question", "Quit?", "", Some(reply_name(Reply::Rejected)));
+        stop_recorder();
+
+        let data = std::fs::read(&path).unwrap();
+        let entries: Vec<Entry> = serde_json::from_slice(&data).unwrap();
+        assert_eq!(entries.len(), 4);
+
+        replay_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        /* Asking for a file first doesn't skip the questions recorded before it. */
+        let picture = PathBuf::from("/tmp/picture.png");
+        assert_eq!(replay_file(), Some(Some(picture)));
+        assert_eq!(replay_reply(), Some(Reply::Accepted));
+        assert_eq!(replay_reply(), Some(Reply::Rejected));
+        assert_eq!(replay_reply(), None);
+        assert_eq!(replay_file(), None);
+        stop_recorder();
+    }
+}
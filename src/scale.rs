@@ -0,0 +1,133 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use std::sync::Mutex;
+
+static SCALE_OVERRIDE: Mutex<Option<f64>> = Mutex::new(None);
+
+/// Returns the scale factor of the primary monitor, e.g. `2.0` on a typical HiDPI display.
+///
+/// Image APIs taking a size (such as [`Image::from_svg`](crate::Image::from_svg)) treat it as a
+/// logical size and multiply it by this factor, so icons stay sharp on scaled displays. If the
+/// factor can't be queried, or NvDialog isn't initialized yet on Linux, `1.0` is returned.
+///
+/// # Platform notes
+/// - On Linux, the factor comes from GDK and is always an integer, since GTK 3 scales in whole steps.
+/// - On Windows, it is the system DPI divided by 96.
+/// - On macOS, it is the backing scale factor of the main screen.
+pub fn scale_factor() -> f64 {
+    if let Some(factor) = *SCALE_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) {
+        return factor;
+    }
+    if !crate::init::NATIVE_CODE {
+        return 1.0;
+    }
+    let factor = native_scale_factor();
+    if factor.is_finite() && factor > 0.0 {
+        factor
+    } else {
+        1.0
+    }
+}
+
+/// Overrides the value returned by [`scale_factor`], or goes back to querying the system with
+/// `None`. Useful for applications with their own scaling settings, and for testing.
+pub fn set_scale_factor(factor: Option<f64>) {
+    *SCALE_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) =
+        factor.filter(|factor| factor.is_finite() && *factor > 0.0);
+}
+
+/// Converts a logical size into pixels for the current [`scale_factor`].
+pub(crate) fn to_physical(size: u32) -> u32 {
+    (size as f64 * scale_factor()).round().min(u32::MAX as f64) as u32
+}
+
+#[cfg(target_os = "linux")]
+fn native_scale_factor() -> f64 {
+    use std::ffi::{c_int, c_void};
+
+    crate::gtk_link::gtk_extern! {
+        fn gdk_display_get_default() -> *mut c_void;
+        fn gdk_display_get_primary_monitor(display: *mut c_void) -> *mut c_void;
+        fn gdk_display_get_monitor(display: *mut c_void, monitor: c_int) -> *mut c_void;
+        fn gdk_monitor_get_scale_factor(monitor: *mut c_void) -> c_int;
+    }
+
+    unsafe {
+        let display = gdk_display_get_default();
+        if display.is_null() {
+            return 1.0;
+        }
+        /* Wayland has no primary monitor. */
+        let mut monitor = gdk_display_get_primary_monitor(display);
+        if monitor.is_null() {
+            monitor = gdk_display_get_monitor(display, 0);
+        }
+        if monitor.is_null() {
+            return 1.0;
+        }
+        gdk_monitor_get_scale_factor(monitor) as f64
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn native_scale_factor() -> f64 {
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetDpiForSystem() -> u32;
+    }
+
+    unsafe { GetDpiForSystem() as f64 / 96.0 }
+}
+
+#[cfg(target_os = "macos")]
+fn native_scale_factor() -> f64 {
+    use std::ffi::{c_char, c_void};
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    unsafe {
+        let send_id: unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        let send_f64: unsafe extern "C" fn(*mut c_void, *mut c_void) -> f64 =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+        let class = objc_getClass(c"NSScreen".as_ptr());
+        let screen = send_id(class, sel_registerName(c"mainScreen".as_ptr()));
+        if screen.is_null() {
+            return 1.0;
+        }
+        send_f64(screen, sel_registerName(c"backingScaleFactor".as_ptr()))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn native_scale_factor() -> f64 {
+    1.0
+}
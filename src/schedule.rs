@@ -0,0 +1,94 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::Notification;
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    rc::Rc,
+    time::Instant,
+};
+
+thread_local! {
+    static SCHEDULED: RefCell<Vec<(u64, Instant, Notification)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A notification waiting to be sent, returned by [`Notification::send_after`].
+///
+/// Dropping the handle doesn't cancel the notification.
+#[derive(Debug)]
+pub struct ScheduledNotification {
+    id: u64,
+    /* Notifications are scheduled per thread, so the handle must stay in that thread. */
+    _thread: PhantomData<Rc<()>>,
+}
+
+impl ScheduledNotification {
+    /// Cancels the notification, and gives it back if it hadn't been sent yet.
+    pub fn cancel_scheduled(self) -> Option<Notification> {
+        SCHEDULED.with(|scheduled| {
+            let mut scheduled = scheduled.borrow_mut();
+            let index = scheduled.iter().position(|(id, _, _)| *id == self.id)?;
+            Some(scheduled.swap_remove(index).2)
+        })
+    }
+
+    /// Returns `true` if the notification hasn't been sent or cancelled yet.
+    pub fn is_pending(&self) -> bool {
+        SCHEDULED.with(|scheduled| scheduled.borrow().iter().any(|(id, _, _)| *id == self.id))
+    }
+}
+
+pub(crate) fn schedule(notification: Notification, at: Instant) -> ScheduledNotification {
+    let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+    SCHEDULED.with(|scheduled| scheduled.borrow_mut().push((id, at, notification)));
+    ScheduledNotification {
+        id,
+        _thread: PhantomData,
+    }
+}
+
+/// Called by [`crate::pump_events`]: sends the notifications of this thread that are due.
+pub(crate) fn send_due() {
+    let now = Instant::now();
+    let due: Vec<_> = SCHEDULED.with(|scheduled| {
+        let mut scheduled = scheduled.borrow_mut();
+        let mut due = Vec::new();
+        let mut i = 0;
+        while i < scheduled.len() {
+            if scheduled[i].1 <= now {
+                due.push(scheduled.swap_remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        due
+    });
+    /* Sent outside of the borrow, in case the notification schedules another one. */
+    for (_, _, mut notification) in due {
+        /* Nobody is waiting for the result; a NotificationLog records failures. */
+        let _ = notification.send();
+    }
+}
@@ -0,0 +1,91 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::DialogType;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ALERT_SOUND: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables the system alert sound for warning and error dialogs.
+///
+/// When enabled, showing a [`DialogBox`](crate::DialogBox) of type [`DialogType::Warning`] or
+/// [`DialogType::Error`] plays the system alert sound right before the dialog appears. Simple
+/// dialogs never play a sound. The setting is global and defaults to disabled; individual dialogs
+/// can override it with [`DialogBox::set_alert_sound`](crate::DialogBox::set_alert_sound).
+///
+/// # Platform notes
+/// - On Linux, the sound is played through `gdk_display_beep`, so it follows the desktop's sound
+///   theme and event sound settings.
+/// - On macOS, `NSBeep` is used.
+/// - On Windows, `MessageBox` already plays the matching system sound for warning and error
+///   dialogs. This setting can't silence it, and enabling it doesn't play a second one.
+pub fn set_alert_sound(enabled: bool) {
+    ALERT_SOUND.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether the alert sound is globally enabled. See [`set_alert_sound`].
+pub fn alert_sound_enabled() -> bool {
+    ALERT_SOUND.load(Ordering::Relaxed)
+}
+
+/// Plays the alert sound for a dialog of type `dialog_type`, if it should have one.
+/// `dialog_override` is the dialog's own setting, taking priority over the global one.
+pub(crate) fn play_alert(dialog_type: DialogType, dialog_override: Option<bool>) {
+    if dialog_type == DialogType::Simple || !dialog_override.unwrap_or_else(alert_sound_enabled) {
+        return;
+    }
+    system_beep();
+}
+
+#[cfg(target_os = "linux")]
+fn system_beep() {
+    use std::ffi::c_void;
+
+    crate::gtk_link::gtk_extern! {
+        fn gdk_display_get_default() -> *mut c_void;
+        fn gdk_display_beep(display: *mut c_void);
+    }
+
+    unsafe {
+        let display = gdk_display_get_default();
+        if !display.is_null() {
+            gdk_display_beep(display);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_beep() {
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        fn NSBeep();
+    }
+
+    unsafe { NSBeep() }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn system_beep() {
+    /* MessageBox already plays the system sound on Windows. */
+}
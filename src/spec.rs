@@ -0,0 +1,208 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+use crate::{
+    DialogBox, DialogType, Error, FileDialog, FileDialogType, QuestionDialog,
+    QuestionDialogButtons, Reply,
+};
+use std::path::PathBuf;
+
+fn default_dialog_type() -> DialogType {
+    DialogType::Simple
+}
+
+fn default_buttons() -> QuestionDialogButtons {
+    QuestionDialogButtons::YesNo
+}
+
+/// A serializable description of a [`DialogBox`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageSpec {
+    /// The title of the dialog box.
+    pub title: String,
+    /// The message of the dialog box.
+    pub message: String,
+    /// The type of the dialog box. Defaults to [`DialogType::Simple`].
+    #[cfg_attr(feature = "serde", serde(default = "default_dialog_type"))]
+    pub kind: DialogType,
+    /// The text of the accept button, if it should be changed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub accept_label: Option<String>,
+}
+
+/// A serializable description of a [`QuestionDialog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuestionSpec {
+    /// The title of the dialog.
+    pub title: String,
+    /// The question asked.
+    pub message: String,
+    /// The buttons of the dialog. Defaults to [`QuestionDialogButtons::YesNo`].
+    #[cfg_attr(feature = "serde", serde(default = "default_buttons"))]
+    pub buttons: QuestionDialogButtons,
+}
+
+/// A serializable description of a [`FileDialog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileDialogSpec {
+    /// The title of the file dialog.
+    pub title: String,
+    /// Whether a file is opened or saved.
+    pub mode: FileDialogType,
+    /// The file extensions to filter by, without the leading dot. Empty means no filter.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extensions: Vec<String>,
+}
+
+/// Any of the dialog descriptions, tagged by a `"type"` field when serialized with the `serde`
+/// feature.
+///
+/// # Examples
+/// A question loaded from JSON:
+/// ```
+/// # #[cfg(all(feature = "serde", feature = "recorder"))] {
+/// use nvdialog_rs::DialogSpec;
+///
+/// let spec: DialogSpec = serde_json::from_str(r#"{
+///     "type": "question",
+///     "title": "Update available",
+///     "message": "Install the update now?",
+///     "buttons": "yes_no"
+/// }"#).unwrap();
+/// let reply = nvdialog_rs::show_from_spec(&spec).unwrap();
+/// println!("{}", serde_json::to_string(&reply).unwrap());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum DialogSpec {
+    /// A dialog box.
+    Message(MessageSpec),
+    /// A question dialog.
+    Question(QuestionSpec),
+    /// A file dialog.
+    File(FileDialogSpec),
+}
+
+/// The outcome of [`show_from_spec`], which can be serialized back with the `serde` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
+pub enum SpecReply {
+    /// A dialog box was shown. Dialog boxes don't return anything.
+    Shown,
+    /// The reply to a question dialog.
+    Reply(Reply),
+    /// The file chosen in a file dialog, if any.
+    File(Option<PathBuf>),
+}
+
+impl From<MessageSpec> for DialogSpec {
+    fn from(spec: MessageSpec) -> Self {
+        Self::Message(spec)
+    }
+}
+
+impl From<QuestionSpec> for DialogSpec {
+    fn from(spec: QuestionSpec) -> Self {
+        Self::Question(spec)
+    }
+}
+
+impl From<FileDialogSpec> for DialogSpec {
+    fn from(spec: FileDialogSpec) -> Self {
+        Self::File(spec)
+    }
+}
+
+impl TryFrom<DialogSpec> for MessageSpec {
+    type Error = DialogSpec;
+
+    fn try_from(spec: DialogSpec) -> Result<Self, DialogSpec> {
+        match spec {
+            DialogSpec::Message(spec) => Ok(spec),
+            other => Err(other),
+        }
+    }
+}
+
+impl TryFrom<DialogSpec> for QuestionSpec {
+    type Error = DialogSpec;
+
+    fn try_from(spec: DialogSpec) -> Result<Self, DialogSpec> {
+        match spec {
+            DialogSpec::Question(spec) => Ok(spec),
+            other => Err(other),
+        }
+    }
+}
+
+impl TryFrom<DialogSpec> for FileDialogSpec {
+    type Error = DialogSpec;
+
+    fn try_from(spec: DialogSpec) -> Result<Self, DialogSpec> {
+        match spec {
+            DialogSpec::File(spec) => Ok(spec),
+            other => Err(other),
+        }
+    }
+}
+
+/// Creates the dialog described by `spec`, shows it and returns its outcome.
+///
+/// # Errors
+/// Returns the error of the dialog's constructor, if it failed.
+pub fn show_from_spec(spec: &DialogSpec) -> Result<SpecReply, Error> {
+    match spec {
+        DialogSpec::Message(spec) => {
+            let mut dialog = DialogBox::new(spec.title.as_str(), spec.message.as_str(), spec.kind)?;
+            if let Some(label) = &spec.accept_label {
+                dialog.set_accept_label(label)?;
+            }
+            dialog.show();
+            Ok(SpecReply::Shown)
+        }
+        DialogSpec::Question(spec) => {
+            let mut dialog = QuestionDialog::new(
+                spec.title.as_str(),
+                spec.message.as_str(),
+                spec.buttons.clone(),
+            );
+            Ok(SpecReply::Reply(dialog.get_reply()))
+        }
+        DialogSpec::File(spec) => {
+            let extensions =
+                (!spec.extensions.is_empty()).then(|| spec.extensions.iter().map(String::as_str));
+            let mut dialog = FileDialog::new(spec.title.as_str(), spec.mode, extensions);
+            Ok(SpecReply::File(dialog.retrieve_filename()))
+        }
+    }
+}
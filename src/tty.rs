@@ -0,0 +1,96 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! The [`Backend::Tty`](crate::Backend::Tty) fallback, which asks questions on the terminal.
+//!
+//! Everything is written to standard error, so the application's standard output stays clean.
+
+use crate::{DialogType, FileDialogType, QuestionDialogButtons, Reply};
+use std::{
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+};
+
+pub(crate) fn available() -> bool {
+    io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Prints `text` and reads a line of input, returning `None` on end of file.
+fn prompt(text: &str) -> Option<String> {
+    eprint!("{text}");
+    let _ = io::stderr().flush();
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_owned()),
+    }
+}
+
+pub(crate) fn message(title: &str, msg: &str, dialog_type: DialogType) {
+    let prefix = match dialog_type {
+        DialogType::Simple => "",
+        DialogType::Warning => "warning: ",
+        DialogType::Error => "error: ",
+    };
+    eprintln!("{prefix}{title}: {msg}");
+}
+
+pub(crate) fn question(title: &str, msg: &str, buttons: &QuestionDialogButtons) -> Reply {
+    let choices = match buttons {
+        QuestionDialogButtons::Yes => {
+            message(title, msg, DialogType::Simple);
+            return Reply::Accepted;
+        }
+        QuestionDialogButtons::YesNo => "[y/n]",
+        QuestionDialogButtons::YesNoCancel => "[y/n/c]",
+    };
+    loop {
+        let Some(answer) = prompt(&format!("{title}: {msg} {choices} ")) else {
+            /* Closing the input is the terminal's equivalent of closing the window. */
+            return Reply::Dismissed;
+        };
+        match answer.to_lowercase().as_str() {
+            "y" | "yes" => return Reply::Accepted,
+            "n" | "no" => return Reply::Rejected,
+            "c" | "cancel" if *buttons == QuestionDialogButtons::YesNoCancel => {
+                return Reply::Cancelled
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn file(
+    title: &str,
+    dialog_type: FileDialogType,
+    extensions: &[String],
+) -> Option<PathBuf> {
+    let hint = match (dialog_type, extensions.is_empty()) {
+        (FileDialogType::OpenFile, false) => format!(" ({})", extensions.join(", ")),
+        _ => String::new(),
+    };
+    prompt(&format!("{title}{hint}, leave empty to cancel: "))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
@@ -25,6 +25,13 @@
 #![macro_use]
 #![allow(temporary_cstring_as_ptr)]
 
+use crate::Error;
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    ffi::{c_char, OsStr, OsString},
+};
+
 #[macro_export]
 macro_rules! c_string {
     ($rstr:expr) => {
@@ -32,6 +39,210 @@ macro_rules! c_string {
     };
 }
 
+/// Fallible version of [`c_string!`], evaluating to a `Result<CString, Error>` instead of
+/// panicking when the string contains a null byte.
+///
+/// # Examples
+/// ```
+/// use nvdialog_rs::try_c_string;
+///
+/// assert!(try_c_string!("Hello").is_ok());
+/// assert!(try_c_string!("Hello\0world").is_err());
+/// ```
+#[macro_export]
+macro_rules! try_c_string {
+    ($rstr:expr) => {
+        std::ffi::CString::new($rstr).map_err(|_| {
+            $crate::Error::ParametersError.context("String contains a null byte")
+        })
+    };
+}
+
+thread_local! {
+    static SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Converts `strings` into null-terminated C strings and calls `f` with pointers to them.
+///
+/// Unlike [`c_string!`], the strings are copied into a scratch buffer that is reused by every
+/// call in the same thread, so converting the title and message of a dialog doesn't allocate
+/// once the buffer has grown large enough. The pointers are only valid for the duration of `f`
+/// and must not be stored by the C side. If `f` calls this function again, the nested call uses
+/// a temporary buffer of its own instead.
+///
+/// # Errors
+/// Returns [`Error::ParametersError`] (with context) if any of the strings contains a null byte,
+/// without calling `f`.
+pub(crate) fn with_c_strings<R, const N: usize>(
+    strings: [&str; N],
+    f: impl FnOnce([*const c_char; N]) -> R,
+) -> Result<R, Error> {
+    with_scratch(|buffer| {
+        for string in strings {
+            if string.as_bytes().contains(&0) {
+                return Err(Error::ParametersError.context("String contains a null byte"));
+            }
+        }
+        Ok(fill_and_call(buffer, strings, f))
+    })
+}
+
+/// Like [`with_c_strings`], but for APIs that can't report errors: strings containing a null
+/// byte are cut short at it, which is exactly what the C side would read from them anyway.
+pub(crate) fn with_truncated_c_strings<R, const N: usize>(
+    strings: [&str; N],
+    f: impl FnOnce([*const c_char; N]) -> R,
+) -> R {
+    let strings = strings.map(|string| string.split('\0').next().unwrap_or_default());
+    with_scratch(|buffer| fill_and_call(buffer, strings, f))
+}
+
+/// Frees the scratch buffer used by [`with_c_strings`] in the current thread.
+pub(crate) fn release_scratch_buffer() {
+    SCRATCH.with(|scratch| {
+        if let Ok(mut buffer) = scratch.try_borrow_mut() {
+            *buffer = Vec::new();
+        }
+    });
+}
+
+fn with_scratch<R>(f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+    SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+        Ok(mut buffer) => f(&mut buffer),
+        Err(_) => f(&mut Vec::new()),
+    })
+}
+
+/// Copies the (null-free) `strings` into `buffer` and calls `f` with pointers to them. Strings
+/// that were [interned](crate::intern) are passed as-is instead of being copied.
+fn fill_and_call<R, const N: usize>(
+    buffer: &mut Vec<u8>,
+    strings: [&str; N],
+    f: impl FnOnce([*const c_char; N]) -> R,
+) -> R {
+    buffer.clear();
+    let mut pointers = [std::ptr::null::<c_char>(); N];
+    let mut offsets = [None; N];
+    for ((pointer, offset), string) in pointers.iter_mut().zip(&mut offsets).zip(strings) {
+        match crate::intern::lookup(string) {
+            Some(interned) => *pointer = interned,
+            None => {
+                *offset = Some(buffer.len());
+                buffer.extend_from_slice(string.as_bytes());
+                buffer.push(0);
+            }
+        }
+    }
+
+    /* Pointers are only taken once the buffer is done growing. */
+    let base = buffer.as_ptr() as *const c_char;
+    for (pointer, offset) in pointers.iter_mut().zip(offsets) {
+        if let Some(offset) = offset {
+            *pointer = unsafe { base.add(offset) };
+        }
+    }
+    f(pointers)
+}
+
+/// Returns the bytes NvDialog should receive for `s`. On Unix, where paths and other OS strings
+/// are arbitrary bytes, they are passed through unchanged; elsewhere `s` is converted to UTF-8,
+/// replacing anything that isn't valid Unicode with `U+FFFD`.
+pub(crate) fn os_str_bytes(s: &OsStr) -> Cow<'_, [u8]> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Cow::Borrowed(s.as_bytes())
+    }
+    #[cfg(not(unix))]
+    match s.to_string_lossy() {
+        Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+        Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+    }
+}
+
+/// The reverse of [`os_str_bytes`]: builds an `OsString` from bytes returned by NvDialog.
+pub(crate) fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        OsString::from_vec(bytes.to_vec())
+    }
+    #[cfg(not(unix))]
+    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Converts a button label using this crate's mnemonic syntax into the platform's own.
+///
+/// An underscore marks the next character as the mnemonic (`_Save` gives Alt+S) and two
+/// underscores stand for a literal one, which is already what GTK expects. Windows uses `&`
+/// instead, with `&&` for a literal ampersand, and macOS has no mnemonics, so the markers are
+/// removed there.
+pub(crate) fn native_mnemonic(label: &str) -> Cow<'_, str> {
+    if cfg!(not(any(target_os = "windows", target_os = "macos")))
+        || !label.contains(['_', '&'])
+    {
+        return Cow::Borrowed(label);
+    }
+
+    let mut native = String::with_capacity(label.len() + 1);
+    let mut chars = label.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '_' if chars.peek() == Some(&'_') => {
+                chars.next();
+                native.push('_');
+            }
+            '_' if cfg!(target_os = "windows") => native.push('&'),
+            '_' => {}
+            '&' if cfg!(target_os = "windows") => native.push_str("&&"),
+            c => native.push(c),
+        }
+    }
+    Cow::Owned(native)
+}
+
+/// Frees a native object created by NvDialog, doing nothing if `raw` is `NULL`.
+///
+/// Dialogs that failed to be constructed, or that were never built (see
+/// [`AboutDialog::build`](crate::AboutDialog::build)), keep a `NULL` pointer around, so every
+/// `Drop` implementation goes through this function instead of calling `nvd_free_object` directly.
+///
+/// # Safety
+/// `raw` must be `NULL` or an object returned by NvDialog that hasn't been freed yet.
+pub(crate) unsafe fn free_raw<T>(raw: *mut T) {
+    if !raw.is_null() {
+        crate::ffi_check::release(raw);
+        nvdialog_sys::ffi::nvd_free_object(raw as *mut std::ffi::c_void);
+    }
+}
+
+/// Runs `f` with `owner` set as NvDialog's parent window, restoring the previous parent after.
+///
+/// NvDialog only has a global parent window, so per-dialog owners (see
+/// [`DialogBox::set_owner_hwnd`](crate::DialogBox::set_owner_hwnd)) are swapped in around the
+/// call that actually displays the dialog. A `NULL` owner leaves the global parent untouched.
+pub(crate) fn with_owner<R>(owner: *mut std::ffi::c_void, f: impl FnOnce() -> R) -> R {
+    #[cfg(target_os = "windows")]
+    if !owner.is_null() {
+        use nvdialog_sys::ffi::{nvd_delete_parent, nvd_get_parent, nvd_set_parent};
+
+        let previous = unsafe { nvd_get_parent() };
+        unsafe { nvd_set_parent(owner) };
+        let result = f();
+        unsafe {
+            if previous.is_null() {
+                nvd_delete_parent();
+            } else {
+                nvd_set_parent(previous);
+            }
+        }
+        return result;
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = owner;
+    f()
+}
+
 /// Computes the length of the null-terminated string pointed to by `s`.
 ///
 /// This function scans the input string `s` and returns the number of non-null bytes
@@ -84,3 +295,75 @@ pub(crate) fn strlen(s: *const u8) -> usize {
     }
     len
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alloc_count::allocations;
+    use std::ffi::CStr;
+
+    #[test]
+    fn with_c_strings_does_not_allocate_once_warm() {
+        let convert = || with_c_strings(["Unsaved changes", "Save first?"], |[t, m]| (t, m));
+        convert().unwrap();
+        assert_eq!(allocations(convert), 0);
+    }
+
+    #[test]
+    fn with_truncated_c_strings_does_not_allocate_once_warm() {
+        let convert = || with_truncated_c_strings(["Title", "Message\0hidden"], |[t, m]| (t, m));
+        convert();
+        assert_eq!(allocations(convert), 0);
+    }
+
+    #[test]
+    fn with_c_strings_passes_null_terminated_copies() {
+        let read = |pointer| unsafe { CStr::from_ptr(pointer) }.to_str().unwrap();
+        let strings = with_c_strings(["", "Title", "Grüße"], |pointers| {
+            pointers.map(read).map(String::from)
+        });
+        assert_eq!(strings.unwrap(), ["", "Title", "Grüße"]);
+    }
+
+    #[test]
+    fn with_c_strings_rejects_null_bytes_without_calling_f() {
+        let mut called = false;
+        assert!(with_c_strings(["Title", "Mess\0age"], |_| called = true).is_err());
+        assert!(!called);
+    }
+
+    #[test]
+    fn with_truncated_c_strings_cuts_at_null_bytes() {
+        let message = with_truncated_c_strings(["Mess\0age"], |[message]| {
+            String::from(unsafe { CStr::from_ptr(message) }.to_str().unwrap())
+        });
+        assert_eq!(message, "Mess");
+    }
+
+    #[test]
+    fn native_mnemonic_uses_the_platform_syntax() {
+        let expected = if cfg!(target_os = "windows") {
+            ["&Save", "Save_As", "Fish && Chips", "Plain"]
+        } else if cfg!(target_os = "macos") {
+            ["Save", "Save_As", "Fish & Chips", "Plain"]
+        } else {
+            ["_Save", "Save__As", "Fish & Chips", "Plain"]
+        };
+        let labels = ["_Save", "Save__As", "Fish & Chips", "Plain"];
+        for (label, expected) in labels.into_iter().zip(expected) {
+            assert_eq!(native_mnemonic(label), expected);
+        }
+    }
+
+    #[test]
+    fn nested_conversions_use_their_own_buffer() {
+        with_c_strings(["outer"], |[outer]| {
+            with_c_strings(["inner"], |[inner]| unsafe {
+                assert_eq!(CStr::from_ptr(inner).to_str(), Ok("inner"));
+            })
+            .unwrap();
+            assert_eq!(unsafe { CStr::from_ptr(outer) }.to_str(), Ok("outer"));
+        })
+        .unwrap();
+    }
+}
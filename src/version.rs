@@ -0,0 +1,101 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Checks that the libnvdialog the crate runs against matches the headers it was built with.
+
+use crate::Error;
+use nvdialog_sys::ffi::{nvd_get_version, NvdVersion};
+use std::fmt;
+
+/// A libnvdialog version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    /// Returns `true` if a library of version `self` can be used by code built against the
+    /// headers of version `built`: the same major version (the same minor version too, before
+    /// 1.0), and not older.
+    pub fn is_compatible_with(self, built: Version) -> bool {
+        let same_series = if built.major == 0 {
+            self.major == 0 && self.minor == built.minor
+        } else {
+            self.major == built.major
+        };
+        same_series && self >= built
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The version of the libnvdialog headers the crate was built against.
+pub const NATIVE_VERSION: Version = Version {
+    major: nvdialog_sys::NATIVE_VERSION.0,
+    minor: nvdialog_sys::NATIVE_VERSION.1,
+    patch: nvdialog_sys::NATIVE_VERSION.2,
+};
+
+/// Returns the version of the libnvdialog the program runs against, which can differ from
+/// [`NATIVE_VERSION`] when NvDialog is linked dynamically. NvDialog releases that can't report
+/// their version are assumed to be [`NATIVE_VERSION`].
+///
+/// # FFI
+/// Corresponds to `nvd_get_version`.
+pub fn runtime_version() -> Version {
+    if !crate::init::NATIVE_CODE || !nvdialog_sys::available::GET_VERSION {
+        return NATIVE_VERSION;
+    }
+    let mut version = NvdVersion {
+        major: 0,
+        minor: 0,
+        patch: 0,
+    };
+    unsafe { nvd_get_version(&mut version) };
+    /* The fields are C shorts, which are never negative for a real version. */
+    Version {
+        major: version.major.max(0) as u32,
+        minor: version.minor.max(0) as u32,
+        patch: version.patch.max(0) as u32,
+    }
+}
+
+/// Called by [`crate::init`]: fails if the runtime library isn't compatible with the headers.
+pub(crate) fn check() -> Result<(), Error> {
+    let runtime = runtime_version();
+    if runtime.is_compatible_with(NATIVE_VERSION) {
+        Ok(())
+    } else {
+        Err(Error::VersionMismatch {
+            built: NATIVE_VERSION,
+            runtime,
+        })
+    }
+}
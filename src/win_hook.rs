@@ -0,0 +1,111 @@
+/*
+ *  The MIT License (MIT)
+ *
+ *  Copyright (c) 2022-2024 Aggelos Tselios
+ *
+ *  Permission is hereby granted, free of charge, to any person obtaining a copy
+ *  of this software and associated documentation files (the "Software"), to
+ * deal in the Software without restriction, including without limitation the
+ * rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+ * sell copies of the Software, and to permit persons to whom the Software is
+ *  furnished to do so, subject to the following conditions:
+ *
+ *  The above copyright notice and this permission notice shall be included in
+ * all copies or substantial portions of the Software.
+ *
+ *  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ *  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ *  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ *  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ *  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+ * FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS
+ * IN THE SOFTWARE.
+ */
+
+//! Finds the window of a dialog NvDialog shows on Windows.
+//!
+//! NvDialog only creates the windows of its dialogs once they are shown, and doesn't hand them
+//! out, so a thread-local `WH_CBT` hook catches the first dialog box activated on the thread while
+//! the dialog is shown.
+
+use std::{cell::RefCell, ffi::c_void};
+
+pub(crate) type Hwnd = *mut c_void;
+
+type Callback = Box<dyn FnOnce(Hwnd)>;
+
+#[link(name = "user32")]
+extern "system" {
+    fn SetWindowsHookExW(
+        id: i32,
+        hook: unsafe extern "system" fn(i32, usize, isize) -> isize,
+        module: *mut c_void,
+        thread_id: u32,
+    ) -> *mut c_void;
+    fn UnhookWindowsHookEx(hook: *mut c_void) -> i32;
+    fn CallNextHookEx(hook: *mut c_void, code: i32, wparam: usize, lparam: isize) -> isize;
+    fn GetClassNameW(hwnd: Hwnd, name: *mut u16, len: i32) -> i32;
+}
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetCurrentThreadId() -> u32;
+}
+
+const WH_CBT: i32 = 5;
+const HCBT_ACTIVATE: i32 = 5;
+
+thread_local! {
+    /* Callbacks waiting for the next dialog box of the thread, by the ID of their hook. */
+    static PENDING: RefCell<Vec<(usize, Callback)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns whether `hwnd` is a dialog box, which message boxes and file dialogs are.
+pub(crate) unsafe fn is_dialog_box(hwnd: Hwnd) -> bool {
+    let dialog_class: Vec<u16> = "#32770".encode_utf16().collect();
+    let mut class = [0u16; 16];
+    let len = GetClassNameW(hwnd, class.as_mut_ptr(), class.len() as i32);
+    class[..len.max(0) as usize] == dialog_class[..]
+}
+
+unsafe extern "system" fn hook(code: i32, wparam: usize, lparam: isize) -> isize {
+    if code == HCBT_ACTIVATE && is_dialog_box(wparam as Hwnd) {
+        /* Taken out first, as the callbacks may show dialogs themselves. */
+        let pending = PENDING.with(|pending| std::mem::take(&mut *pending.borrow_mut()));
+        for (_, callback) in pending {
+            callback(wparam as Hwnd);
+        }
+    }
+    CallNextHookEx(std::ptr::null_mut(), code, wparam, lparam)
+}
+
+/// Removes the hook, along with its callback if no dialog box was activated.
+struct Installed(*mut c_void);
+
+impl Drop for Installed {
+    fn drop(&mut self) {
+        let id = self.0 as usize;
+        PENDING.with(|pending| pending.borrow_mut().retain(|(hook, _)| *hook != id));
+        unsafe { UnhookWindowsHookEx(self.0) };
+    }
+}
+
+/// Runs `show`, which shows a dialog on the current thread, and calls `on_shown` with the window
+/// of the first dialog box activated on the thread meanwhile, which is the dialog's own.
+///
+/// `on_shown` runs from inside the hook, before the dialog box gets activated. It isn't called if
+/// the hook can't be installed or no dialog box is activated.
+pub(crate) fn on_dialog<T>(on_shown: impl FnOnce(Hwnd) + 'static, show: impl FnOnce() -> T) -> T {
+    let handle =
+        unsafe { SetWindowsHookExW(WH_CBT, hook, std::ptr::null_mut(), GetCurrentThreadId()) };
+    if handle.is_null() {
+        return show();
+    }
+    let _installed = Installed(handle);
+    PENDING.with(|pending| {
+        pending
+            .borrow_mut()
+            .push((handle as usize, Box::new(on_shown)))
+    });
+    show()
+}